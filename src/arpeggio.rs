@@ -0,0 +1,144 @@
+use std::ops::Range;
+
+use crate::util;
+use crate::Sieve;
+
+/// The order in which an Arpeggiator walks its pitches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    /// Ascend, returning to the lowest pitch after the highest.
+    Up,
+    /// Descend, returning to the highest pitch after the lowest.
+    Down,
+    /// Ascend then descend, without repeating the highest or lowest pitch.
+    UpDown,
+    /// Choose each pitch at random, reproducibly from the provided seed.
+    Random(u64),
+}
+
+/// A note produced by an Arpeggiator.
+///
+/// # Fields
+/// * `onset` - The onset, a member of the rhythm Sieve.
+/// * `pitch` - The pitch, a member of the pitch Sieve.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Note {
+    pub onset: i128,
+    pub pitch: i128,
+}
+
+/// A generator of notes that walks the members of a pitch Sieve, within a bounded range, in a Pattern, placing one pitch at each onset of a rhythm Sieve.
+///
+/// ```
+/// let pitch = xensieve::Sieve::new("12@0|12@4|12@7");
+/// let a = xensieve::Arpeggiator::new(&pitch, 60..72, xensieve::Pattern::UpDown);
+/// assert_eq!(a.pitches(), &[60, 64, 67]);
+/// let rhythm = xensieve::Sieve::new("3@0");
+/// let p: Vec<_> = a.notes(&rhythm, 0..15).iter().map(|n| n.pitch).collect();
+/// assert_eq!(p, vec![60, 64, 67, 64, 60]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Arpeggiator {
+    pitches: Vec<i128>,
+    pattern: Pattern,
+}
+
+impl Arpeggiator {
+    /// Construct an Arpeggiator from the members of the pitch Sieve within the range.
+    pub fn new(pitch: &Sieve, range: Range<i128>, pattern: Pattern) -> Self {
+        Self {
+            pitches: pitch.scan(range),
+            pattern,
+        }
+    }
+
+    /// Return the pitches, ascending, that this Arpeggiator walks.
+    pub fn pitches(&self) -> &[i128] {
+        &self.pitches
+    }
+
+    /// Return the index into the pitches of the step `k`, where there are `n` pitches.
+    fn index(&self, k: usize, n: usize, random: &mut util::Random) -> usize {
+        match self.pattern {
+            Pattern::Up => k % n,
+            Pattern::Down => n - 1 - k % n,
+            Pattern::UpDown if n < 2 => 0,
+            Pattern::UpDown => {
+                let k = k % (2 * n - 2);
+                if k < n {
+                    k
+                } else {
+                    2 * n - 2 - k
+                }
+            }
+            Pattern::Random(_) => random.below(n as u64) as usize,
+        }
+    }
+
+    /// Return a Note for each member of the rhythm Sieve within the range, taking pitches in the order of the Pattern. If there are no pitches, there are no Notes.
+    pub fn notes(&self, rhythm: &Sieve, range: Range<i128>) -> Vec<Note> {
+        let n = self.pitches.len();
+        if n == 0 {
+            return Vec::new();
+        }
+        let mut random = util::Random::new(match self.pattern {
+            Pattern::Random(seed) => seed,
+            _ => 0,
+        });
+        rhythm
+            .scan(range)
+            .into_iter()
+            .enumerate()
+            .map(|(k, onset)| Note {
+                onset,
+                pitch: self.pitches[self.index(k, n, &mut random)],
+            })
+            .collect()
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pitches(pattern: Pattern, steps: i128) -> Vec<i128> {
+        let a = Arpeggiator::new(&Sieve::new("5@0|5@2"), 0..10, pattern);
+        a.notes(&Sieve::new("1@0"), 0..steps)
+            .iter()
+            .map(|n| n.pitch)
+            .collect()
+    }
+
+    #[test]
+    fn test_arpeggiator_notes_a() {
+        assert_eq!(pitches(Pattern::Up, 6), vec![0, 2, 5, 7, 0, 2]);
+        assert_eq!(pitches(Pattern::Down, 6), vec![7, 5, 2, 0, 7, 5]);
+        assert_eq!(pitches(Pattern::UpDown, 8), vec![0, 2, 5, 7, 5, 2, 0, 2]);
+    }
+
+    #[test]
+    fn test_arpeggiator_notes_b() {
+        let p1 = pitches(Pattern::Random(3), 20);
+        assert_eq!(p1, pitches(Pattern::Random(3), 20));
+        assert!(p1.iter().all(|p| [0, 2, 5, 7].contains(p)));
+    }
+
+    #[test]
+    fn test_arpeggiator_notes_c() {
+        let a = Arpeggiator::new(&Sieve::new("5@0"), 0..5, Pattern::UpDown);
+        let n = a.notes(&Sieve::new("4@1"), 0..10);
+        assert_eq!(
+            n,
+            vec![
+                Note { onset: 1, pitch: 0 },
+                Note { onset: 5, pitch: 0 },
+                Note { onset: 9, pitch: 0 }
+            ]
+        );
+        let a = Arpeggiator::new(&Sieve::empty(), 0..5, Pattern::Up);
+        assert_eq!(a.notes(&Sieve::new("4@1"), 0..10), vec![]);
+    }
+}
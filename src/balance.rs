@@ -0,0 +1,114 @@
+use crate::util;
+use crate::Residual;
+
+/// Return the exact density of a union of Residuals: the fraction of each period that is contained.
+pub(crate) fn density(residuals: &[Residual]) -> f64 {
+    let period = residuals
+        .iter()
+        .filter(|r| r.modulus() != 0)
+        .try_fold(1, |p, r| util::lcm(p, r.modulus()))
+        .expect("period overflows");
+    let count = (0..period as i128)
+        .filter(|v| residuals.iter().any(|r| r.contains(*v)))
+        .count();
+    count as f64 / period as f64
+}
+
+/// Return the classes, of the non-zero moduli of `residuals`, that are not among `residuals`, as candidates for addition.
+pub(crate) fn candidates(residuals: &[Residual]) -> Vec<Residual> {
+    let mut moduli: Vec<u64> = residuals
+        .iter()
+        .map(|r| r.modulus())
+        .filter(|m| *m != 0)
+        .collect();
+    moduli.sort_unstable();
+    moduli.dedup();
+    moduli
+        .iter()
+        .flat_map(|m| (0..*m).map(move |s| Residual::new(*m, s)))
+        .filter(|r| !residuals.contains(r))
+        .collect()
+}
+
+/// Starting from `residuals`, greedily drop one of them, or add one of `candidates`, choosing at each step the change that brings the density of the union nearest to `target`, until no change brings it nearer.
+pub(crate) fn balance(
+    mut residuals: Vec<Residual>,
+    mut candidates: Vec<Residual>,
+    target: f64,
+) -> Vec<Residual> {
+    let mut error = (density(&residuals) - target).abs();
+    loop {
+        let mut best: Option<(f64, Vec<Residual>, Vec<Residual>)> = None;
+        for i in 0..residuals.len() {
+            let mut next = residuals.clone();
+            let dropped = next.remove(i);
+            let e = (density(&next) - target).abs();
+            if e < best.as_ref().map_or(error, |b| b.0) {
+                let mut c = candidates.clone();
+                c.push(dropped);
+                best = Some((e, next, c));
+            }
+        }
+        for i in 0..candidates.len() {
+            let mut next = residuals.clone();
+            let mut c = candidates.clone();
+            next.push(c.remove(i));
+            let e = (density(&next) - target).abs();
+            if e < best.as_ref().map_or(error, |b| b.0) {
+                best = Some((e, next, c));
+            }
+        }
+        match best {
+            Some((e, next, c)) => {
+                error = e;
+                residuals = next;
+                candidates = c;
+            }
+            None => return residuals,
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_density_a() {
+        assert_eq!(density(&[]), 0.0);
+        assert_eq!(density(&[Residual::new(4, 0)]), 0.25);
+        assert_eq!(
+            density(&[Residual::new(2, 0), Residual::new(3, 0)]),
+            4.0 / 6.0
+        );
+    }
+
+    #[test]
+    fn test_candidates_a() {
+        let c = candidates(&[
+            Residual::new(3, 1),
+            Residual::new(2, 0),
+            Residual::new(0, 0),
+        ]);
+        let c: Vec<String> = c.iter().map(|r| r.to_string()).collect();
+        assert_eq!(c, vec!["2@1", "3@0", "3@2"]);
+        assert_eq!(candidates(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_balance_a() {
+        let r = vec![
+            Residual::new(4, 1),
+            Residual::new(4, 2),
+            Residual::new(4, 3),
+        ];
+        let b = balance(r.clone(), vec![], 0.5);
+        assert_eq!(b.len(), 2);
+        let b = balance(r.clone(), vec![], 1.0);
+        assert_eq!(b, r);
+        let b = balance(r, vec![Residual::new(4, 0)], 1.0);
+        assert_eq!(b.len(), 4);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::Residual;
+use crate::SieveNode;
+
+/// The number of values tested together against each Residual by `Classes`.
+const BLOCK: usize = 64;
+
+/// The fewest values for which `Sieve::contains_batch_fill` evaluates a union of Residuals with `Classes` rather than the tree.
+pub(crate) const CLASSES_MIN: usize = 256;
+
+/// Write the membership of each value into `post`, evaluating the node one Residual at a time across all values rather than one value at a time through the tree. Each Residual is a tight loop of the same modulo test over every value, which the compiler can vectorize; operators then combine whole buffers.
+pub(crate) fn contains(node: &SieveNode, values: &[i128], post: &mut [bool]) {
+    match node {
+        SieveNode::Unit(residual) => {
+            let m = residual.modulus() as i128;
+            let s = residual.shift() as i128;
+            if m == 0 {
+                post.iter_mut().for_each(|p| *p = false);
+            } else {
+                for (p, v) in post.iter_mut().zip(values) {
+                    *p = v.rem_euclid(m) == s;
+                }
+            }
+        }
+        SieveNode::Intersection(lhs, rhs) => combine(lhs, rhs, values, post, |a, b| a & b),
+        SieveNode::Union(lhs, rhs) => combine(lhs, rhs, values, post, |a, b| a | b),
+        SieveNode::SymmetricDifference(lhs, rhs) => combine(lhs, rhs, values, post, |a, b| a ^ b),
+        SieveNode::Difference(lhs, rhs) => combine(lhs, rhs, values, post, |a, b| a & !b),
+        SieveNode::Inversion(part) => {
+            contains(part, values, post);
+            post.iter_mut().for_each(|p| *p = !*p);
+        }
+    }
+}
+
+fn combine(
+    lhs: &SieveNode,
+    rhs: &SieveNode,
+    values: &[i128],
+    post: &mut [bool],
+    op: impl Fn(bool, bool) -> bool,
+) {
+    contains(lhs, values, post);
+    let mut other = vec![false; values.len()];
+    contains(rhs, values, &mut other);
+    for (p, o) in post.iter_mut().zip(other) {
+        *p = op(*p, o);
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// The Residuals of a union stored as a structure of arrays: moduli and shifts in flat, parallel vectors. Values are tested in blocks, each block against every Residual in turn, such that the inner loop is the same test over contiguous values and contiguous accumulators, suited to wide integer SIMD; where every value and modulus fits in 64 bits, the test is made in `i64`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Classes {
+    moduli: Vec<u64>,
+    shifts: Vec<u64>,
+}
+
+impl Classes {
+    pub(crate) fn new(residuals: &[Residual]) -> Self {
+        let residuals = residuals.iter().filter(|r| r.modulus() > 0);
+        Self {
+            moduli: residuals.clone().map(|r| r.modulus()).collect(),
+            shifts: residuals.map(|r| r.shift()).collect(),
+        }
+    }
+
+    /// Write the membership of each value into `post`.
+    pub(crate) fn contains(&self, values: &[i128], post: &mut [bool]) {
+        let narrow = self.moduli.iter().all(|m| *m <= i64::MAX as u64)
+            && values
+                .iter()
+                .all(|v| *v >= i64::MIN as i128 && *v <= i64::MAX as i128);
+        for (block, out) in values.chunks(BLOCK).zip(post.chunks_mut(BLOCK)) {
+            out.iter_mut().for_each(|p| *p = false);
+            if narrow {
+                let mut lanes = [0i64; BLOCK];
+                for (l, v) in lanes.iter_mut().zip(block) {
+                    *l = *v as i64;
+                }
+                let lanes = &lanes[..block.len()];
+                for (m, s) in self.moduli.iter().zip(self.shifts.iter()) {
+                    let (m, s) = (*m as i64, *s as i64);
+                    for (p, v) in out.iter_mut().zip(lanes) {
+                        *p |= v.rem_euclid(m) == s;
+                    }
+                }
+            } else {
+                for (m, s) in self.moduli.iter().zip(self.shifts.iter()) {
+                    let (m, s) = (*m as i128, *s as i128);
+                    for (p, v) in out.iter_mut().zip(block) {
+                        *p |= v.rem_euclid(m) == s;
+                    }
+                }
+            }
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sieve;
+
+    #[test]
+    fn test_contains_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^7@3-0@0");
+        let values: Vec<i128> = (-100..100).chain([i128::MAX]).collect();
+        let mut post = vec![true; values.len()];
+        contains(&s.root, &values, &mut post);
+        for (v, p) in values.iter().zip(post) {
+            assert_eq!(p, s.contains(*v), "{v}");
+        }
+    }
+
+    #[test]
+    fn test_classes_a() {
+        for e in ["(5@0|4@2)|7@3|0@0", "4@1&6@0|9@2", "1@0"] {
+            let s = Sieve::new(e);
+            let classes = Classes::new(&s.root.progression_residuals().unwrap());
+            for values in [
+                (-300..300).collect::<Vec<i128>>(),
+                vec![i128::MIN / 2, -1, 0, i64::MAX as i128 + 1, i128::MAX],
+                vec![],
+            ] {
+                let mut post = vec![true; values.len()];
+                classes.contains(&values, &mut post);
+                for (v, p) in values.iter().zip(post) {
+                    assert_eq!(p, s.contains(*v), "{e} {v}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_classes_b() {
+        let classes = Classes::new(&[Residual::new(0, 0), Residual::new(u64::MAX, 3)]);
+        assert_eq!(classes.moduli, vec![u64::MAX]);
+        let mut post = vec![true; 3];
+        classes.contains(&[3, u64::MAX as i128 + 3, 4], &mut post);
+        assert_eq!(post, vec![true, true, false]);
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+
+use crate::Residual;
+use crate::SieveNode;
+
+/// The structure of a node, with each operand replaced by its interned identifier.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum NodeKey {
+    Unit(Residual),
+    Intersection(usize, usize),
+    Union(usize, usize),
+    SymmetricDifference(usize, usize),
+    Difference(usize, usize),
+    Inversion(usize),
+}
+
+/// A cache of the Boolean states of Sieve nodes over ranges, for use with `Sieve::reevaluate_with_cache`. Nodes are keyed by their structure, so after an edit to a Sieve, the states of all unchanged subtrees are reused. Each distinct structure is interned once as an identifier, built from the identifiers of its operands, so keys are neither cloned nor hashed as whole subtrees. The cache is unbounded: states are retained for every node and range evaluated until `clear` is called.
+#[derive(Clone, Debug, Default)]
+pub struct SegmentCache {
+    nodes: HashMap<NodeKey, usize>,
+    states: HashMap<(usize, i128, i128), Rc<Vec<bool>>>,
+    hits: u64,
+    misses: u64,
+}
+
+impl SegmentCache {
+    /// Construct an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the number of cached node states.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Return `true` if there are no cached node states.
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Return the number of node evaluations that were found in the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Return the number of node evaluations that were not found in the cache.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Remove all cached node states.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.states.clear();
+    }
+
+    /// Return the identifier of the node's structure, interning it and its operands, and recording the identifier of each node by address.
+    fn intern(&mut self, node: &SieveNode, ids: &mut HashMap<*const SieveNode, usize>) -> usize {
+        let key = match node {
+            SieveNode::Unit(residual) => NodeKey::Unit(*residual),
+            SieveNode::Intersection(lhs, rhs) => {
+                NodeKey::Intersection(self.intern(lhs, ids), self.intern(rhs, ids))
+            }
+            SieveNode::Union(lhs, rhs) => {
+                NodeKey::Union(self.intern(lhs, ids), self.intern(rhs, ids))
+            }
+            SieveNode::SymmetricDifference(lhs, rhs) => {
+                NodeKey::SymmetricDifference(self.intern(lhs, ids), self.intern(rhs, ids))
+            }
+            SieveNode::Difference(lhs, rhs) => {
+                NodeKey::Difference(self.intern(lhs, ids), self.intern(rhs, ids))
+            }
+            SieveNode::Inversion(part) => NodeKey::Inversion(self.intern(part, ids)),
+        };
+        let next = self.nodes.len();
+        let id = *self.nodes.entry(key).or_insert(next);
+        ids.insert(node as *const SieveNode, id);
+        id
+    }
+
+    /// Return the states of the node over the range, computing and caching them if necessary.
+    pub(crate) fn states(&mut self, node: &SieveNode, range: &Range<i128>) -> Rc<Vec<bool>> {
+        let mut ids = HashMap::new();
+        self.intern(node, &mut ids);
+        self.evaluate(node, range, &ids)
+    }
+
+    /// Return the states of the node over the range, keyed by the identifiers recorded by `intern`.
+    fn evaluate(
+        &mut self,
+        node: &SieveNode,
+        range: &Range<i128>,
+        ids: &HashMap<*const SieveNode, usize>,
+    ) -> Rc<Vec<bool>> {
+        let key = (ids[&(node as *const SieveNode)], range.start, range.end);
+        if let Some(states) = self.states.get(&key) {
+            self.hits += 1;
+            return Rc::clone(states);
+        }
+        self.misses += 1;
+        let states: Vec<bool> = match node {
+            SieveNode::Unit(residual) => range.clone().map(|v| residual.contains(v)).collect(),
+            SieveNode::Intersection(lhs, rhs) => {
+                let (a, b) = (
+                    self.evaluate(lhs, range, ids),
+                    self.evaluate(rhs, range, ids),
+                );
+                a.iter().zip(b.iter()).map(|(a, b)| *a && *b).collect()
+            }
+            SieveNode::Union(lhs, rhs) => {
+                let (a, b) = (
+                    self.evaluate(lhs, range, ids),
+                    self.evaluate(rhs, range, ids),
+                );
+                a.iter().zip(b.iter()).map(|(a, b)| *a || *b).collect()
+            }
+            SieveNode::SymmetricDifference(lhs, rhs) => {
+                let (a, b) = (
+                    self.evaluate(lhs, range, ids),
+                    self.evaluate(rhs, range, ids),
+                );
+                a.iter().zip(b.iter()).map(|(a, b)| *a ^ *b).collect()
+            }
+            SieveNode::Difference(lhs, rhs) => {
+                let (a, b) = (
+                    self.evaluate(lhs, range, ids),
+                    self.evaluate(rhs, range, ids),
+                );
+                a.iter().zip(b.iter()).map(|(a, b)| *a && !*b).collect()
+            }
+            SieveNode::Inversion(part) => self
+                .evaluate(part, range, ids)
+                .iter()
+                .map(|a| !*a)
+                .collect(),
+        };
+        let states = Rc::new(states);
+        self.states.insert(key, Rc::clone(&states));
+        states
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sieve;
+
+    #[test]
+    fn test_segment_cache_a() {
+        let mut cache = SegmentCache::new();
+        let s1 = Sieve::new("(5@0|4@2)&!30@10");
+        assert_eq!(
+            s1.reevaluate_with_cache(0..20, &mut cache),
+            vec![0, 2, 5, 6, 14, 15, 18]
+        );
+        assert_eq!(cache.len(), 6);
+        assert_eq!(cache.hits(), 0);
+        assert_eq!(cache.misses(), 6);
+
+        // an edit of the inverted Residual reuses the union
+        let s2 = Sieve::new("(5@0|4@2)&!30@14");
+        assert_eq!(
+            s2.reevaluate_with_cache(0..20, &mut cache),
+            vec![0, 2, 5, 6, 10, 15, 18]
+        );
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 9);
+    }
+
+    #[test]
+    fn test_segment_cache_b() {
+        let mut cache = SegmentCache::new();
+        let s1 = Sieve::new("3@0^!2@1");
+        s1.reevaluate_with_cache(0..10, &mut cache);
+        // a different range is not reused
+        assert_eq!(
+            s1.reevaluate_with_cache(0..12, &mut cache),
+            s1.iter_value(0..12).collect::<Vec<_>>()
+        );
+        assert_eq!(cache.hits(), 0);
+        assert_eq!(
+            s1.reevaluate_with_cache(0..12, &mut cache),
+            s1.iter_value(0..12).collect::<Vec<_>>()
+        );
+        assert_eq!(cache.hits(), 1);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_segment_cache_c() {
+        // repeated subtrees share an identifier and their states
+        let mut cache = SegmentCache::new();
+        let s1 = Sieve::new("(3@0|4@1)&!(3@0|4@1)^(3@0|4@1)");
+        assert_eq!(
+            s1.reevaluate_with_cache(0..24, &mut cache),
+            s1.iter_value(0..24).collect::<Vec<_>>()
+        );
+        assert_eq!(cache.nodes.len(), 6);
+        assert_eq!(cache.len(), 6);
+        assert_eq!(cache.hits(), 2);
+    }
+}
@@ -0,0 +1,142 @@
+use std::ops::Range;
+
+use crate::Sieve;
+use crate::SieveNode;
+
+/// A Sieve compiled to one period of membership, stored as a packed bitmask, as produced by `Sieve::compile`. Membership is a constant-time lookup of one bit, and iteration scans words of the mask rather than evaluating the tree; the mask requires one bit per value of the period.
+///
+/// ```
+/// let c = xensieve::Sieve::new("(5@0|4@2)&!(30@10)").compile();
+/// assert_eq!(c.period(), 60);
+/// assert!(c.contains(-60));
+/// assert!(!c.contains(10));
+/// assert_eq!(c.iter_value(0..20).collect::<Vec<_>>(), vec![0, 2, 5, 6, 14, 15, 18]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompiledSieve {
+    period: u64,
+    mask: Vec<u64>,
+}
+
+impl CompiledSieve {
+    pub(crate) fn new(sieve: &Sieve) -> Self {
+        Self::from_node(&sieve.root)
+    }
+
+    pub(crate) fn from_node(node: &SieveNode) -> Self {
+        let period = node.cycle().expect("period overflows");
+        let words = usize::try_from((period - 1) / 64 + 1).expect("period too large to compile");
+        let mut mask = vec![0u64; words];
+        let end = period as i128;
+        let mut pos = 0;
+        while let Some(v) = node.next_member(pos, end) {
+            mask[(v / 64) as usize] |= 1 << (v % 64);
+            pos = v + 1;
+        }
+        Self { period, mask }
+    }
+
+    /// Return the period of the mask.
+    pub fn period(&self) -> u64 {
+        self.period
+    }
+
+    /// Return the position of the value within the period.
+    fn position(&self, value: i128) -> u64 {
+        value.rem_euclid(self.period as i128) as u64
+    }
+
+    fn bit(&self, position: u64) -> bool {
+        self.mask[(position / 64) as usize] & (1 << (position % 64)) != 0
+    }
+
+    /// Return `true` if the value is a member.
+    pub fn contains(&self, value: i128) -> bool {
+        self.bit(self.position(value))
+    }
+
+    /// Return the smallest member greater than or equal to `start`, from the mask words of at most one period and one word.
+    pub(crate) fn next_member(&self, start: i128) -> Option<i128> {
+        let position = self.position(start);
+        let base = start - position as i128;
+        let words = self.mask.len() as u64;
+        let first = position / 64;
+        // search from the start word to the end of the period, then wrap to the start word again
+        for k in 0..=words {
+            let w = (first + k) % words;
+            let mut word = self.mask[w as usize];
+            if k == 0 {
+                word &= u64::MAX << (position % 64);
+            }
+            if word != 0 {
+                let p = w * 64 + word.trailing_zeros() as u64;
+                let cycle = if w < first || (k > 0 && w == first) {
+                    self.period as i128
+                } else {
+                    0
+                };
+                return base.checked_add(cycle)?.checked_add(p as i128);
+            }
+        }
+        None
+    }
+
+    /// Iterate the members within the range.
+    pub fn iter_value(&self, range: Range<i128>) -> impl Iterator<Item = i128> + '_ {
+        let mut pos = range.start;
+        let end = range.end;
+        std::iter::from_fn(move || {
+            if pos >= end {
+                return None;
+            }
+            let v = self.next_member(pos).filter(|v| *v < end)?;
+            pos = v + 1;
+            Some(v)
+        })
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compiled_sieve_a() {
+        for s in [
+            "(5@0|4@2)&!(30@10)^7@3",
+            "1@0",
+            "0@0",
+            "!(0@0)",
+            "64@63",
+            "130@0|65@64",
+        ] {
+            let s = Sieve::new(s);
+            let c = s.compile();
+            for v in -200..200 {
+                assert_eq!(c.contains(v), s.contains(v), "{s} {v}");
+            }
+            for (a, b) in [(-200, 200), (0, 0), (63, 65), (-1, 1)] {
+                assert_eq!(
+                    c.iter_value(a..b).collect::<Vec<_>>(),
+                    s.iter_value(a..b).collect::<Vec<_>>(),
+                    "{s} {a}..{b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_compiled_sieve_b() {
+        let c = Sieve::new("3@0").compile();
+        assert_eq!(c.period(), 3);
+        assert_eq!(c.mask, vec![0b1]);
+        assert_eq!(
+            c.iter_value(i128::MAX - 10..i128::MAX).count(),
+            Sieve::new("3@0")
+                .iter_value(i128::MAX - 10..i128::MAX)
+                .count()
+        );
+    }
+}
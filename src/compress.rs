@@ -0,0 +1,87 @@
+use crate::util;
+use crate::Residual;
+
+/// Return a union of Residuals whose members, over a period, are exactly `members`, where `members` are ascending values in `0..period`, following the compression by residual classes of Ariza (2005). Each member not yet covered selects the class of the smallest modulus, dividing the period, that contains it and is wholly within the members; classes whose members are all covered by the remaining classes are then removed.
+pub(crate) fn compress(period: u64, members: &[u64]) -> Vec<Residual> {
+    let is_member = |v: u64| members.binary_search(&v).is_ok();
+    let moduli = util::divisors(period);
+    let mut covered = vec![false; members.len()];
+    let mut selected: Vec<Residual> = Vec::new();
+    for (i, p) in members.iter().enumerate() {
+        if covered[i] {
+            continue;
+        }
+        // the modulus of the period always qualifies
+        let m = moduli
+            .iter()
+            .copied()
+            .find(|m| (0..period / m).all(|k| is_member(p % m + k * m)))
+            .unwrap();
+        let r = Residual::new(m, *p);
+        for (j, q) in members.iter().enumerate() {
+            if r.contains(*q as i128) {
+                covered[j] = true;
+            }
+        }
+        selected.push(r);
+    }
+    // remove redundant classes, last selected first
+    let mut i = selected.len();
+    while i > 0 {
+        i -= 1;
+        let r = selected[i];
+        let redundant = members.iter().filter(|q| r.contains(**q as i128)).all(|q| {
+            selected
+                .iter()
+                .enumerate()
+                .any(|(j, o)| j != i && o.contains(*q as i128))
+        });
+        if redundant {
+            selected.remove(i);
+        }
+    }
+    selected
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strings;
+
+    #[test]
+    fn test_compress_a() {
+        assert_eq!(
+            strings(compress(12, &[0, 3, 4, 6, 8, 9])),
+            vec!["3@0", "4@0"]
+        );
+        assert_eq!(
+            strings(compress(12, &[0, 2, 4, 5, 7, 9, 11])),
+            vec!["12@0", "12@2", "12@4", "6@5", "12@7", "12@9"]
+        );
+        assert_eq!(strings(compress(5, &[0, 1, 2, 3, 4])), vec!["1@0"]);
+        assert_eq!(compress(5, &[]), vec![]);
+    }
+
+    #[test]
+    fn test_compress_b() {
+        assert_eq!(
+            strings(compress(6, &[0, 1, 2, 3, 4])),
+            vec!["2@0", "3@1", "3@0"]
+        );
+        assert_eq!(strings(compress(6, &[0, 1, 3, 5])), vec!["3@0", "2@1"]);
+    }
+
+    #[test]
+    fn test_compress_c() {
+        // 10@9 is selected for 9, then covered by 6@5 and 15@9
+        let members = [
+            1, 4, 5, 7, 9, 10, 11, 13, 16, 17, 18, 19, 22, 23, 24, 25, 28, 29,
+        ];
+        assert_eq!(
+            strings(compress(30, &members)),
+            vec!["3@1", "6@5", "30@18", "15@9"]
+        );
+    }
+}
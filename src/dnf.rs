@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use crate::Residual;
+use crate::SieveNode;
+
+/// Remove empty, duplicate, and subsumed Residuals, and sort the remainder. A Residual can only be a subset of a Residual whose modulus divides its own, so the shifts of each modulus are collected, and each Residual is tested against the distinct moduli rather than against every other Residual.
+fn reduce(mut residuals: Vec<Residual>) -> Vec<Residual> {
+    residuals.retain(|r| r.modulus() != 0);
+    residuals.sort();
+    residuals.dedup();
+    let mut shifts: HashMap<u64, HashSet<u64>> = HashMap::new();
+    for r in residuals.iter() {
+        shifts.entry(r.modulus()).or_default().insert(r.shift());
+    }
+    let moduli: Vec<u64> = shifts.keys().copied().collect();
+    residuals.retain(|r| {
+        !moduli.iter().any(|m| {
+            *m != r.modulus() && r.modulus() % m == 0 && shifts[m].contains(&(r.shift() % m))
+        })
+    });
+    residuals
+}
+
+fn intersect(a: &[Residual], b: &[Residual]) -> Vec<Residual> {
+    let mut post = Vec::with_capacity(a.len() * b.len());
+    for x in a {
+        for y in b {
+            post.push(x.try_intersection(y).unwrap_or_else(|e| panic!("{e}")));
+        }
+    }
+    reduce(post)
+}
+
+/// Expand the complement of a union of Residuals: the complement of `m@s` is the union of the other `m - 1` classes of `m`, and the complement of a union is the intersection of the complements.
+fn complement(residuals: &[Residual]) -> Vec<Residual> {
+    residuals.iter().fold(vec![Residual::new(1, 0)], |acc, r| {
+        let others: Vec<Residual> = if r.modulus() == 0 {
+            vec![Residual::new(1, 0)]
+        } else {
+            (0..r.modulus())
+                .filter(|t| *t != r.shift())
+                .map(|t| Residual::new(r.modulus(), t))
+                .collect()
+        };
+        intersect(&acc, &others)
+    })
+}
+
+/// Return the Residuals of a union equivalent to the node, sorted, with none a subset of another.
+pub(crate) fn dnf(node: &SieveNode) -> Vec<Residual> {
+    match node {
+        SieveNode::Unit(r) => reduce(vec![*r]),
+        SieveNode::Union(lhs, rhs) => {
+            let mut post = dnf(lhs);
+            post.extend(dnf(rhs));
+            reduce(post)
+        }
+        SieveNode::Intersection(lhs, rhs) => intersect(&dnf(lhs), &dnf(rhs)),
+        SieveNode::Difference(lhs, rhs) => intersect(&dnf(lhs), &complement(&dnf(rhs))),
+        SieveNode::SymmetricDifference(lhs, rhs) => {
+            let (a, b) = (dnf(lhs), dnf(rhs));
+            let mut post = intersect(&a, &complement(&b));
+            post.extend(intersect(&b, &complement(&a)));
+            reduce(post)
+        }
+        SieveNode::Inversion(part) => complement(&dnf(part)),
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strings;
+
+    #[test]
+    fn test_reduce_a() {
+        let r = reduce(vec![
+            Residual::new(6, 0),
+            Residual::new(0, 0),
+            Residual::new(3, 0),
+            Residual::new(3, 0),
+            Residual::new(4, 1),
+        ]);
+        assert_eq!(strings(r), vec!["3@0", "4@1"]);
+    }
+
+    #[test]
+    fn test_complement_a() {
+        let r = complement(&[Residual::new(2, 0), Residual::new(3, 0)]);
+        assert_eq!(strings(r), vec!["6@1", "6@5"]);
+        assert_eq!(complement(&[Residual::new(1, 0)]), vec![]);
+        assert_eq!(strings(complement(&[])), vec!["1@0"]);
+    }
+}
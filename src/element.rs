@@ -0,0 +1,120 @@
+use std::fmt;
+use std::ops::Bound;
+use std::ops::RangeBounds;
+
+/// An integer type that can be iterated through a Sieve. Sieve membership is evaluated in `i128`; values are converted on the way in, and intervals are converted back to the element type on the way out.
+pub trait NumericElement: Copy + PartialOrd + fmt::Debug {
+    /// The smallest value of this type.
+    const MIN: Self;
+
+    /// The largest value of this type.
+    const MAX: Self;
+
+    /// Widen this value to `i128`.
+    fn to_i128(self) -> i128;
+
+    /// Narrow an `i128` to this type, or `None` if it is not representable.
+    fn from_i128(value: i128) -> Option<Self>;
+
+    /// Narrow an `i128` to this type, clamping values that are not representable to `MIN` or `MAX`.
+    fn saturating_from_i128(value: i128) -> Self {
+        if value < Self::MIN.to_i128() {
+            Self::MIN
+        } else if value > Self::MAX.to_i128() {
+            Self::MAX
+        } else {
+            Self::from_i128(value).unwrap()
+        }
+    }
+}
+
+macro_rules! impl_numeric_element {
+    ($($t:ty),*) => {
+        $(
+            impl NumericElement for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+
+                fn from_i128(value: i128) -> Option<Self> {
+                    <$t>::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric_element!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+/// Return the first and last values of the range, inclusive and widened to `i128`, with unbounded ends taken from the limits of the element type, or `None` if the range is empty.
+pub(crate) fn inclusive_bounds<T: NumericElement>(
+    range: &impl RangeBounds<T>,
+) -> Option<(i128, i128)> {
+    let first = match range.start_bound() {
+        Bound::Included(v) => v.to_i128(),
+        Bound::Excluded(v) => v.to_i128().checked_add(1)?,
+        Bound::Unbounded => T::MIN.to_i128(),
+    };
+    let last = match range.end_bound() {
+        Bound::Included(v) => v.to_i128(),
+        Bound::Excluded(v) => v.to_i128().checked_sub(1)?,
+        Bound::Unbounded => T::MAX.to_i128(),
+    };
+    Some((first, last)).filter(|(f, l)| f <= l)
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numeric_element_a() {
+        assert_eq!((-3i8).to_i128(), -3);
+        assert_eq!(u64::MAX.to_i128(), u64::MAX as i128);
+        assert_eq!(usize::from_i128(7), Some(7));
+    }
+
+    #[test]
+    fn test_numeric_element_b() {
+        assert_eq!(u8::from_i128(-1), None);
+        assert_eq!(u8::from_i128(256), None);
+        assert_eq!(i32::from_i128(-20), Some(-20));
+    }
+
+    #[test]
+    fn test_numeric_element_c() {
+        assert_eq!(u8::saturating_from_i128(-1), 0);
+        assert_eq!(u8::saturating_from_i128(256), 255);
+        assert_eq!(i8::saturating_from_i128(-20), -20);
+        assert_eq!(i128::saturating_from_i128(i128::MIN), i128::MIN);
+    }
+
+    #[test]
+    fn test_inclusive_bounds_a() {
+        assert_eq!(inclusive_bounds(&(3i32..7)), Some((3, 6)));
+        assert_eq!(inclusive_bounds(&(3u8..)), Some((3, 255)));
+        assert_eq!(inclusive_bounds(&(..=-2i8)), Some((-128, -2)));
+        assert_eq!(
+            inclusive_bounds(&(Bound::<i32>::Unbounded, Bound::<i32>::Unbounded)),
+            Some((i32::MIN as i128, i32::MAX as i128))
+        );
+        assert_eq!(
+            inclusive_bounds(&(i128::MIN..=i128::MAX)),
+            Some((i128::MIN, i128::MAX))
+        );
+    }
+
+    #[test]
+    fn test_inclusive_bounds_b() {
+        use std::ops::Bound::*;
+        assert_eq!(inclusive_bounds(&(0u64..0)), None);
+        assert_eq!(inclusive_bounds(&(Included(5), Included(4))), None);
+        assert_eq!(inclusive_bounds(&(..i128::MIN)), None);
+        assert_eq!(inclusive_bounds(&(Excluded(i128::MAX), Unbounded)), None);
+    }
+}
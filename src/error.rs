@@ -0,0 +1,47 @@
+use std::error::Error;
+use std::fmt;
+
+/// The error returned when a Sieve cannot be constructed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SieveError {
+    /// The expression contains no Residuals.
+    Empty,
+    /// The expression contains a character that is not part of the notation.
+    UnknownToken(char),
+    /// A parenthesis is not matched by a corresponding parenthesis.
+    UnbalancedParentheses,
+    /// An operator is not provided with a required operand.
+    MissingOperand,
+    /// Two operands are not separated by a binary operator.
+    MissingOperator,
+    /// A Residual cannot be parsed as a modulus and a shift.
+    InvalidResidual(String),
+    /// A number uses a digit separator other than `_`, such as a locale-style `,`, `.`, `'`, or space.
+    DigitSeparator(char),
+    /// A number exceeds the range of a modulus or shift.
+    Overflow(String),
+    /// A window of values, spanning `span` values, is wider than the `limit` of an operation.
+    WindowTooLarge { span: u128, limit: u64 },
+}
+
+impl fmt::Display for SieveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SieveError::Empty => write!(f, "empty expression"),
+            SieveError::UnknownToken(c) => write!(f, "unknown token: {c}"),
+            SieveError::UnbalancedParentheses => write!(f, "unbalanced parentheses"),
+            SieveError::MissingOperand => write!(f, "missing operand"),
+            SieveError::MissingOperator => write!(f, "missing operator"),
+            SieveError::InvalidResidual(s) => write!(f, "invalid Residual: {s}"),
+            SieveError::DigitSeparator(c) => {
+                write!(f, "invalid digit separator: {c:?}; separate digits with _")
+            }
+            SieveError::Overflow(s) => write!(f, "number out of range: {s}"),
+            SieveError::WindowTooLarge { span, limit } => {
+                write!(f, "window of {span} values exceeds the limit of {limit}")
+            }
+        }
+    }
+}
+
+impl Error for SieveError {}
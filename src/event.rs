@@ -0,0 +1,11 @@
+/// A member of a Sieve tagged with the Residual classes that generated it, as produced by `Sieve::iter_event`.
+///
+/// # Fields
+/// * `value` - The Sieve value.
+/// * `channels` - The indices, into `Sieve::channels`, of the Residuals that contain the value. A member produced only through inversion has no channels.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub value: i128,
+    pub channels: Vec<usize>,
+}
@@ -0,0 +1,83 @@
+use crate::Residual;
+
+/// Return a union of Residuals approximating a target set within a window of values starting at `start`, where `target[i]` is `true` if `start + i` is in the set. Residuals are chosen greedily: at each step, the class (with modulus from one to one more than the window length) that covers the most uncovered targets, without adding more than the remaining budget of extra members, is added; ties prefer fewer extra members, then smaller moduli. Selection stops once no more than `max_missing` targets are uncovered. As classes with a modulus greater than the window contain one value of the window, a fit is always found.
+pub(crate) fn fit(
+    start: i128,
+    target: &[bool],
+    max_extra: usize,
+    max_missing: usize,
+) -> Vec<Residual> {
+    let len = target.len();
+    let mut covered = vec![false; len];
+    let mut missing = target.iter().filter(|t| **t).count();
+    let mut extra = 0;
+    let mut selected = Vec::new();
+    while missing > max_missing {
+        // (hits, extras, modulus, shift)
+        let mut best: Option<(usize, usize, u64, u64)> = None;
+        for m in 1..=len as u64 + 1 {
+            for offset in 0..m.min(len as u64) {
+                let (mut hits, mut extras) = (0, 0);
+                for i in (offset as usize..len).step_by(m as usize) {
+                    if covered[i] {
+                        continue;
+                    }
+                    if target[i] {
+                        hits += 1;
+                    } else {
+                        extras += 1;
+                    }
+                }
+                if hits == 0 || extra + extras > max_extra {
+                    continue;
+                }
+                let better = match best {
+                    None => true,
+                    Some((h, e, _, _)) => hits > h || (hits == h && extras < e),
+                };
+                if better {
+                    best = Some((hits, extras, m, offset));
+                }
+            }
+        }
+        // a class with a modulus beyond the window always qualifies
+        let (hits, extras, m, offset) = best.unwrap();
+        for i in (offset as usize..len).step_by(m as usize) {
+            covered[i] = true;
+        }
+        missing -= hits;
+        extra += extras;
+        let shift = (start + offset as i128).rem_euclid(m as i128) as u64;
+        selected.push(Residual::new(m, shift));
+    }
+    selected
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strings;
+
+    fn mask(values: &[usize], len: usize) -> Vec<bool> {
+        (0..len).map(|i| values.contains(&i)).collect()
+    }
+
+    #[test]
+    fn test_fit_a() {
+        let t = mask(&[0, 3, 6, 9, 4, 8], 10);
+        assert_eq!(strings(fit(0, &t, 0, 0)), vec!["3@0", "4@0"]);
+        assert_eq!(strings(fit(0, &t, 0, 2)), vec!["3@0"]);
+        assert_eq!(strings(fit(0, &t, 0, 6)), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_fit_b() {
+        // 2@0 missing 6, with one extra allowed
+        let t = mask(&[0, 2, 4, 8, 10], 11);
+        assert_eq!(strings(fit(0, &t, 1, 0)), vec!["2@0"]);
+        assert_eq!(strings(fit(0, &t, 0, 0)), vec!["4@0", "8@2"]);
+        assert_eq!(strings(fit(5, &t, 1, 0)), vec!["2@1"]);
+    }
+}
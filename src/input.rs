@@ -0,0 +1,86 @@
+use std::collections::btree_set;
+use std::collections::BTreeSet;
+
+/// Return an iterator of the provided values sorted in ascending order with duplicates removed. Use this to prepare unsorted input for Sieve iterators, such that `iter_interval` yields only positive intervals.
+///
+/// ```
+/// let s = xensieve::Sieve::new("3@0|4@0");
+/// let input = vec![12, 3, 4, 3, 0];
+/// assert_eq!(s.iter_interval(xensieve::sorted_unique(input)).collect::<Vec<_>>(), vec![3, 1, 8]);
+/// ```
+pub fn sorted_unique<T: Ord>(values: impl IntoIterator<Item = T>) -> btree_set::IntoIter<T> {
+    values.into_iter().collect::<BTreeSet<T>>().into_iter()
+}
+
+/// Wrap an iterator such that iteration panics if a value is not greater than the value before it. Use this to assert that input to Sieve iterators is strictly ascending.
+///
+/// ```should_panic
+/// let s = xensieve::Sieve::new("3@0|4@0");
+/// let input = vec![0, 4, 3];
+/// s.iter_interval(xensieve::strictly_ascending(input.into_iter())).for_each(drop);
+/// ```
+pub fn strictly_ascending<I>(iterator: I) -> StrictlyAscending<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd + Copy,
+{
+    StrictlyAscending {
+        iterator,
+        last: None,
+    }
+}
+
+/// The iterator returned by `strictly_ascending`.
+pub struct StrictlyAscending<I>
+where
+    I: Iterator,
+{
+    iterator: I,
+    last: Option<I::Item>,
+}
+
+impl<I> Iterator for StrictlyAscending<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd + Copy,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let p = self.iterator.next()?;
+        if let Some(last) = self.last {
+            assert!(p > last, "input is not strictly ascending");
+        }
+        self.last = Some(p);
+        Some(p)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iterator.size_hint()
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sorted_unique_a() {
+        let post: Vec<_> = sorted_unique(vec![5, -2, 5, 0, -2]).collect();
+        assert_eq!(post, vec![-2, 0, 5]);
+    }
+
+    #[test]
+    fn test_strictly_ascending_a() {
+        let post: Vec<_> = strictly_ascending(-3..3).collect();
+        assert_eq!(post, vec![-3, -2, -1, 0, 1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "input is not strictly ascending")]
+    fn test_strictly_ascending_b() {
+        strictly_ascending(vec![1, 2, 2].into_iter()).for_each(drop);
+    }
+}
@@ -0,0 +1,134 @@
+use std::ops::Range;
+
+use crate::util;
+use crate::Sieve;
+
+/// A policy for assigning positions that belong to both Sieves of an `interleave` to one voice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Conflict {
+    /// Assign shared positions to each voice in turn, starting with the first.
+    Alternate,
+    /// Assign shared positions to the first voice.
+    First,
+    /// Assign shared positions to the second voice.
+    Second,
+    /// Assign shared positions to a voice chosen at random, reproducibly from the provided seed.
+    Random(u64),
+}
+
+/// Two disjoint voices produced by `interleave`.
+///
+/// # Fields
+/// * `first` - The positions, ascending, assigned to the first voice.
+/// * `second` - The positions, ascending, assigned to the second voice.
+/// * `conflicts` - The positions, ascending, that belong to both Sieves and were assigned by the Conflict policy.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Interleaved {
+    pub first: Vec<i128>,
+    pub second: Vec<i128>,
+    pub conflicts: Vec<i128>,
+}
+
+/// Divide the members of two Sieves within the range into two disjoint voices: positions belonging to only one Sieve go to that Sieve's voice, and positions belonging to both are assigned by the Conflict policy and reported.
+///
+/// ```
+/// let a = xensieve::Sieve::new("3@0");
+/// let b = xensieve::Sieve::new("2@0");
+/// let v = xensieve::interleave(&a, &b, 0..13, xensieve::Conflict::Alternate);
+/// assert_eq!(v.first, vec![0, 3, 9, 12]);
+/// assert_eq!(v.second, vec![2, 4, 6, 8, 10]);
+/// assert_eq!(v.conflicts, vec![0, 6, 12]);
+/// ```
+pub fn interleave(a: &Sieve, b: &Sieve, range: Range<i128>, policy: Conflict) -> Interleaved {
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut random = util::Random::new(match policy {
+        Conflict::Random(seed) => seed,
+        _ => 0,
+    });
+
+    let mut x = a.scan(range.clone()).into_iter().peekable();
+    let mut y = b.scan(range).into_iter().peekable();
+    loop {
+        match (x.peek().copied(), y.peek().copied()) {
+            (Some(p), Some(q)) if p == q => {
+                x.next();
+                y.next();
+                let to_first = match policy {
+                    Conflict::Alternate => conflicts.len() % 2 == 0,
+                    Conflict::First => true,
+                    Conflict::Second => false,
+                    Conflict::Random(_) => random.below(2) == 0,
+                };
+                conflicts.push(p);
+                if to_first {
+                    first.push(p);
+                } else {
+                    second.push(p);
+                }
+            }
+            (Some(p), q) if q.map_or(true, |q| p < q) => {
+                x.next();
+                first.push(p);
+            }
+            (_, Some(q)) => {
+                y.next();
+                second.push(q);
+            }
+            _ => break,
+        }
+    }
+    Interleaved {
+        first,
+        second,
+        conflicts,
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interleave_a() {
+        let a = Sieve::new("4@0|4@1");
+        let b = Sieve::new("2@0");
+        let v = interleave(&a, &b, 0..9, Conflict::First);
+        assert_eq!(v.first, vec![0, 1, 4, 5, 8]);
+        assert_eq!(v.second, vec![2, 6]);
+        assert_eq!(v.conflicts, vec![0, 4, 8]);
+
+        let v = interleave(&a, &b, 0..9, Conflict::Second);
+        assert_eq!(v.first, vec![1, 5]);
+        assert_eq!(v.second, vec![0, 2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn test_interleave_b() {
+        let a = Sieve::new("2@0");
+        let b = Sieve::new("3@0");
+        let v1 = interleave(&a, &b, -30..30, Conflict::Random(42));
+        let v2 = interleave(&a, &b, -30..30, Conflict::Random(42));
+        assert_eq!(v1, v2);
+        assert_eq!(v1.conflicts, (-5..5).map(|k| k * 6).collect::<Vec<_>>());
+        assert_eq!(v1.first.len() + v1.second.len(), 40);
+        assert!(v1.first.iter().all(|p| !v1.second.contains(p)));
+    }
+
+    #[test]
+    fn test_interleave_c() {
+        let v = interleave(
+            &Sieve::new("5@1"),
+            &Sieve::empty(),
+            0..12,
+            Conflict::Alternate,
+        );
+        assert_eq!(v.first, vec![1, 6, 11]);
+        assert_eq!(v.second, vec![]);
+        assert_eq!(v.conflicts, vec![]);
+    }
+}
@@ -1,29 +1,136 @@
 use std::cmp::Ordering;
 use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::ops::BitAnd;
+use std::ops::BitAndAssign;
 use std::ops::BitOr;
+use std::ops::BitOrAssign;
 use std::ops::BitXor;
+use std::ops::BitXorAssign;
 use std::ops::Not;
+use std::ops::Sub;
+use std::str::FromStr;
+use std::sync::Arc;
 
+mod arpeggio;
+mod balance;
+mod batch;
+mod cache;
+mod compiled;
+mod compress;
+mod dnf;
+mod element;
+mod error;
+mod event;
+mod fit;
+mod input;
+mod interleave;
+mod lint;
+mod manifest;
+mod memo;
+mod meter;
+mod minimize;
 mod parser;
+mod piecewise;
+mod plan;
+mod precision;
+mod preset;
+mod profile;
+mod program;
+mod progression;
+mod proof;
+#[cfg(feature = "presets")]
+mod published;
+mod pyramid;
+pub mod realtime;
+mod render;
+mod rewrite;
+mod rhythm;
+mod rounding;
+mod scale;
+mod segment;
+mod simplify;
+mod step;
+mod table;
 mod util;
+pub mod verify;
+mod wheel;
+
+pub use arpeggio::Arpeggiator;
+pub use arpeggio::Note;
+pub use arpeggio::Pattern;
+pub use cache::SegmentCache;
+pub use compiled::CompiledSieve;
+pub use element::NumericElement;
+pub use error::SieveError;
+pub use event::Event;
+pub use input::sorted_unique;
+pub use input::strictly_ascending;
+pub use input::StrictlyAscending;
+pub use interleave::interleave;
+pub use interleave::Conflict;
+pub use interleave::Interleaved;
+pub use lint::lint;
+pub use lint::Lint;
+pub use lint::LintKind;
+pub use manifest::Manifest;
+pub use meter::Meter;
+pub use piecewise::Piecewise;
+pub use plan::Plan;
+pub use plan::Strategy;
+pub use precision::Precision;
+pub use precision::Promoted;
+pub use preset::Preset;
+pub use preset::PresetError;
+pub use profile::NodeProfile;
+pub use proof::verify;
+pub use proof::Congruence;
+pub use proof::Evidence;
+pub use proof::MembershipProof;
+pub use pyramid::Pyramid;
+pub use render::render_segments;
+pub use rewrite::Rewriting;
+pub use rhythm::Duration;
+pub use rhythm::NoteValue;
+pub use rhythm::RhythmNode;
+pub use rhythm::RhythmTree;
+pub use rounding::Rounding;
+pub use scale::MidiNote;
+pub use scale::RatioMap;
+pub use scale::Scale;
+pub use scale::Spelling;
+pub use segment::Segment;
+pub use step::Step;
+pub use table::write_corpus;
+pub use table::Table;
+pub use wheel::Wheel;
 
 //------------------------------------------------------------------------------
 
-/// Container of integer values for the modulus and the shift of a Residual class.
+/// Container of integer values for the modulus and the shift of a Residual class. A Residual can be converted into a Sieve, and combined with other Residuals by intersection.
+///
+/// ```
+/// let r = xensieve::Residual::new(3, 4);
+/// assert_eq!(r.to_string(), "3@1");
+/// assert_eq!(r.contains(7), true);
+/// let s: xensieve::Sieve = r.into();
+/// assert_eq!(s.iter_value(0..10).collect::<Vec<_>>(), vec![1, 4, 7]);
+/// ```
 ///
 /// # Fields
 /// * `modulus` - The modulus.
 /// * `shift` - The shift.
 ///
-#[derive(Clone, Debug, Copy)]
-pub(crate) struct Residual {
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+pub struct Residual {
     modulus: u64,
     shift: u64,
 }
 
 impl Residual {
-    pub(crate) fn new(modulus: u64, mut shift: u64) -> Self {
+    /// Construct a Residual. The shift is reduced modulo the modulus; a modulus of zero defines an empty Residual, `0@0`.
+    pub fn new(modulus: u64, mut shift: u64) -> Self {
         if modulus == 0 {
             shift = 0;
         } else {
@@ -32,15 +139,66 @@ impl Residual {
         Self { modulus, shift }
     }
 
-    /// Return `true` if the value is contained with this Sieve.
+    /// Return the modulus.
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    /// Return the shift.
+    pub fn shift(&self) -> u64 {
+        self.shift
+    }
+
+    /// Return `true` if the value is contained with this Residual.
     ///
-    pub(crate) fn contains(&self, value: i128) -> bool {
+    pub fn contains(&self, value: i128) -> bool {
         if self.modulus == 0 {
             return false;
         }
         let pos: i128 = value - self.shift as i128;
         pos % self.modulus as i128 == 0
     }
+
+    /// Return `true` if every value of this Residual is in `other`.
+    pub(crate) fn is_subset_of(&self, other: &Residual) -> bool {
+        self.modulus == 0
+            || (other.modulus != 0
+                && self.modulus % other.modulus == 0
+                && self.shift % other.modulus == other.shift)
+    }
+
+    /// Return the intersection of this Residual and `other` as one Residual, or `None` if the modulus overflows.
+    pub(crate) fn intersection(&self, other: &Residual) -> Option<Residual> {
+        self.try_intersection(other).ok()
+    }
+
+    /// Return the intersection of this Residual and `other` as one Residual, or a `SieveError::Overflow` naming both Residuals if the modulus of the intersection, the least common multiple of the moduli, exceeds `u64`.
+    ///
+    /// ```
+    /// let a = xensieve::Residual::new(4, 3);
+    /// assert_eq!(a.try_intersection(&xensieve::Residual::new(6, 1)).unwrap().to_string(), "12@7");
+    /// let b = xensieve::Residual::new(u64::MAX, 0);
+    /// assert!(b.try_intersection(&xensieve::Residual::new(u64::MAX - 1, 0)).is_err());
+    /// ```
+    pub fn try_intersection(&self, other: &Residual) -> Result<Residual, SieveError> {
+        util::intersection(self.modulus, other.modulus, self.shift, other.shift)
+            .map(|(m, s)| Residual::new(m, s))
+            .map_err(|e| {
+                SieveError::Overflow(format!(
+                    "intersection of {self} and {other}: {e}; the least common multiple of the moduli exceeds {}",
+                    u64::MAX
+                ))
+            })
+    }
+
+    /// Return the smallest contained value greater than or equal to `start`, or `None` if this Residual is empty or the value overflows.
+    pub(crate) fn next_member(&self, start: i128) -> Option<i128> {
+        if self.modulus == 0 {
+            return None;
+        }
+        let m = self.modulus as i128;
+        start.checked_add((self.shift as i128 - start.rem_euclid(m)).rem_euclid(m))
+    }
 }
 
 impl fmt::Display for Residual {
@@ -53,20 +211,14 @@ impl fmt::Display for Residual {
 impl BitAnd for Residual {
     type Output = Residual;
 
+    /// # Panics
+    /// Panics if the modulus of the intersection overflows; use `try_intersection` to handle this as an error.
     fn bitand(self, rhs: Self) -> Self::Output {
-        let (m, s) = util::intersection(self.modulus, rhs.modulus, self.shift, rhs.shift).unwrap();
-        Self::new(m, s)
+        self.try_intersection(&rhs)
+            .unwrap_or_else(|e| panic!("{e}"))
     }
 }
 
-impl PartialEq for Residual {
-    fn eq(&self, other: &Self) -> bool {
-        self.modulus == other.modulus && self.shift == other.shift
-    }
-}
-
-impl Eq for Residual {}
-
 impl PartialOrd for Residual {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -81,16 +233,32 @@ impl Ord for Residual {
     }
 }
 
+/// Return the string notation of each Residual, for comparison in tests.
+#[cfg(test)]
+pub(crate) fn strings(residuals: Vec<Residual>) -> Vec<String> {
+    residuals.iter().map(|r| r.to_string()).collect()
+}
+
 //------------------------------------------------------------------------------
 
+/// The largest number of Residuals in a Sieve constructed by `Sieve::nest`.
+const NEST_LIMIT: u64 = 1 << 12;
+
+/// The largest number of values in the window searched by `Sieve::fit`.
+const FIT_SPAN_LIMIT: u64 = 1 << 12;
+
+/// The largest number of Residuals into which `SieveNode::progression_residuals` expands a node; beyond this, callers fall back to evaluating the tree.
+const PROGRESSION_LIMIT: usize = 1 << 12;
+
 /// A node in the graph of Residuals combined by logical operations.
 ///
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub(crate) enum SieveNode {
     Unit(Residual),
     Intersection(Box<SieveNode>, Box<SieveNode>),
     Union(Box<SieveNode>, Box<SieveNode>),
     SymmetricDifference(Box<SieveNode>, Box<SieveNode>),
+    Difference(Box<SieveNode>, Box<SieveNode>),
     Inversion(Box<SieveNode>),
 }
 
@@ -98,21 +266,10 @@ impl fmt::Display for SieveNode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s: String = match self {
             SieveNode::Unit(residual) => residual.to_string(),
-            SieveNode::Intersection(lhs, rhs) => {
-                let lhs_str = lhs.to_string();
-                let rhs_str = rhs.to_string();
-                format!("{lhs_str}&{rhs_str}")
-            }
-            SieveNode::Union(lhs, rhs) => {
-                let lhs_str = lhs.to_string();
-                let rhs_str = rhs.to_string();
-                format!("{lhs_str}|{rhs_str}")
-            }
-            SieveNode::SymmetricDifference(lhs, rhs) => {
-                let lhs_str = lhs.to_string();
-                let rhs_str = rhs.to_string();
-                format!("{lhs_str}^{rhs_str}")
-            }
+            SieveNode::Intersection(lhs, rhs) => self.format_binary(lhs, rhs, '&'),
+            SieveNode::Union(lhs, rhs) => self.format_binary(lhs, rhs, '|'),
+            SieveNode::SymmetricDifference(lhs, rhs) => self.format_binary(lhs, rhs, '^'),
+            SieveNode::Difference(lhs, rhs) => self.format_binary(lhs, rhs, '-'),
             SieveNode::Inversion(part) => {
                 let r = part.to_string();
                 format!("!({r})")
@@ -123,6 +280,33 @@ impl fmt::Display for SieveNode {
 }
 
 impl SieveNode {
+    /// Return the binding precedence of this node in string notation, as used by the parser.
+    fn precedence(&self) -> i8 {
+        match self {
+            SieveNode::Unit(_) => 5,
+            SieveNode::Inversion(_) => 4,
+            SieveNode::Intersection(_, _) | SieveNode::Difference(_, _) => 3,
+            SieveNode::SymmetricDifference(_, _) => 2,
+            SieveNode::Union(_, _) => 1,
+        }
+    }
+
+    /// Format a binary operation, adding parentheses to operands where needed such that the string parses to the same tree. As operators are parsed left-associatively, a right operand of equal precedence is parenthesized.
+    fn format_binary(&self, lhs: &SieveNode, rhs: &SieveNode, op: char) -> String {
+        let p = self.precedence();
+        let lhs_str = if lhs.precedence() < p {
+            format!("({lhs})")
+        } else {
+            lhs.to_string()
+        };
+        let rhs_str = if rhs.precedence() <= p {
+            format!("({rhs})")
+        } else {
+            rhs.to_string()
+        };
+        format!("{lhs_str}{op}{rhs_str}")
+    }
+
     /// Return `true` if the values is contained within this Sieve.
     ///
     pub fn contains(&self, value: i128) -> bool {
@@ -131,26 +315,524 @@ impl SieveNode {
             SieveNode::Intersection(lhs, rhs) => lhs.contains(value) && rhs.contains(value),
             SieveNode::Union(lhs, rhs) => lhs.contains(value) || rhs.contains(value),
             SieveNode::SymmetricDifference(lhs, rhs) => lhs.contains(value) ^ rhs.contains(value),
+            SieveNode::Difference(lhs, rhs) => lhs.contains(value) && !rhs.contains(value),
             SieveNode::Inversion(part) => !part.contains(value),
         }
     }
+
+    /// Return the least common multiple of all non-zero moduli, or one if there are none; returns `None` if the result overflows.
+    pub(crate) fn cycle(&self) -> Option<u64> {
+        match self {
+            SieveNode::Unit(residual) => Some(residual.modulus.max(1)),
+            SieveNode::Intersection(lhs, rhs)
+            | SieveNode::Union(lhs, rhs)
+            | SieveNode::SymmetricDifference(lhs, rhs)
+            | SieveNode::Difference(lhs, rhs) => util::lcm(lhs.cycle()?, rhs.cycle()?),
+            SieveNode::Inversion(part) => part.cycle(),
+        }
+    }
+
+    /// Return the smallest member in the range `start..end`, or `None` if there is none. Residuals are resolved analytically, and intersections leapfrog between the next members of each operand. The complements of inversions are found by stepping through values, and differences and symmetric differences by stepping through the members of their operands; as the pattern repeats, each of these searches, and the leapfrogging of intersections, is bounded to one cycle of the node from `start`, such that its cost is at most linear in the cycle rather than in the range.
+    pub(crate) fn next_member(&self, start: i128, end: i128) -> Option<i128> {
+        if start >= end {
+            return None;
+        }
+        match self {
+            SieveNode::Unit(residual) => residual.next_member(start).filter(|v| *v < end),
+            SieveNode::Union(lhs, rhs) => match lhs.next_member(start, end) {
+                Some(a) => Some(rhs.next_member(start, a).unwrap_or(a)),
+                None => rhs.next_member(start, end),
+            },
+            SieveNode::Intersection(lhs, rhs) => {
+                let end = self.cycle_end(start, end);
+                let mut pos = start;
+                loop {
+                    let a = lhs.next_member(pos, end)?;
+                    let b = rhs.next_member(a, end)?;
+                    if a == b {
+                        return Some(a);
+                    }
+                    pos = b;
+                }
+            }
+            SieveNode::Difference(lhs, rhs) => {
+                let end = self.cycle_end(start, end);
+                let mut pos = start;
+                loop {
+                    let a = lhs.next_member(pos, end)?;
+                    if !rhs.contains(a) {
+                        return Some(a);
+                    }
+                    pos = a + 1;
+                }
+            }
+            SieveNode::SymmetricDifference(lhs, rhs) => {
+                let end = self.cycle_end(start, end);
+                let mut pos = start;
+                loop {
+                    let a = match lhs.next_member(pos, end) {
+                        Some(a) => Some(rhs.next_member(pos, a).unwrap_or(a)),
+                        None => rhs.next_member(pos, end),
+                    }?;
+                    if lhs.contains(a) ^ rhs.contains(a) {
+                        return Some(a);
+                    }
+                    pos = a + 1;
+                }
+            }
+            SieveNode::Inversion(part) => {
+                (start..self.cycle_end(start, end)).find(|v| !part.contains(*v))
+            }
+        }
+    }
+
+    /// Return the lesser of `end` and one cycle past `start`, beyond which a search from `start` finds no member not already found.
+    fn cycle_end(&self, start: i128, end: i128) -> i128 {
+        match self.cycle().and_then(|c| start.checked_add(c as i128)) {
+            Some(limit) => end.min(limit),
+            None => end,
+        }
+    }
+
+    /// Return the number of members from `start` up to but excluding `end`, stepping from each member to the next without collecting them.
+    pub(crate) fn count_members(&self, start: i128, end: i128) -> u128 {
+        let mut count: u128 = 0;
+        let mut pos = start;
+        while let Some(v) = self.next_member(pos, end) {
+            count += 1;
+            pos = match v.checked_add(1) {
+                Some(p) => p,
+                None => break,
+            };
+        }
+        count
+    }
+
+    /// Collect, in order of appearance and without duplicates, the Residuals that can generate members: those not complemented by an inversion or excluded as the right operand of a difference.
+    pub(crate) fn generators(&self, positive: bool, residuals: &mut Vec<Residual>) {
+        match self {
+            SieveNode::Unit(residual) => {
+                if positive && !residuals.contains(residual) {
+                    residuals.push(*residual);
+                }
+            }
+            SieveNode::Intersection(lhs, rhs)
+            | SieveNode::Union(lhs, rhs)
+            | SieveNode::SymmetricDifference(lhs, rhs) => {
+                lhs.generators(positive, residuals);
+                rhs.generators(positive, residuals);
+            }
+            SieveNode::Difference(lhs, rhs) => {
+                lhs.generators(positive, residuals);
+                rhs.generators(!positive, residuals);
+            }
+            SieveNode::Inversion(part) => part.generators(!positive, residuals),
+        }
+    }
+
+    /// Collect all Residuals, in order of appearance.
+    pub(crate) fn residuals(&self, residuals: &mut Vec<Residual>) {
+        match self {
+            SieveNode::Unit(residual) => residuals.push(*residual),
+            SieveNode::Intersection(lhs, rhs)
+            | SieveNode::Union(lhs, rhs)
+            | SieveNode::SymmetricDifference(lhs, rhs)
+            | SieveNode::Difference(lhs, rhs) => {
+                lhs.residuals(residuals);
+                rhs.residuals(residuals);
+            }
+            SieveNode::Inversion(part) => part.residuals(residuals),
+        }
+    }
+
+    /// Return the number of nodes.
+    pub(crate) fn size(&self) -> usize {
+        match self {
+            SieveNode::Unit(_) => 1,
+            SieveNode::Intersection(lhs, rhs)
+            | SieveNode::Union(lhs, rhs)
+            | SieveNode::SymmetricDifference(lhs, rhs)
+            | SieveNode::Difference(lhs, rhs) => 1 + lhs.size() + rhs.size(),
+            SieveNode::Inversion(part) => 1 + part.size(),
+        }
+    }
+
+    /// If this node is composed only of unions of Residuals, return those Residuals.
+    pub(crate) fn union_residuals(&self) -> Option<Vec<Residual>> {
+        match self {
+            SieveNode::Unit(residual) => Some(vec![*residual]),
+            SieveNode::Union(lhs, rhs) => {
+                let mut post = lhs.union_residuals()?;
+                post.extend(rhs.union_residuals()?);
+                Some(post)
+            }
+            _ => None,
+        }
+    }
+
+    /// If this node is composed only of unions and intersections of Residuals, return Residuals whose union is equivalent, resolving each intersection of classes into one class; return `None` for any other node, if the modulus of an intersection overflows, or if the number of Residuals, which grows as the product of the sizes of intersected unions, exceeds `PROGRESSION_LIMIT`.
+    pub(crate) fn progression_residuals(&self) -> Option<Vec<Residual>> {
+        match self {
+            SieveNode::Unit(residual) => Some(vec![*residual]),
+            SieveNode::Union(lhs, rhs) => {
+                let mut post = lhs.progression_residuals()?;
+                post.extend(rhs.progression_residuals()?);
+                (post.len() <= PROGRESSION_LIMIT).then(|| post)
+            }
+            SieveNode::Intersection(lhs, rhs) => {
+                let lhs = lhs.progression_residuals()?;
+                let rhs = rhs.progression_residuals()?;
+                let size = lhs
+                    .len()
+                    .checked_mul(rhs.len())
+                    .filter(|n| *n <= PROGRESSION_LIMIT)?;
+                let mut post = Vec::with_capacity(size);
+                for a in lhs.iter() {
+                    for b in rhs.iter() {
+                        post.push(a.intersection(b)?);
+                    }
+                }
+                Some(post)
+            }
+            _ => None,
+        }
+    }
+
+    /// Return `true` if `lhs` and `rhs` are each unions and intersections of Residuals with members, but their intersection has none, as for `5@2&10@3`. Returns `false` if the intersection would expand to more than `PROGRESSION_LIMIT` Residuals.
+    pub(crate) fn collapses(lhs: &SieveNode, rhs: &SieveNode) -> bool {
+        let (a, b) = match (lhs.progression_residuals(), rhs.progression_residuals()) {
+            (Some(a), Some(b))
+                if a.len()
+                    .checked_mul(b.len())
+                    .map_or(false, |n| n <= PROGRESSION_LIMIT) =>
+            {
+                (a, b)
+            }
+            _ => return false,
+        };
+        a.iter().any(|r| r.modulus > 0)
+            && b.iter().any(|r| r.modulus > 0)
+            && a.iter().all(|x| {
+                b.iter()
+                    .all(|y| x.intersection(y).map_or(false, |r| r.modulus == 0))
+            })
+    }
+
+    /// Return `true` if any intersection within this node collapses to the empty set, as determined by `collapses`.
+    pub(crate) fn collapsed(&self) -> bool {
+        match self {
+            SieveNode::Unit(_) => false,
+            SieveNode::Intersection(lhs, rhs) => {
+                lhs.collapsed() || rhs.collapsed() || SieveNode::collapses(lhs, rhs)
+            }
+            SieveNode::Union(lhs, rhs)
+            | SieveNode::SymmetricDifference(lhs, rhs)
+            | SieveNode::Difference(lhs, rhs) => lhs.collapsed() || rhs.collapsed(),
+            SieveNode::Inversion(part) => part.collapsed(),
+        }
+    }
+
+    /// Return an estimate of the fraction of integers contained in this node, treating operands as independent.
+    pub(crate) fn density_estimate(&self) -> f64 {
+        match self {
+            SieveNode::Unit(residual) => {
+                if residual.modulus == 0 {
+                    0.0
+                } else {
+                    1.0 / residual.modulus as f64
+                }
+            }
+            SieveNode::Intersection(lhs, rhs) => lhs.density_estimate() * rhs.density_estimate(),
+            SieveNode::Union(lhs, rhs) => {
+                let (a, b) = (lhs.density_estimate(), rhs.density_estimate());
+                a + b - a * b
+            }
+            SieveNode::SymmetricDifference(lhs, rhs) => {
+                let (a, b) = (lhs.density_estimate(), rhs.density_estimate());
+                a + b - 2.0 * a * b
+            }
+            SieveNode::Difference(lhs, rhs) => {
+                lhs.density_estimate() * (1.0 - rhs.density_estimate())
+            }
+            SieveNode::Inversion(part) => 1.0 - part.density_estimate(),
+        }
+    }
+
+    /// Return a node with the operands of intersections and unions ordered for short-circuit evaluation.
+    pub(crate) fn optimize_order(&self) -> SieveNode {
+        match self {
+            SieveNode::Unit(residual) => SieveNode::Unit(*residual),
+            SieveNode::Intersection(lhs, rhs) => {
+                let (lhs, rhs) = (lhs.optimize_order(), rhs.optimize_order());
+                // test the less dense operand first, as it is more likely to be false
+                if rhs.density_estimate() < lhs.density_estimate() {
+                    SieveNode::Intersection(Box::new(rhs), Box::new(lhs))
+                } else {
+                    SieveNode::Intersection(Box::new(lhs), Box::new(rhs))
+                }
+            }
+            SieveNode::Union(lhs, rhs) => {
+                let (lhs, rhs) = (lhs.optimize_order(), rhs.optimize_order());
+                // test the more dense operand first, as it is more likely to be true
+                if rhs.density_estimate() > lhs.density_estimate() {
+                    SieveNode::Union(Box::new(rhs), Box::new(lhs))
+                } else {
+                    SieveNode::Union(Box::new(lhs), Box::new(rhs))
+                }
+            }
+            SieveNode::SymmetricDifference(lhs, rhs) => SieveNode::SymmetricDifference(
+                Box::new(lhs.optimize_order()),
+                Box::new(rhs.optimize_order()),
+            ),
+            SieveNode::Difference(lhs, rhs) => SieveNode::Difference(
+                Box::new(lhs.optimize_order()),
+                Box::new(rhs.optimize_order()),
+            ),
+            SieveNode::Inversion(part) => SieveNode::Inversion(Box::new(part.optimize_order())),
+        }
+    }
+
+    /// Return a node with each Residual's modulus and shift multiplied by `numerator / denominator`, rounded by `rounding`; `exact` is set to `false` if any rounding was necessary. Null Residuals are retained as they are.
+    pub(crate) fn scale_rational(
+        &self,
+        numerator: u64,
+        denominator: u64,
+        rounding: Rounding,
+        exact: &mut bool,
+    ) -> SieveNode {
+        match self {
+            SieveNode::Unit(residual) => {
+                if residual.modulus == 0 {
+                    return SieveNode::Unit(*residual);
+                }
+                let (n, d) = (numerator as u128, denominator as u128);
+                let (m, m_exact) = rounding.divide(residual.modulus as u128 * n, d);
+                let (s, s_exact) = rounding.divide(residual.shift as u128 * n, d);
+                *exact = *exact && m_exact && s_exact;
+                let m = u64::try_from(m.max(1)).expect("modulus overflow");
+                let s = u64::try_from(s % m as u128).unwrap();
+                SieveNode::Unit(Residual::new(m, s))
+            }
+            SieveNode::Intersection(lhs, rhs) => SieveNode::Intersection(
+                Box::new(lhs.scale_rational(numerator, denominator, rounding, exact)),
+                Box::new(rhs.scale_rational(numerator, denominator, rounding, exact)),
+            ),
+            SieveNode::Union(lhs, rhs) => SieveNode::Union(
+                Box::new(lhs.scale_rational(numerator, denominator, rounding, exact)),
+                Box::new(rhs.scale_rational(numerator, denominator, rounding, exact)),
+            ),
+            SieveNode::SymmetricDifference(lhs, rhs) => SieveNode::SymmetricDifference(
+                Box::new(lhs.scale_rational(numerator, denominator, rounding, exact)),
+                Box::new(rhs.scale_rational(numerator, denominator, rounding, exact)),
+            ),
+            SieveNode::Difference(lhs, rhs) => SieveNode::Difference(
+                Box::new(lhs.scale_rational(numerator, denominator, rounding, exact)),
+                Box::new(rhs.scale_rational(numerator, denominator, rounding, exact)),
+            ),
+            SieveNode::Inversion(part) => SieveNode::Inversion(Box::new(part.scale_rational(
+                numerator,
+                denominator,
+                rounding,
+                exact,
+            ))),
+        }
+    }
+
+    /// Return a node that contains `v` if and only if `v` divided (with flooring) by `factor` is contained in this node.
+    pub(crate) fn dilate(&self, factor: u64) -> SieveNode {
+        match self {
+            SieveNode::Unit(residual) => {
+                if residual.modulus == 0 {
+                    return SieveNode::Unit(*residual);
+                }
+                let m = residual
+                    .modulus
+                    .checked_mul(factor)
+                    .expect("modulus overflow");
+                let s = residual.shift * factor;
+                (1..factor).fold(SieveNode::Unit(Residual::new(m, s)), |node, i| {
+                    SieveNode::Union(
+                        Box::new(node),
+                        Box::new(SieveNode::Unit(Residual::new(m, s + i))),
+                    )
+                })
+            }
+            SieveNode::Intersection(lhs, rhs) => {
+                SieveNode::Intersection(Box::new(lhs.dilate(factor)), Box::new(rhs.dilate(factor)))
+            }
+            SieveNode::Union(lhs, rhs) => {
+                SieveNode::Union(Box::new(lhs.dilate(factor)), Box::new(rhs.dilate(factor)))
+            }
+            SieveNode::SymmetricDifference(lhs, rhs) => SieveNode::SymmetricDifference(
+                Box::new(lhs.dilate(factor)),
+                Box::new(rhs.dilate(factor)),
+            ),
+            SieveNode::Difference(lhs, rhs) => {
+                SieveNode::Difference(Box::new(lhs.dilate(factor)), Box::new(rhs.dilate(factor)))
+            }
+            SieveNode::Inversion(part) => SieveNode::Inversion(Box::new(part.dilate(factor))),
+        }
+    }
+
+    /// Return a node whose members are the members of this node plus `offset`.
+    pub(crate) fn translate(&self, offset: i128) -> SieveNode {
+        match self {
+            SieveNode::Unit(residual) => {
+                if residual.modulus == 0 {
+                    return SieveNode::Unit(*residual);
+                }
+                let m = residual.modulus as i128;
+                let s = (residual.shift as i128 + offset.rem_euclid(m)) % m;
+                SieveNode::Unit(Residual::new(residual.modulus, s as u64))
+            }
+            SieveNode::Intersection(lhs, rhs) => SieveNode::Intersection(
+                Box::new(lhs.translate(offset)),
+                Box::new(rhs.translate(offset)),
+            ),
+            SieveNode::Union(lhs, rhs) => SieveNode::Union(
+                Box::new(lhs.translate(offset)),
+                Box::new(rhs.translate(offset)),
+            ),
+            SieveNode::SymmetricDifference(lhs, rhs) => SieveNode::SymmetricDifference(
+                Box::new(lhs.translate(offset)),
+                Box::new(rhs.translate(offset)),
+            ),
+            SieveNode::Difference(lhs, rhs) => SieveNode::Difference(
+                Box::new(lhs.translate(offset)),
+                Box::new(rhs.translate(offset)),
+            ),
+            SieveNode::Inversion(part) => SieveNode::Inversion(Box::new(part.translate(offset))),
+        }
+    }
+
+    /// Return a node whose members are the negations of the members of this node.
+    pub(crate) fn reflect(&self) -> SieveNode {
+        match self {
+            SieveNode::Unit(residual) => {
+                if residual.modulus == 0 {
+                    return SieveNode::Unit(*residual);
+                }
+                let m = residual.modulus;
+                SieveNode::Unit(Residual::new(m, (m - residual.shift) % m))
+            }
+            SieveNode::Intersection(lhs, rhs) => {
+                SieveNode::Intersection(Box::new(lhs.reflect()), Box::new(rhs.reflect()))
+            }
+            SieveNode::Union(lhs, rhs) => {
+                SieveNode::Union(Box::new(lhs.reflect()), Box::new(rhs.reflect()))
+            }
+            SieveNode::SymmetricDifference(lhs, rhs) => {
+                SieveNode::SymmetricDifference(Box::new(lhs.reflect()), Box::new(rhs.reflect()))
+            }
+            SieveNode::Difference(lhs, rhs) => {
+                SieveNode::Difference(Box::new(lhs.reflect()), Box::new(rhs.reflect()))
+            }
+            SieveNode::Inversion(part) => SieveNode::Inversion(Box::new(part.reflect())),
+        }
+    }
+
+    /// Return the membership condition of this node as an SMT-LIB term over the integer variable `var`.
+    pub(crate) fn to_smtlib(&self, var: &str) -> String {
+        match self {
+            SieveNode::Unit(residual) => {
+                if residual.modulus == 0 {
+                    "false".to_string()
+                } else {
+                    format!("(= (mod {var} {}) {})", residual.modulus, residual.shift)
+                }
+            }
+            SieveNode::Intersection(lhs, rhs) => {
+                format!("(and {} {})", lhs.to_smtlib(var), rhs.to_smtlib(var))
+            }
+            SieveNode::Union(lhs, rhs) => {
+                format!("(or {} {})", lhs.to_smtlib(var), rhs.to_smtlib(var))
+            }
+            SieveNode::SymmetricDifference(lhs, rhs) => {
+                format!("(xor {} {})", lhs.to_smtlib(var), rhs.to_smtlib(var))
+            }
+            SieveNode::Difference(lhs, rhs) => {
+                format!("(and {} (not {}))", lhs.to_smtlib(var), rhs.to_smtlib(var))
+            }
+            SieveNode::Inversion(part) => format!("(not {})", part.to_smtlib(var)),
+        }
+    }
+
+    /// Return the membership condition of this node as a MiniZinc expression over the integer variable `var`.
+    pub(crate) fn to_minizinc(&self, var: &str) -> String {
+        match self {
+            SieveNode::Unit(residual) => {
+                if residual.modulus == 0 {
+                    "false".to_string()
+                } else {
+                    // MiniZinc `mod` takes the sign of the dividend
+                    let m = residual.modulus;
+                    format!("(({var} mod {m}) + {m}) mod {m} = {}", residual.shift)
+                }
+            }
+            SieveNode::Intersection(lhs, rhs) => {
+                format!("({} /\\ {})", lhs.to_minizinc(var), rhs.to_minizinc(var))
+            }
+            SieveNode::Union(lhs, rhs) => {
+                format!("({} \\/ {})", lhs.to_minizinc(var), rhs.to_minizinc(var))
+            }
+            SieveNode::SymmetricDifference(lhs, rhs) => {
+                format!("({} xor {})", lhs.to_minizinc(var), rhs.to_minizinc(var))
+            }
+            SieveNode::Difference(lhs, rhs) => {
+                format!(
+                    "({} /\\ not ({}))",
+                    lhs.to_minizinc(var),
+                    rhs.to_minizinc(var)
+                )
+            }
+            SieveNode::Inversion(part) => format!("not ({})", part.to_minizinc(var)),
+        }
+    }
 }
 
 //------------------------------------------------------------------------------
 
 /// The representation of a Xenakis Sieve, constructed from a string notation of one or more Residual classes combined with logical operators. This Rust implementation follows the Python implementation in Ariza (2005), with significant performance and interface enhancements: https://direct.mit.edu/comj/article/29/2/40/93957
-#[derive(Clone, Debug)]
+///
+/// Sieves are compared and hashed structurally: two Sieves are equal if their trees of Residuals and operators are the same, even though differently structured Sieves may contain the same values.
+///
+/// ```
+/// use xensieve::Sieve;
+/// assert_eq!(Sieve::new("3@0|4@1"), Sieve::new("3@3 | 4@5"));
+/// assert_ne!(Sieve::new("3@0|4@1"), Sieve::new("4@1|3@0"));
+/// ```
+#[derive(Clone)]
 pub struct Sieve {
     root: SieveNode,
+    program: Option<Arc<program::Program>>,
+    memo: Option<Arc<memo::Memo>>,
+}
+
+impl PartialEq for Sieve {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root
+    }
+}
+
+impl Eq for Sieve {}
+
+impl Hash for Sieve {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.root.hash(state);
+    }
+}
+
+impl fmt::Debug for Sieve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sieve").field("root", &self.root).finish()
+    }
 }
 
 impl BitAnd for Sieve {
     type Output = Sieve;
 
     fn bitand(self, rhs: Self) -> Self::Output {
-        Sieve {
-            root: SieveNode::Intersection(Box::new(self.root), Box::new(rhs.root)),
-        }
+        Sieve::join(self, rhs, program::Instruction::Intersection)
     }
 }
 
@@ -158,72 +840,185 @@ impl BitAnd for &Sieve {
     type Output = Sieve;
 
     fn bitand(self, rhs: Self) -> Self::Output {
-        Sieve {
-            root: SieveNode::Intersection(Box::new(self.root.clone()), Box::new(rhs.root.clone())),
-        }
+        Sieve::join(
+            self.clone(),
+            rhs.clone(),
+            program::Instruction::Intersection,
+        )
     }
 }
 
-impl BitOr for Sieve {
+impl BitAnd<&Sieve> for Sieve {
     type Output = Sieve;
 
-    fn bitor(self, rhs: Self) -> Self::Output {
-        Sieve {
-            root: SieveNode::Union(Box::new(self.root), Box::new(rhs.root)),
-        }
+    fn bitand(self, rhs: &Sieve) -> Self::Output {
+        Sieve::join(self, rhs.clone(), program::Instruction::Intersection)
     }
 }
 
-impl BitOr for &Sieve {
+impl BitAnd<Sieve> for &Sieve {
     type Output = Sieve;
 
-    fn bitor(self, rhs: Self) -> Self::Output {
-        Sieve {
-            root: SieveNode::Union(Box::new(self.root.clone()), Box::new(rhs.root.clone())),
-        }
+    fn bitand(self, rhs: Sieve) -> Self::Output {
+        Sieve::join(self.clone(), rhs, program::Instruction::Intersection)
     }
 }
 
-impl BitXor for Sieve {
+impl BitOr for Sieve {
     type Output = Sieve;
 
-    fn bitxor(self, rhs: Self) -> Self::Output {
-        Sieve {
-            root: SieveNode::SymmetricDifference(Box::new(self.root), Box::new(rhs.root)),
-        }
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Sieve::join(self, rhs, program::Instruction::Union)
     }
 }
 
-impl BitXor for &Sieve {
+impl BitOr for &Sieve {
     type Output = Sieve;
 
-    fn bitxor(self, rhs: Self) -> Self::Output {
-        Sieve {
-            root: SieveNode::SymmetricDifference(
-                Box::new(self.root.clone()),
-                Box::new(rhs.root.clone()),
-            ),
-        }
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Sieve::join(self.clone(), rhs.clone(), program::Instruction::Union)
     }
 }
 
-impl Not for Sieve {
+impl BitOr<&Sieve> for Sieve {
     type Output = Sieve;
 
-    fn not(self) -> Self::Output {
-        Sieve {
-            root: SieveNode::Inversion(Box::new(self.root)),
-        }
+    fn bitor(self, rhs: &Sieve) -> Self::Output {
+        Sieve::join(self, rhs.clone(), program::Instruction::Union)
     }
 }
 
-impl Not for &Sieve {
+impl BitOr<Sieve> for &Sieve {
+    type Output = Sieve;
+
+    fn bitor(self, rhs: Sieve) -> Self::Output {
+        Sieve::join(self.clone(), rhs, program::Instruction::Union)
+    }
+}
+
+impl BitXor for Sieve {
+    type Output = Sieve;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Sieve::join(self, rhs, program::Instruction::SymmetricDifference)
+    }
+}
+
+impl BitXor for &Sieve {
+    type Output = Sieve;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Sieve::join(
+            self.clone(),
+            rhs.clone(),
+            program::Instruction::SymmetricDifference,
+        )
+    }
+}
+
+impl BitXor<&Sieve> for Sieve {
+    type Output = Sieve;
+
+    fn bitxor(self, rhs: &Sieve) -> Self::Output {
+        Sieve::join(self, rhs.clone(), program::Instruction::SymmetricDifference)
+    }
+}
+
+impl BitXor<Sieve> for &Sieve {
+    type Output = Sieve;
+
+    fn bitxor(self, rhs: Sieve) -> Self::Output {
+        Sieve::join(self.clone(), rhs, program::Instruction::SymmetricDifference)
+    }
+}
+
+impl Sub for Sieve {
+    type Output = Sieve;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Sieve::join(self, rhs, program::Instruction::Difference)
+    }
+}
+
+impl Sub for &Sieve {
+    type Output = Sieve;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Sieve::join(self.clone(), rhs.clone(), program::Instruction::Difference)
+    }
+}
+
+impl Sub<&Sieve> for Sieve {
+    type Output = Sieve;
+
+    fn sub(self, rhs: &Sieve) -> Self::Output {
+        Sieve::join(self, rhs.clone(), program::Instruction::Difference)
+    }
+}
+
+impl Sub<Sieve> for &Sieve {
+    type Output = Sieve;
+
+    fn sub(self, rhs: Sieve) -> Self::Output {
+        Sieve::join(self.clone(), rhs, program::Instruction::Difference)
+    }
+}
+
+impl Not for Sieve {
     type Output = Sieve;
 
     fn not(self) -> Self::Output {
-        Sieve {
-            root: SieveNode::Inversion(Box::new(self.root.clone())),
-        }
+        Sieve::invert(self)
+    }
+}
+
+impl Not for &Sieve {
+    type Output = Sieve;
+
+    fn not(self) -> Self::Output {
+        Sieve::invert(self.clone())
+    }
+}
+
+impl BitAndAssign for Sieve {
+    fn bitand_assign(&mut self, rhs: Self) {
+        let lhs = std::mem::take(self);
+        *self = Sieve::join(lhs, rhs, program::Instruction::Intersection);
+    }
+}
+
+impl BitAndAssign<&Sieve> for Sieve {
+    fn bitand_assign(&mut self, rhs: &Sieve) {
+        let lhs = std::mem::take(self);
+        *self = Sieve::join(lhs, rhs.clone(), program::Instruction::Intersection);
+    }
+}
+
+impl BitOrAssign for Sieve {
+    fn bitor_assign(&mut self, rhs: Self) {
+        let lhs = std::mem::take(self);
+        *self = Sieve::join(lhs, rhs, program::Instruction::Union);
+    }
+}
+
+impl BitOrAssign<&Sieve> for Sieve {
+    fn bitor_assign(&mut self, rhs: &Sieve) {
+        let lhs = std::mem::take(self);
+        *self = Sieve::join(lhs, rhs.clone(), program::Instruction::Union);
+    }
+}
+
+impl BitXorAssign for Sieve {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        let lhs = std::mem::take(self);
+        *self = Sieve::join(lhs, rhs, program::Instruction::SymmetricDifference);
+    }
+}
+
+impl BitXorAssign<&Sieve> for Sieve {
+    fn bitxor_assign(&mut self, rhs: &Sieve) {
+        let lhs = std::mem::take(self);
+        *self = Sieve::join(lhs, rhs.clone(), program::Instruction::SymmetricDifference);
     }
 }
 
@@ -233,6 +1028,43 @@ impl fmt::Display for Sieve {
     }
 }
 
+impl From<Residual> for Sieve {
+    fn from(residual: Residual) -> Self {
+        Self::from_node(SieveNode::Unit(residual))
+    }
+}
+
+impl FromStr for Sieve {
+    type Err = SieveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Sieve::try_new(s)
+    }
+}
+
+impl TryFrom<&str> for Sieve {
+    type Error = SieveError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Sieve::try_new(value)
+    }
+}
+
+impl TryFrom<String> for Sieve {
+    type Error = SieveError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Sieve::try_new(&value)
+    }
+}
+
+/// The default Sieve is the empty Sieve, `0@0`.
+impl Default for Sieve {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
 impl Sieve {
     /// Construct a Xenakis Sieve from a string representation.
     ///
@@ -240,440 +1072,3762 @@ impl Sieve {
     /// let s = xensieve::Sieve::new("3@0|5@1");
     /// assert_eq!(s.iter_value(0..15).collect::<Vec<_>>(), vec![0, 1, 3, 6, 9, 11, 12])
     /// ````
+    ///
+    /// # Panics
+    /// Panics if the string cannot be parsed; use `try_new` to handle malformed notation.
     pub fn new(value: &str) -> Self {
+        match Self::try_new(value) {
+            Ok(s) => s,
+            Err(e) => panic!("Invalid syntax: {e}"),
+        }
+    }
+
+    /// Construct a Xenakis Sieve from a string representation, returning a SieveError if the string cannot be parsed. The string may be given in the `Sieve{...}` form produced by `Display`.
+    ///
+    /// ```
+    /// use xensieve::{Sieve, SieveError};
+    /// assert!(Sieve::try_new("3@0|5@1").is_ok());
+    /// assert_eq!(Sieve::try_new("(3@0|5@1").unwrap_err(), SieveError::UnbalancedParentheses);
+    /// assert_eq!(Sieve::try_new("3@0|").unwrap_err(), SieveError::MissingOperand);
+    /// ```
+    ///
+    /// Digits may be grouped with underscores; other separators, and numbers beyond the range of `u64`, are rejected.
+    /// ```
+    /// use xensieve::{Sieve, SieveError};
+    /// assert_eq!(Sieve::new("1_000_003@17").to_string(), "Sieve{1000003@17}");
+    /// assert_eq!(Sieve::try_new("1,000@3").unwrap_err(), SieveError::DigitSeparator(','));
+    /// assert_eq!(Sieve::try_new("3@99999999999999999999").unwrap_err(), SieveError::Overflow("99999999999999999999".to_string()));
+    /// ```
+    ///
+    /// A modulus or shift may be a hexadecimal literal, or arithmetic with `+`, `-`, and `*` in parentheses, evaluated when parsed.
+    /// ```
+    /// use xensieve::Sieve;
+    /// assert_eq!(Sieve::new("(2*3*5)@(4+3)|0x10@3").to_string(), "Sieve{30@7|16@3}");
+    /// ```
+    pub fn try_new(value: &str) -> Result<Self, SieveError> {
         let mut stack: Vec<Self> = Vec::new();
-        for token in parser::infix_to_postfix(value).expect("Parsing failure") {
+        let expr = parser::evaluate_literals(parser::strip_display(value))?;
+        for token in parser::infix_to_postfix(&expr)? {
             match token.as_str() {
                 "!" => {
-                    let s = stack.pop().expect("Invalid syntax: missing operand");
+                    let s = stack.pop().ok_or(SieveError::MissingOperand)?;
                     stack.push(!s);
                 }
                 "&" => {
-                    let right = stack.pop().expect("Invalid syntax: missing operand");
-                    let left = stack.pop().expect("Invalid syntax: missing operand");
+                    let right = stack.pop().ok_or(SieveError::MissingOperand)?;
+                    let left = stack.pop().ok_or(SieveError::MissingOperand)?;
                     stack.push(left & right);
                 }
                 "^" => {
-                    let right = stack.pop().expect("Invalid syntax: missing operand");
-                    let left = stack.pop().expect("Invalid syntax: missing operand");
+                    let right = stack.pop().ok_or(SieveError::MissingOperand)?;
+                    let left = stack.pop().ok_or(SieveError::MissingOperand)?;
                     stack.push(left ^ right);
                 }
                 "|" => {
-                    let right = stack.pop().expect("Invalid syntax: missing operand");
-                    let left = stack.pop().expect("Invalid syntax: missing operand");
+                    let right = stack.pop().ok_or(SieveError::MissingOperand)?;
+                    let left = stack.pop().ok_or(SieveError::MissingOperand)?;
                     stack.push(left | right);
                 }
+                "-" => {
+                    let right = stack.pop().ok_or(SieveError::MissingOperand)?;
+                    let left = stack.pop().ok_or(SieveError::MissingOperand)?;
+                    stack.push(left - right);
+                }
                 operand => {
-                    let (m, s) = parser::residual_to_ints(operand)
-                        .expect("Invalid syntax: cannot parse Residual");
+                    let (m, s) = parser::residual_to_ints(operand)?;
                     let r = Residual::new(m, s);
-                    let s = Self {
-                        root: SieveNode::Unit(r),
-                    };
+                    let s = Self::from_node(SieveNode::Unit(r));
                     stack.push(s);
                 }
             }
         }
-        stack.pop().expect("Invalid syntax: no result")
+        stack.pop().ok_or(SieveError::Empty)
     }
 
-    /// Return `true` if the value is contained with this Sieve.
+    /// Construct a Sieve from a node, flattening it into a Program.
+    pub(crate) fn from_node(root: SieveNode) -> Self {
+        Self {
+            program: program::Program::new(&root).map(Arc::new),
+            memo: None,
+            root,
+        }
+    }
+
+    /// Combine two sieves by a binary operator, extending the Program of `lhs` rather than flattening the combined tree anew.
+    fn join(lhs: Self, rhs: Self, instruction: program::Instruction) -> Self {
+        let program = match (lhs.program, &rhs.program) {
+            (Some(a), Some(b)) => {
+                let a = Arc::try_unwrap(a).unwrap_or_else(|a| (*a).clone());
+                program::Program::join(a, b, instruction).map(Arc::new)
+            }
+            _ => None,
+        };
+        #[cfg(feature = "warnings")]
+        if instruction == program::Instruction::Intersection
+            && log::log_enabled!(log::Level::Warn)
+            && SieveNode::collapses(&lhs.root, &rhs.root)
+        {
+            log::warn!(
+                "the intersection of {} and {} has no members",
+                lhs.root,
+                rhs.root
+            );
+        }
+        let (l, r) = (Box::new(lhs.root), Box::new(rhs.root));
+        let root = match instruction {
+            program::Instruction::Intersection => SieveNode::Intersection(l, r),
+            program::Instruction::Union => SieveNode::Union(l, r),
+            program::Instruction::SymmetricDifference => SieveNode::SymmetricDifference(l, r),
+            _ => SieveNode::Difference(l, r),
+        };
+        Self {
+            program,
+            memo: None,
+            root,
+        }
+    }
+
+    /// Invert a sieve, extending its Program.
+    fn invert(part: Self) -> Self {
+        let program = part.program.map(|p| {
+            let p = Arc::try_unwrap(p).unwrap_or_else(|p| (*p).clone());
+            Arc::new(program::Program::invert(p))
+        });
+        Self {
+            program,
+            memo: None,
+            root: SieveNode::Inversion(Box::new(part.root)),
+        }
+    }
+
+    /// Construct a Sieve of a single Residual class.
     ///
     /// ```
-    /// let s = xensieve::Sieve::new("3@0 & 5@0");
-    /// assert_eq!(s.contains(15), true);
-    /// assert_eq!(s.contains(16), false);
-    /// assert_eq!(s.contains(30), true);
+    /// let s = xensieve::Sieve::unit(3, 1);
+    /// assert_eq!(s.to_string(), "Sieve{3@1}");
     /// ```
-    pub fn contains(&self, value: i128) -> bool {
-        self.root.contains(value)
+    pub fn unit(modulus: u64, shift: u64) -> Self {
+        Residual::new(modulus, shift).into()
     }
 
-    /// For the iterator provided as an input, iterate the subset of values that are contained within the sieve.
+    /// Construct the empty Sieve, `0@0`, which contains no values.
+    ///
     /// ```
-    /// let s = xensieve::Sieve::new("3@0|4@0");
-    /// assert_eq!(s.iter_value(0..=12).collect::<Vec<_>>(), vec![0, 3, 4, 6, 8, 9, 12])
-    /// ````
-    pub fn iter_value(
-        &self,
-        iterator: impl Iterator<Item = i128>,
-    ) -> IterValue<impl Iterator<Item = i128>> {
-        // NOTE: do not want to clone self here...
-        IterValue {
-            iterator,
-            sieve_node: self.root.clone(),
-        }
+    /// let s = xensieve::Sieve::empty();
+    /// assert_eq!(s.iter_value(-10..10).count(), 0);
+    /// ```
+    pub fn empty() -> Self {
+        Self::unit(0, 0)
     }
 
-    /// For the iterator provided as an input, iterate the Boolean status of contained.
+    /// Construct the Sieve `1@0`, which contains all values.
+    ///
     /// ```
-    /// let s = xensieve::Sieve::new("3@0|4@0");
-    /// assert_eq!(s.iter_state(0..=6).collect::<Vec<_>>(), vec![true, false, false, true, true, false, true])
-    /// ````
-    pub fn iter_state(
-        &self,
-        iterator: impl Iterator<Item = i128>,
-    ) -> IterState<impl Iterator<Item = i128>> {
-        IterState {
-            iterator,
-            sieve_node: self.root.clone(),
-        }
+    /// let s = xensieve::Sieve::all();
+    /// assert_eq!(s.iter_value(-10..10).count(), 20);
+    /// ```
+    pub fn all() -> Self {
+        Self::unit(1, 0)
     }
 
-    /// Iterate over integer intervals between values in the sieve.
+    /// Construct a Sieve as the union of the Residual classes given as pairs of modulus and shift, in the order provided. An empty input produces the empty Sieve.
+    ///
     /// ```
-    /// let s = xensieve::Sieve::new("3@0|4@0");
-    /// assert_eq!(s.iter_interval(0..=12).collect::<Vec<_>>(), vec![3, 1, 2, 2, 1, 3])
-    /// ````
-    pub fn iter_interval(
-        &self,
-        iterator: impl Iterator<Item = i128>,
-    ) -> IterInterval<impl Iterator<Item = i128>> {
-        IterInterval {
-            iterator,
-            sieve_node: self.root.clone(),
-            last: PositionLast::Init,
-        }
+    /// let s = xensieve::Sieve::from_residuals(vec![(3, 0), (5, 1)]);
+    /// assert_eq!(s.to_string(), "Sieve{3@0|5@1}");
+    /// ```
+    pub fn from_residuals(residuals: impl IntoIterator<Item = (u64, u64)>) -> Self {
+        residuals
+            .into_iter()
+            .map(|(m, s)| Self::unit(m, s))
+            .reduce(|lhs, rhs| lhs | rhs)
+            .unwrap_or_else(Self::empty)
     }
-}
 
-//------------------------------------------------------------------------------
+    /// Construct a Sieve whose members, repeating every `period`, are the provided values, by the compression by residual classes of Ariza (2005). Values are taken modulo the period; each value not yet covered selects the Residual of the smallest modulus, dividing the period, that contains it and whose members are all values, and Residuals made redundant by later selections are removed. The result is not always the fewest Residuals (see `minimize`). An empty input produces the empty (`0@0`) Sieve.
+    /// ```
+    /// let s = xensieve::Sieve::from_values(vec![0, 3, 4, 6, 8, 9, 12, 15], 12);
+    /// assert_eq!(s.to_string(), "Sieve{3@0|4@0}");
+    /// let s = xensieve::Sieve::from_values(vec![0, 2, 4, 5, 7, 9, 11], 12);
+    /// assert_eq!(s.to_string(), "Sieve{12@0|12@2|12@4|6@5|12@7|12@9}");
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `period` is zero.
+    pub fn from_values(values: impl IntoIterator<Item = i128>, period: u64) -> Self {
+        assert!(period > 0, "period must be greater than zero");
+        let mut members: Vec<u64> = values
+            .into_iter()
+            .map(|v| v.rem_euclid(period as i128) as u64)
+            .collect();
+        members.sort_unstable();
+        members.dedup();
+        Self::from_residuals(
+            compress::compress(period, &members)
+                .into_iter()
+                .map(|r| (r.modulus, r.shift)),
+        )
+    }
 
-/// The iterator returned by `iter_value`.
-/// ```
-/// let s = xensieve::Sieve::new("3@0|4@0");
-/// let mut s_iter = s.iter_value(17..);
-/// assert_eq!(s_iter.next().unwrap(), 18);
-/// assert_eq!(s_iter.next().unwrap(), 20);
-/// ```
-pub struct IterValue<I>
-where
-    I: Iterator<Item = i128>,
-{
-    iterator: I,
-    sieve_node: SieveNode,
-}
+    /// Construct a simple Sieve approximating the provided values, within the window from the smallest to the largest value, such that no more than `max_extra` members of the window are not values, and no more than `max_missing` values are not members. Residuals are chosen greedily, each covering the most remaining values within the error budget, trading exactness for fewer Residuals; the result is not always the simplest possible. An empty input produces the empty (`0@0`) Sieve. As the search is quadratic in the window, the window may span no more than `FIT_SPAN_LIMIT` (4096) values; use `try_fit` to handle a wider window as an error.
+    /// ```
+    /// // a pulse of 3 with one onset displaced
+    /// let values = vec![0, 3, 6, 10, 12, 15];
+    /// assert_eq!(xensieve::Sieve::fit(values.clone(), 0, 0).to_string(), "Sieve{6@0|7@3|9@6}");
+    /// assert_eq!(xensieve::Sieve::fit(values.clone(), 1, 1).to_string(), "Sieve{3@0}");
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the window spans more than `FIT_SPAN_LIMIT` values.
+    pub fn fit(
+        values: impl IntoIterator<Item = i128>,
+        max_extra: usize,
+        max_missing: usize,
+    ) -> Self {
+        Self::try_fit(values, max_extra, max_missing).unwrap_or_else(|e| panic!("{e}"))
+    }
 
-impl<I> Iterator for IterValue<I>
-where
-    I: Iterator<Item = i128>,
-{
-    type Item = i128;
+    /// Construct a simple Sieve approximating the provided values, as `fit`, returning a SieveError if the window from the smallest to the largest value spans more than `FIT_SPAN_LIMIT` (4096) values.
+    /// ```
+    /// use xensieve::{Sieve, SieveError};
+    /// assert_eq!(Sieve::try_fit(vec![0, 4, 8], 0, 0).unwrap().to_string(), "Sieve{4@0}");
+    /// assert_eq!(
+    ///     Sieve::try_fit(vec![0, 1 << 40], 0, 0).unwrap_err(),
+    ///     SieveError::WindowTooLarge { span: (1 << 40) + 1, limit: 4096 }
+    /// );
+    /// ```
+    pub fn try_fit(
+        values: impl IntoIterator<Item = i128>,
+        max_extra: usize,
+        max_missing: usize,
+    ) -> Result<Self, SieveError> {
+        let mut values: Vec<i128> = values.into_iter().collect();
+        values.sort_unstable();
+        values.dedup();
+        let (start, end) = match (values.first(), values.last()) {
+            (Some(s), Some(e)) => (*s, *e),
+            _ => return Ok(Self::empty()),
+        };
+        let span = end.abs_diff(start);
+        if span >= FIT_SPAN_LIMIT as u128 {
+            return Err(SieveError::WindowTooLarge {
+                span: span.saturating_add(1),
+                limit: FIT_SPAN_LIMIT,
+            });
+        }
+        let mut target = vec![false; span as usize + 1];
+        for v in values.iter() {
+            target[v.abs_diff(start) as usize] = true;
+        }
+        Ok(Self::from_residuals(
+            fit::fit(start, &target, max_extra, max_missing)
+                .into_iter()
+                .map(|r| (r.modulus, r.shift)),
+        ))
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iterator
-            .by_ref()
-            .find(|&p| self.sieve_node.contains(p))
+    /// Construct a Sieve from successive intervals, starting from `start`, that together span one period, compressed into Residuals as `from_values`.
+    /// ```
+    /// let major = xensieve::Sieve::from_intervals(0, &[2, 2, 1, 2, 2, 2, 1], 12);
+    /// assert_eq!(major.iter_value(0..13).collect::<Vec<_>>(), vec![0, 2, 4, 5, 7, 9, 11, 12]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if an interval is not positive, or if the intervals do not sum to the period.
+    pub fn from_intervals(start: i128, intervals: &[u64], period: u64) -> Self {
+        assert!(
+            intervals.iter().all(|i| *i > 0),
+            "intervals must be greater than zero"
+        );
+        assert!(
+            intervals.iter().map(|i| *i as u128).sum::<u128>() == period as u128,
+            "intervals must sum to the period"
+        );
+        let mut points = Vec::with_capacity(intervals.len());
+        let mut p = start;
+        for i in intervals {
+            points.push(p);
+            p += *i as i128;
+        }
+        Self::from_values(points, period)
     }
-}
 
-//------------------------------------------------------------------------------
+    /// Construct a Sieve from intervals, such as those produced by `iter_interval`, between successive members starting from the member `anchor`, compressed into Residuals of the provided period as `from_values`. Unlike `from_intervals`, the intervals may span any number of values; with the first member of the iterated range as the anchor, this inverts `iter_interval`.
+    /// ```
+    /// let s1 = xensieve::Sieve::new("(5@0|4@2)&!(30@10)");
+    /// let intervals = s1.iter_interval(0..60);
+    /// let s2 = xensieve::Sieve::from_iter_interval(0, intervals, 60);
+    /// assert!(s2.is_equivalent(&s1));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `period` is zero.
+    pub fn from_iter_interval(
+        anchor: i128,
+        intervals: impl IntoIterator<Item = i128>,
+        period: u64,
+    ) -> Self {
+        let points = std::iter::once(anchor).chain(intervals.into_iter().scan(anchor, |p, i| {
+            *p += i;
+            Some(*p)
+        }));
+        Self::from_values(points, period)
+    }
 
-/// The iterator returned by `iter_state`.
-/// ```
-/// let s = xensieve::Sieve::new("3@0|4@0");
-/// let mut s_iter = s.iter_state(17..);
-/// assert_eq!(s_iter.next().unwrap(), false);
-/// assert_eq!(s_iter.next().unwrap(), true);
-/// assert_eq!(s_iter.next().unwrap(), false);
-/// assert_eq!(s_iter.next().unwrap(), true);
-/// ```
-pub struct IterState<I>
-where
-    I: Iterator<Item = i128>,
-{
-    iterator: I,
-    sieve_node: SieveNode,
-}
+    /// Construct a Sieve by concatenating finite segments end to end, such as short rhythmic cells, into one period, compressed into Residuals as `from_values`. The values of each segment are counted from zero, and each segment spans one more than its greatest value; each segment is offset by the summed spans of the segments before it, and the period is the sum of all spans. An empty segment spans nothing; if all segments are empty, the empty (`0@0`) Sieve is produced.
+    /// ```
+    /// let s = xensieve::Sieve::from_segments(&[&[0, 2], &[0, 1, 3]]);
+    /// assert_eq!(s.iter_value(0..14).collect::<Vec<_>>(), vec![0, 2, 3, 4, 6, 7, 9, 10, 11, 13]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if a value is negative, or if the period overflows.
+    pub fn from_segments<T: NumericElement>(segments: &[&[T]]) -> Self {
+        let mut points = Vec::new();
+        let mut offset: u64 = 0;
+        for segment in segments {
+            let mut span = 0;
+            for v in segment.iter() {
+                let v = v.to_i128();
+                assert!(v >= 0, "segment values must not be negative");
+                let end = u64::try_from(v + 1).expect("period overflows");
+                points.push(offset as i128 + v);
+                span = span.max(end);
+            }
+            offset = offset.checked_add(span).expect("period overflows");
+        }
+        if offset == 0 {
+            return Self::empty();
+        }
+        Self::from_values(points, offset)
+    }
 
-impl<I> Iterator for IterState<I>
-where
-    I: Iterator<Item = i128>, // the values returned by iterator
-{
-    type Item = bool; // the value returned
+    /// Construct a Sieve from one period of membership, where each `true` is a member, compressed into Residuals as `from_values`.
+    /// ```
+    /// let s = xensieve::Sieve::from_pattern(&[true, false, true, true, true, false]);
+    /// assert_eq!(s.to_string(), "Sieve{2@0|3@0}");
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the pattern is empty.
+    pub fn from_pattern(pattern: &[bool]) -> Self {
+        assert!(!pattern.is_empty(), "pattern must not be empty");
+        Self::from_values(
+            pattern
+                .iter()
+                .enumerate()
+                .filter(|(_, m)| **m)
+                .map(|(i, _)| i as i128),
+            pattern.len() as u64,
+        )
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.iterator.next() {
-            Some(p) => Some(self.sieve_node.contains(p)),
-            None => None,
+    /// Construct a Sieve from a string of one period of membership, in the style of a drum machine, where `1` or `x` is a member and `0`, `.`, or `-` is not; whitespace is ignored.
+    /// ```
+    /// let s = xensieve::Sieve::from_pattern_str("x..x..x.").unwrap();
+    /// assert_eq!(s.to_string(), "Sieve{8@0|8@3|8@6}");
+    /// assert_eq!(xensieve::Sieve::from_pattern_str("1001 0010").unwrap(), s);
+    /// ```
+    pub fn from_pattern_str(pattern: &str) -> Result<Self, SieveError> {
+        let states = pattern
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| match c {
+                '1' | 'x' | 'X' => Ok(true),
+                '0' | '.' | '-' => Ok(false),
+                _ => Err(SieveError::UnknownToken(c)),
+            })
+            .collect::<Result<Vec<bool>, SieveError>>()?;
+        if states.is_empty() {
+            return Err(SieveError::Empty);
         }
+        Ok(Self::from_pattern(&states))
     }
-}
 
-//------------------------------------------------------------------------------
+    /// Construct a Sieve, of period 12, of the major scale starting from the pitch class `shift`.
+    /// ```
+    /// let s = xensieve::Sieve::major_scale(2);
+    /// assert_eq!(s.iter_value(0..12).collect::<Vec<_>>(), vec![1, 2, 4, 6, 7, 9, 11]);
+    /// ```
+    pub fn major_scale(shift: u64) -> Self {
+        Self::from_intervals(shift as i128, &scale::MAJOR, 12)
+    }
 
-enum PositionLast {
-    Init,
-    Value(i128),
-}
+    /// Construct a Sieve, of period 12, of the natural minor scale starting from the pitch class `shift`.
+    pub fn natural_minor(shift: u64) -> Self {
+        Self::from_intervals(shift as i128, &scale::NATURAL_MINOR, 12)
+    }
 
-/// The iterator returned by `iter_interval`.
-/// ```
-/// let s = xensieve::Sieve::new("3@0|4@0");
-/// let mut s_iter = s.iter_interval(17..);
-/// assert_eq!(s_iter.next().unwrap(), 2);
-/// assert_eq!(s_iter.next().unwrap(), 1);
-/// assert_eq!(s_iter.next().unwrap(), 3);
+    /// Construct a Sieve, of period 12, of the harmonic minor scale starting from the pitch class `shift`.
+    pub fn harmonic_minor(shift: u64) -> Self {
+        Self::from_intervals(shift as i128, &scale::HARMONIC_MINOR, 12)
+    }
+
+    /// Construct a Sieve, of period 12, of the ascending melodic minor scale starting from the pitch class `shift`.
+    /// ```
+    /// let s = xensieve::Sieve::melodic_minor(0);
+    /// assert_eq!(s.iter_value(0..12).collect::<Vec<_>>(), vec![0, 2, 3, 5, 7, 9, 11]);
+    /// ```
+    pub fn melodic_minor(shift: u64) -> Self {
+        Self::from_intervals(shift as i128, &scale::MELODIC_MINOR, 12)
+    }
+
+    /// Construct a Sieve, of period 12, of Messiaen's mode of limited transposition `n`, from 1 to 7, starting from the pitch class `shift`. As the modes are symmetric, their Residuals have moduli smaller than 12.
+    /// ```
+    /// assert_eq!(xensieve::Sieve::messiaen_mode(1, 1).to_string(), "Sieve{2@1}");
+    /// assert_eq!(xensieve::Sieve::messiaen_mode(2, 0).to_string(), "Sieve{3@0|3@1}");
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `n` is not from 1 to 7.
+    pub fn messiaen_mode(n: usize, shift: u64) -> Self {
+        assert!((1..=7).contains(&n), "mode must be from 1 to 7");
+        Self::from_intervals(shift as i128, scale::MESSIAEN_MODES[n - 1], 12)
+    }
+
+    /// Return the primes less than or equal to `n`. As the primes are not periodic, they are returned as a realized set rather than a Sieve; see `coprime_to` for a periodic approximation.
+    /// ```
+    /// assert_eq!(xensieve::Sieve::primes_up_to(20), vec![2, 3, 5, 7, 11, 13, 17, 19]);
+    /// ```
+    pub fn primes_up_to(n: u64) -> Vec<u64> {
+        util::primes(n)
+    }
+
+    /// Construct a Sieve of the values coprime to every one of the provided moduli: the complement of the union of the multiples of their prime factors.
+    /// ```
+    /// let s = xensieve::Sieve::coprime_to(&[6, 5]);
+    /// assert_eq!(s.to_string(), "Sieve{!(2@0|3@0|5@0)}");
+    /// assert_eq!(s.iter_value(0..30).collect::<Vec<_>>(), vec![1, 7, 11, 13, 17, 19, 23, 29]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if a modulus is zero.
+    pub fn coprime_to(moduli: &[u64]) -> Self {
+        assert!(
+            moduli.iter().all(|m| *m > 0),
+            "moduli must be greater than zero"
+        );
+        let mut factors: Vec<u64> = moduli
+            .iter()
+            .flat_map(|m| util::prime_factors(*m))
+            .collect();
+        factors.sort_unstable();
+        factors.dedup();
+        if factors.is_empty() {
+            return Self::all();
+        }
+        !Self::from_residuals(factors.into_iter().map(|p| (p, 0)))
+    }
+
+    /// Construct a Sieve of the factorization wheel of a basis of moduli: the union of the residues, modulo the least common multiple of the basis, coprime to every modulus. Unlike `coprime_to`, the Sieve is an explicit union of Residuals; see `Wheel` to iterate it by stepping between spokes.
+    /// ```
+    /// let s = xensieve::Sieve::wheel(&[2, 3]);
+    /// assert_eq!(s.to_string(), "Sieve{6@1|6@5}");
+    /// assert!(s.is_equivalent(&xensieve::Sieve::coprime_to(&[2, 3])));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if a modulus is zero, or if the least common multiple overflows.
+    pub fn wheel(basis: &[u64]) -> Self {
+        Wheel::new(basis).to_sieve()
+    }
+
+    /// Construct a Sieve of the Euclidean rhythm of `pulses` onsets distributed as evenly as possible over a period of `steps`, by the algorithm of Bjorklund, with the onsets rotated later by `rotation` steps.
+    /// ```
+    /// let s = xensieve::Sieve::euclidean(3, 8, 0);
+    /// assert_eq!(s.iter_value(0..16).collect::<Vec<_>>(), vec![0, 3, 6, 8, 11, 14]);
+    /// let s = xensieve::Sieve::euclidean(3, 8, 2);
+    /// assert_eq!(s.iter_value(0..8).collect::<Vec<_>>(), vec![0, 2, 5]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `steps` is zero, or if `pulses` is greater than `steps`.
+    pub fn euclidean(pulses: u64, steps: u64, rotation: u64) -> Self {
+        assert!(steps > 0, "steps must be greater than zero");
+        assert!(pulses <= steps, "pulses must not be greater than steps");
+        Self::from_pattern(&rhythm::bjorklund(pulses, steps)).translate((rotation % steps) as i128)
+    }
+
+    /// Construct a Sieve as the union of one Residual of the provided period per value. An empty input produces the empty (`0@0`) Sieve.
+    pub(crate) fn from_points(values: impl IntoIterator<Item = i128>, period: u64) -> Self {
+        assert!(period > 0, "period must be greater than zero");
+        let mut shifts: Vec<u64> = values
+            .into_iter()
+            .map(|v| v.rem_euclid(period as i128) as u64)
+            .collect();
+        shifts.sort_unstable();
+        shifts.dedup();
+        Self::from_residuals(shifts.into_iter().map(|s| (period, s)))
+    }
+
+    /// Return a sieve whose members are the members of this sieve plus `offset`, shifting every Residual.
+    /// ```
+    /// let s = xensieve::Sieve::new("(5@0|4@2)&!(30@10)");
+    /// assert_eq!(s.translate(3).to_string(), "Sieve{(5@3|4@1)&!(30@13)}");
+    /// assert_eq!(s.translate(-7).contains(-7), true);
+    /// ```
+    pub fn translate(&self, offset: i128) -> Self {
+        Self::from_node(self.root.translate(offset))
+    }
+
+    /// Construct a Piecewise where this sieve is followed, from position `at`, by `other`, translated such that its first non-negative member lands exactly at `at`. If `other` has no members, it is not translated.
+    /// ```
+    /// let a = xensieve::Sieve::new("3@0");
+    /// let b = xensieve::Sieve::new("4@1");
+    /// let p = a.append(&b, 10);
+    /// assert_eq!(p.scan(0..20), vec![0, 3, 6, 9, 10, 14, 18]);
+    /// ```
+    pub fn append(&self, other: &Sieve, at: i128) -> Piecewise {
+        Piecewise::new(self.clone()).append(other, at)
+    }
+
+    /// Return an ordered transcript of the evaluation of the value by this sieve, for demonstrating how sieve formulas work: each Residual test with its division, and each operation combining the results of its operands. Unlike `contains`, every operand is evaluated. The result of the last step is the membership of the value.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0&!(4@1)");
+    /// let steps = s.evaluate_verbose(9);
+    /// assert_eq!(steps[0].to_string(), "9 = 3 * 3 + 0; 0 == 0, so 9 is in 3@0");
+    /// assert_eq!(steps[3].to_string(), "3@0&!(4@1): true and false is false");
+    /// assert_eq!(steps.len(), 4);
+    /// ```
+    pub fn evaluate_verbose(&self, value: i128) -> Vec<Step> {
+        let mut steps = Vec::new();
+        step::evaluate(&self.root, value, &mut steps);
+        steps
+    }
+
+    /// Return a proof of the membership, or non-membership, of the value: the Congruence of the value for each Residual, in the structure of this sieve. The proof can be checked with `verify` without this sieve.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@1");
+    /// let p = s.prove(13);
+    /// assert_eq!(p.member, true);
+    /// let c = p.congruences();
+    /// assert_eq!((c[0].quotient, c[0].remainder, c[0].holds()), (4, 1, false));
+    /// assert_eq!((c[1].quotient, c[1].remainder, c[1].holds()), (3, 1, true));
+    /// assert!(xensieve::verify(&p));
+    /// ```
+    pub fn prove(&self, value: i128) -> MembershipProof {
+        MembershipProof::new(&self.root, value)
+    }
+
+    /// Return a CompiledSieve storing one period of membership as a packed bitmask, for constant-time `contains` and fast iteration, such as at audio rate. The mask requires one bit per value of the period.
+    /// ```
+    /// let c = xensieve::Sieve::new("3@0|4@1").compile();
+    /// assert_eq!(c.period(), 12);
+    /// assert!(c.contains(1_000_000_000_005));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the period overflows, or is too large to allocate.
+    pub fn compile(&self) -> CompiledSieve {
+        CompiledSieve::new(self)
+    }
+
+    /// Return the membership of each value, as `contains`, evaluating the sieve one Residual at a time across all values rather than one value at a time through the tree, such that the modulo test of each Residual is a loop the compiler can vectorize. For large batches this is much faster than calling `contains` for each value. For large batches of a sieve of unions and intersections of Residuals, the Residuals are flattened into arrays of moduli and shifts and blocks of values are tested against all of them, without walking the tree.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@1");
+    /// assert_eq!(s.contains_batch(&[0u32, 1, 2, 3]), vec![true, true, false, true]);
+    /// ```
+    pub fn contains_batch<T: NumericElement>(&self, values: &[T]) -> Vec<bool> {
+        let mut post = vec![false; values.len()];
+        self.contains_batch_fill(values, &mut post);
+        post
+    }
+
+    /// Write the membership of each value into the corresponding position of `post`, as `contains_batch`, such that a caller's buffer can be reused between batches.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@1");
+    /// let mut post = [false; 4];
+    /// s.contains_batch_fill(&[-3i64, -2, 5, 6], &mut post);
+    /// assert_eq!(post, [true, false, true, true]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `values` and `post` differ in length.
+    pub fn contains_batch_fill<T: NumericElement>(&self, values: &[T], post: &mut [bool]) {
+        assert_eq!(
+            values.len(),
+            post.len(),
+            "values and post must have the same length"
+        );
+        let values: Vec<i128> = values.iter().map(|v| v.to_i128()).collect();
+        match self
+            .root
+            .progression_residuals()
+            .filter(|_| values.len() >= batch::CLASSES_MIN)
+        {
+            Some(residuals) => batch::Classes::new(&residuals).contains(&values, post),
+            None => batch::contains(&self.root, &values, post),
+        }
+    }
+
+    /// Return `true` if the value is contained with this Sieve.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0 & 5@0");
+    /// assert_eq!(s.contains(15), true);
+    /// assert_eq!(s.contains(16), false);
+    /// assert_eq!(s.contains(30), true);
+    /// ```
+    pub fn contains(&self, value: i128) -> bool {
+        match (&self.memo, &self.program) {
+            (Some(memo), _) => memo.contains(value),
+            (None, Some(program)) => program.contains(value),
+            (None, None) => self.root.contains(value),
+        }
+    }
+
+    /// Memoize this sieve for repeated `contains` calls: each node whose local period (the least common multiple of the moduli beneath it) is small stores a table of its membership over that period, such that it is answered by one lookup rather than by walking its subtree. Warming is transparent: results are unchanged, equality and hashing ignore it, and sieves produced from this sieve, such as by operators, are not warmed.
+    /// ```
+    /// let mut s = xensieve::Sieve::new("(5@0|4@2)&!(30@10)|65537@3");
+    /// assert!(!s.is_warm());
+    /// s.warm();
+    /// assert!(s.is_warm());
+    /// assert!(s.contains(65540));
+    /// assert!(!s.contains(10));
+    /// ```
+    pub fn warm(&mut self) {
+        self.memo = Some(Arc::new(memo::Memo::new(&self.root)));
+    }
+
+    /// Return `true` if this sieve has been memoized by `warm`.
+    pub fn is_warm(&self) -> bool {
+        self.memo.is_some()
+    }
+
+    /// Return the member nearest to the measurement `value` that is within `tolerance` of it, or `None` if there is no such member, or if `value` or `tolerance` is not finite, or `tolerance` is negative. Where two members are equally near, the lower is returned. The nearest members below and above `value` are found with `prev_below` and `next_above`, so the cost does not grow with the tolerance.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("4@0|4@1");
+    /// assert_eq!(s.quantize_approx(3.7, 0.5), Some(4));
+    /// assert_eq!(s.quantize_approx(2.5, 1.5), Some(1));
+    /// assert_eq!(s.quantize_approx(2.5, 0.5), None);
+    /// assert_eq!(s.quantize_approx(2.5, f64::INFINITY), None);
+    /// ```
+    pub fn quantize_approx(&self, value: f64, tolerance: f64) -> Option<i128> {
+        if !value.is_finite()
+            || !tolerance.is_finite()
+            || tolerance < 0.0
+            || value.abs() >= i128::MAX as f64
+        {
+            return None;
+        }
+        let (floor, ceil) = (value.floor() as i128, value.ceil() as i128);
+        let below = Some(floor)
+            .filter(|v| self.contains(*v))
+            .or_else(|| self.prev_below(floor));
+        let above = Some(ceil)
+            .filter(|v| self.contains(*v))
+            .or_else(|| self.next_above(ceil));
+        let distance = |v: i128| (v as f64 - value).abs();
+        let post = match (below, above) {
+            (Some(b), Some(a)) if distance(a) < distance(b) => a,
+            (Some(b), _) => b,
+            (None, Some(a)) => a,
+            (None, None) => return None,
+        };
+        let (lo, hi) = ((value - tolerance).ceil(), (value + tolerance).floor());
+        Some(post).filter(|v| lo <= *v as f64 && *v as f64 <= hi)
+    }
+
+    /// Return `true` if the measurement `value` is within `tolerance` of a member of this Sieve.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("4@0|4@1");
+    /// assert_eq!(s.contains_approx(4.98, 0.05), true);
+    /// assert_eq!(s.contains_approx(6.2, 0.5), false);
+    /// ```
+    pub fn contains_approx(&self, value: f64, tolerance: f64) -> bool {
+        self.quantize_approx(value, tolerance).is_some()
+    }
+
+    /// For the iterator provided as an input, iterate the subset of values that are contained within the sieve. The input may be of any integer type implementing `NumericElement`, and values are yielded in that type. Values are yielded in the order of the input iterator, and duplicate values are passed through; no sorting or deduplication is performed. For sieves of unions and intersections of Residuals, members are found by stepping the arithmetic progression of each Residual, such that ascending input between members is passed over with one comparison per value rather than an evaluation of the sieve. Where the input is double-ended, so is the iterator, such that members can be taken from the end with `rev`.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// assert_eq!(s.iter_value(0..=12).collect::<Vec<_>>(), vec![0, 3, 4, 6, 8, 9, 12]);
+    /// assert_eq!(s.iter_value(0..=12).rev().collect::<Vec<_>>(), vec![12, 9, 8, 6, 4, 3, 0]);
+    /// ````
+    pub fn iter_value<I>(&self, iterator: I) -> IterValue<I>
+    where
+        I: Iterator,
+        I::Item: NumericElement,
+    {
+        // NOTE: do not want to clone self here...
+        IterValue {
+            iterator,
+            sieve_node: self.root.clone(),
+            progressions: self
+                .root
+                .progression_residuals()
+                .map(progression::Progressions::new),
+        }
+    }
+
+    /// Iterate the members of this sieve within the range, in ascending order and in the element type of the range. Any standard range syntax may be used; an unbounded end extends to the limit of the element type. Members are found by stepping from one to the next rather than by testing every value of the range. Where the period is small, the number of members is counted when the iterator is created, such that its `size_hint` is exact.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// assert_eq!(s.values(0..=12).collect::<Vec<_>>(), vec![0, 3, 4, 6, 8, 9, 12]);
+    /// assert_eq!(s.values(250u8..).collect::<Vec<_>>(), vec![252, 255]);
+    /// assert_eq!(s.values(..-8i8).count(), 60);
+    /// assert_eq!(s.values(0..=12).size_hint(), (7, Some(7)));
+    /// ```
+    pub fn values<T: NumericElement>(&self, range: impl std::ops::RangeBounds<T>) -> Values<'_, T> {
+        Values::new(self, element::inclusive_bounds(&range))
+    }
+
+    /// Iterate the members in `[now, now + horizon)`, in ascending order, such as for a scheduler that repeatedly asks what happens in the next `horizon` ticks as time advances. For sieves of unions and intersections of Residuals, the first member of each Residual is found directly and the progressions are merged, such that the cost of each call depends on the number of Residuals and members, not on `now` or the span of the horizon; other sieves step from one member to the next, each search bounded to one period, such that the cost of each step depends on the period rather than the horizon.
+    /// ```
+    /// let s = xensieve::Sieve::new("5@0|4@1");
+    /// assert_eq!(s.iter_upcoming(1_000_000_000, 10).collect::<Vec<_>>(), vec![1_000_000_000, 1_000_000_001, 1_000_000_005, 1_000_000_009]);
+    /// ```
+    pub fn iter_upcoming(&self, now: i128, horizon: u64) -> impl Iterator<Item = i128> + '_ {
+        let end = now.saturating_add(horizon as i128);
+        let mut merge = self
+            .root
+            .progression_residuals()
+            .map(|residuals| progression::Merge::new(&residuals, now, end));
+        let mut pos = now;
+        std::iter::from_fn(move || match merge.as_mut() {
+            Some(merge) => merge.next(),
+            None => {
+                let v = self.root.next_member(pos, end)?;
+                pos = v + 1;
+                Some(v)
+            }
+        })
+    }
+
+    /// Iterate the maximal runs of adjacent members within the range, in ascending order, as pairs of the first member and the number of members of the run, such as for distinguishing clusters from isolated points. The first member of each run is found by stepping from one member to the next; runs are cut at the ends of the range.
+    /// ```
+    /// let s = xensieve::Sieve::new("12@0|12@1|12@2|12@7|12@11");
+    /// assert_eq!(s.iter_segment(0..24).collect::<Vec<_>>(), vec![(0, 3), (7, 1), (11, 4), (19, 1), (23, 1)]);
+    /// ```
+    pub fn iter_segment(
+        &self,
+        range: std::ops::Range<i128>,
+    ) -> impl Iterator<Item = (i128, u64)> + '_ {
+        let mut pos = range.start;
+        let end = range.end;
+        std::iter::from_fn(move || {
+            let first = self.root.next_member(pos, end)?;
+            let mut last = first;
+            while last + 1 < end && self.contains(last + 1) {
+                last += 1;
+            }
+            pos = last + 1;
+            Some((first, last.abs_diff(first) as u64 + 1))
+        })
+    }
+
+    /// Iterate the Boolean status of contained for each value of the range, in ascending order. Any standard range syntax may be used; an unbounded end extends to the limit of the element type. The `size_hint` of the iterator is exact where the length of the range fits in `usize`.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// assert_eq!(s.states(0..=6).collect::<Vec<_>>(), vec![true, false, false, true, true, false, true]);
+    /// assert_eq!(s.states(..3u8).collect::<Vec<_>>(), vec![true, false, false]);
+    /// assert_eq!(s.states(0..=6).size_hint(), (7, Some(7)));
+    /// ```
+    pub fn states<T: NumericElement>(&self, range: impl std::ops::RangeBounds<T>) -> States<'_> {
+        States {
+            sieve: self,
+            bounds: element::inclusive_bounds(&range),
+        }
+    }
+
+    /// For the iterator provided as an input, iterate the Boolean status of contained. One state is yielded for each input value, in the order of the input iterator.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// assert_eq!(s.iter_state(0..=6).collect::<Vec<_>>(), vec![true, false, false, true, true, false, true])
+    /// ````
+    pub fn iter_state<I>(&self, iterator: I) -> IterState<I>
+    where
+        I: Iterator,
+        I::Item: NumericElement,
+    {
+        IterState {
+            iterator,
+            sieve_node: self.root.clone(),
+        }
+    }
+
+    /// Iterate over integer intervals between values in the sieve. Intervals are taken between successive contained values in the order of the input iterator; a duplicated contained value yields an interval of zero. Intervals are signed: where the input descends, the interval is negative, such that a walk up and down through the sieve yields positive and then negative intervals. Intervals are yielded in the element type of the input; an interval not representable in that type, such as a negative interval of an unsigned type or an interval wider than the type's range, is saturated to the type's nearest limit rather than panicking, so descending unsigned input should use `iter_interval_abs`.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// assert_eq!(s.iter_interval(0..=12).collect::<Vec<_>>(), vec![3, 1, 2, 2, 1, 3]);
+    /// assert_eq!(s.iter_interval((0..=6).rev()).collect::<Vec<_>>(), vec![-2, -1, -3]);
+    /// ````
+    pub fn iter_interval<I>(&self, iterator: I) -> IterInterval<I>
+    where
+        I: Iterator,
+        I::Item: NumericElement,
+    {
+        IterInterval {
+            iterator,
+            sieve_node: self.root.clone(),
+            last: PositionLast::Init,
+            absolute: false,
+        }
+    }
+
+    /// Iterate over the absolute values of integer intervals between values in the sieve; otherwise identical to `iter_interval`.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// assert_eq!(s.iter_interval_abs((0..=6).rev()).collect::<Vec<_>>(), vec![2, 1, 3]);
+    /// ```
+    pub fn iter_interval_abs<I>(&self, iterator: I) -> IterInterval<I>
+    where
+        I: Iterator,
+        I::Item: NumericElement,
+    {
+        IterInterval {
+            iterator,
+            sieve_node: self.root.clone(),
+            last: PositionLast::Init,
+            absolute: true,
+        }
+    }
+
+    /// Return the Residuals that generate members of this sieve, in order of appearance and without duplicates. The index of each Residual is its channel in the Events produced by `iter_event`.
+    /// ```
+    /// let s = xensieve::Sieve::new("5@2|3@0&!(6@0)");
+    /// assert_eq!(s.channels().iter().map(|r| r.to_string()).collect::<Vec<_>>(), vec!["5@2", "3@0"]);
+    /// ```
+    pub fn channels(&self) -> Vec<Residual> {
+        let mut residuals = Vec::new();
+        self.root.generators(true, &mut residuals);
+        residuals
+    }
+
+    /// For the iterator provided as an input, iterate an Event for each value contained within the sieve, recording the channels of the Residuals that contain it. Where Residuals overlap, an Event has more than one channel.
+    /// ```
+    /// let s = xensieve::Sieve::new("5@2|3@0");
+    /// let e: Vec<_> = s.iter_event(0..13).map(|e| (e.value, e.channels)).collect();
+    /// assert_eq!(e[..4], [(0, vec![1]), (2, vec![0]), (3, vec![1]), (6, vec![1])]);
+    /// assert_eq!(e[6], (12, vec![0, 1]));
+    /// ```
+    pub fn iter_event<'a>(
+        &'a self,
+        iterator: impl Iterator<Item = i128> + 'a,
+    ) -> impl Iterator<Item = Event> + 'a {
+        let channels = self.channels();
+        iterator
+            .filter(move |v| self.contains(*v))
+            .map(move |v| Event {
+                value: v,
+                channels: channels
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, r)| r.contains(v))
+                    .map(|(i, _)| i)
+                    .collect(),
+            })
+    }
+
+    /// Return, for each channel Residual (see `channels`), an iterator of the members of the sieve within the range that the Residual contains, stepping through the Residual's progression rather than the whole range. Each member is paired with `true` if it is shared with another channel. Members produced only through inversion belong to no stem.
+    /// ```
+    /// let s = xensieve::Sieve::new("4@0|6@0");
+    /// let stems = s.iter_by_residual(0..13);
+    /// assert_eq!(stems[0].0.to_string(), "4@0");
+    /// let v: Vec<_> = stems.into_iter().map(|(_, i)| i.collect::<Vec<_>>()).collect();
+    /// assert_eq!(v[0], vec![(0, true), (4, false), (8, false), (12, true)]);
+    /// assert_eq!(v[1], vec![(0, true), (6, false), (12, true)]);
+    /// ```
+    pub fn iter_by_residual(
+        &self,
+        range: std::ops::Range<i128>,
+    ) -> Vec<(Residual, impl Iterator<Item = (i128, bool)> + '_)> {
+        let channels = self.channels();
+        channels
+            .iter()
+            .enumerate()
+            .map(|(i, residual)| {
+                let others: Vec<Residual> = channels
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, r)| *r)
+                    .collect();
+                let step = residual.modulus as i128;
+                let first = residual.next_member(range.start);
+                let end = range.end;
+                let values = std::iter::successors(first, move |v| v.checked_add(step))
+                    .take_while(move |v| *v < end)
+                    .filter(move |v| self.contains(*v))
+                    .map(move |v| (v, others.iter().any(|r| r.contains(v))));
+                (*residual, values)
+            })
+            .collect()
+    }
+
+    /// For the iterator provided as an input, iterate the frequencies in Hz of the values contained within the sieve, where each value is a step in an equal division of the octave into `steps_per_octave` steps, and step zero sounds at `base_hz`.
+    /// ```
+    /// let s = xensieve::Sieve::new("12@0|12@7");
+    /// assert_eq!(s.frequencies(0..=12, 220.0, 12).map(|f| f.round()).collect::<Vec<_>>(), vec![220.0, 330.0, 440.0])
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `steps_per_octave` is zero.
+    pub fn frequencies(
+        &self,
+        iterator: impl Iterator<Item = i128>,
+        base_hz: f64,
+        steps_per_octave: u32,
+    ) -> impl Iterator<Item = f64> {
+        assert!(
+            steps_per_octave > 0,
+            "steps per octave must be greater than zero"
+        );
+        let steps = steps_per_octave as f64;
+        self.iter_value(iterator)
+            .map(move |v| base_hz * 2f64.powf(v as f64 / steps))
+    }
+
+    /// Return the members from `start`, over a number of measures of the time signature, as a RhythmTree of onsets for import into OpenMusic or bach, where `steps_per_beat` steps make one beat of the signature. Each measure is divided into beats, and each beat at its onsets; time before the first onset of the range is a rest, and a note continuing across a beat is tied.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// let t = s.to_rhythm_tree(0, 1, (3, 4), 4);
+    /// assert_eq!(t.to_string(), "(? (((3 4) ((1 (3 1)) (1 (1 1)) (1 (1 3))))))");
+    /// let t = xensieve::Sieve::new("8@1").to_rhythm_tree(0, 2, (2, 4), 4);
+    /// assert_eq!(t.to_string(), "(? (((2 4) ((1 (-1 3)) 1.0)) ((2 4) ((1 (1.0 3)) 1.0))))");
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `steps_per_beat` or the beats of the signature are zero.
+    pub fn to_rhythm_tree(
+        &self,
+        start: i128,
+        measures: usize,
+        signature: (u64, u64),
+        steps_per_beat: u64,
+    ) -> RhythmTree {
+        assert!(
+            steps_per_beat > 0 && signature.0 > 0,
+            "steps_per_beat and beats must be greater than zero"
+        );
+        let beat = steps_per_beat as usize;
+        let measure = beat * signature.0 as usize;
+        let states: Vec<bool> = self
+            .iter_state(start..start + (measure * measures) as i128)
+            .collect();
+        let mut sounding = false;
+        let measures = states
+            .chunks(measure)
+            .map(|m| {
+                m.chunks(beat)
+                    .map(|b| {
+                        let node = rhythm::beat(b, sounding);
+                        sounding |= b.contains(&true);
+                        node
+                    })
+                    .collect()
+            })
+            .collect();
+        RhythmTree {
+            signature,
+            measures,
+        }
+    }
+
+    /// For the iterator provided as an input, iterate the intervals between values contained within the sieve as notated Durations, where `steps_per_beat` steps make a quarter-note beat. Intervals that are not a power-of-two division of the beat are notated within a tuplet, and intervals that need more than one note value are tied.
+    /// ```
+    /// use xensieve::{NoteValue, Sieve};
+    /// let s = Sieve::new("4@0|6@0");
+    /// let d: Vec<_> = s.iter_duration(0..=12, 2).map(|d| d.values).collect();
+    /// assert_eq!(d[0], vec![NoteValue { base: 2, dots: 0, tuplet: None }]);
+    /// assert_eq!(d[1], vec![NoteValue { base: 4, dots: 0, tuplet: None }]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `steps_per_beat` is zero.
+    pub fn iter_duration(
+        &self,
+        iterator: impl Iterator<Item = i128>,
+        steps_per_beat: u64,
+    ) -> impl Iterator<Item = Duration> {
+        assert!(
+            steps_per_beat > 0,
+            "steps_per_beat must be greater than zero"
+        );
+        self.iter_interval_abs(iterator)
+            .map(move |i| Duration::new(i, steps_per_beat))
+    }
+
+    /// Return the greatest common divisor of all intervals between members within the range: the finest pulse the sieve actually uses there. Returns `None` if the range has fewer than two members.
+    /// ```
+    /// let s = xensieve::Sieve::new("12@0|12@4|12@8|18@6");
+    /// assert_eq!(s.implied_grid(0..36), Some(2));
+    /// assert_eq!(s.implied_grid(0..2), None);
+    /// ```
+    pub fn implied_grid(&self, range: std::ops::Range<i128>) -> Option<i128> {
+        self.iter_interval(range).fold(None, |grid, i| match grid {
+            None => Some(i),
+            Some(g) => Some(util::gcd(g, i, 0).unwrap()),
+        })
+    }
+
+    /// Return the members within the range at which the interval `pattern` begins: each member from which the intervals to the following consecutive members are those of the pattern, such as for finding the occurrences of a motif within a long realization. Occurrences may overlap; an empty pattern occurs at every member.
+    /// ```
+    /// let s = xensieve::Sieve::new("12@0|12@2|12@4|12@5|12@7|12@9|12@11");
+    /// assert_eq!(s.find_interval_pattern(&[2, 2, 1], 0..24), vec![0, 7, 12]);
+    /// assert_eq!(s.find_interval_pattern(&[1, 1], 0..24), Vec::<i128>::new());
+    /// ```
+    pub fn find_interval_pattern(
+        &self,
+        pattern: &[i128],
+        range: std::ops::Range<i128>,
+    ) -> Vec<i128> {
+        let members = self.scan(range);
+        let intervals: Vec<i128> = members.windows(2).map(|w| w[1] - w[0]).collect();
+        if pattern.is_empty() {
+            return members;
+        }
+        util::find_all(&intervals, pattern)
+            .into_iter()
+            .map(|i| members[i])
+            .collect()
+    }
+
+    /// For the iterator provided as an input, collect the values contained within the sieve into a Segment.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// assert_eq!(s.segment(0..=12).values(), &[0, 3, 4, 6, 8, 9, 12])
+    /// ```
+    pub fn segment(&self, iterator: impl Iterator<Item = i128>) -> Segment {
+        Segment::new(self.iter_value(iterator))
+    }
+
+    /// For the iterator provided as an input, construct a new Sieve from the intervals between the values contained within this sieve, treating the intervals as a point set with a period equal to the span of the values. If fewer than two values are found, the empty Sieve is returned.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// let d = s.derive_from_intervals(0..=12);
+    /// assert_eq!(d.to_string(), "Sieve{12@1|12@2|12@3}");
+    /// ```
+    pub fn derive_from_intervals(&self, iterator: impl Iterator<Item = i128>) -> Self {
+        let segment = self.segment(iterator);
+        let intervals = segment.intervals();
+        let span: i128 = intervals.iter().sum();
+        if span <= 0 {
+            return Self::from_points(std::iter::empty(), 1);
+        }
+        Self::from_points(intervals, span as u64)
+    }
+
+    /// For the iterator provided as an input, evaluate each value and return evaluation statistics for each node of the sieve, in pre-order. Intersections and unions evaluate their left operand first, and short-circuit when its result determines the outcome; these statistics show which operands are selective and cheap enough to be placed first.
+    /// ```
+    /// let s = xensieve::Sieve::new("2@0&7@0");
+    /// let p = s.profile(0..14);
+    /// assert_eq!(p[0].short_circuits, 7);
+    /// assert_eq!(p[2].evaluations, 7);
+    /// ```
+    pub fn profile(&self, iterator: impl Iterator<Item = i128>) -> Vec<NodeProfile> {
+        profile::profile(&self.root, iterator)
+    }
+
+    /// Return an equivalent Sieve with the commutative operands of intersections and unions reordered to reduce the average cost of evaluation: for intersections, the operand with the lower estimated density is tested first; for unions, the operand with the higher estimated density is tested first.
+    /// ```
+    /// let s = xensieve::Sieve::new("2@0&7@0|3@0");
+    /// assert_eq!(s.optimize_order().to_string(), "Sieve{3@0|7@0&2@0}");
+    /// ```
+    pub fn optimize_order(&self) -> Self {
+        Self::from_node(self.root.optimize_order())
+    }
+
+    /// Return an equivalent sieve, no larger, rewritten by algebraic identities: double negations are removed, inverted operands are combined by De Morgan's laws, idempotent (`x|x`) and absorbed (`x&(x|y)`) operands are dropped, empty (`0@0`) and universal (`1@0`) operands are resolved, and intersections of Residuals are folded into a single Residual.
+    /// ```
+    /// let s = xensieve::Sieve::new("!(!(4@3)|!(6@1))|1@0&3@0|0@0");
+    /// assert_eq!(s.simplify().to_string(), "Sieve{12@7|3@0}");
+    /// ```
+    pub fn simplify(&self) -> Self {
+        Self::from_node(simplify::simplify(&self.root))
+    }
+
+    /// Return an equivalent sieve that is a flat union of Residuals, sorted, with no Residual a subset of another. Intersections are resolved into single Residuals, and complements are expanded into the remaining classes of each modulus; complements of large moduli can produce many Residuals.
+    /// ```
+    /// let s = xensieve::Sieve::new("(4@0|6@2)&!(3@0)");
+    /// assert_eq!(s.to_dnf().to_string(), "Sieve{6@2|12@4}");
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the modulus of an intersection overflows.
+    pub fn to_dnf(&self) -> Self {
+        Self::from_residuals(
+            dnf::dnf(&self.root)
+                .into_iter()
+                .map(|r| (r.modulus, r.shift)),
+        )
+    }
+
+    /// Return the complement of this sieve as an explicit union of Residuals, rather than an inversion evaluated by `contains`, such that it can be displayed, minimized, and exported like any other sieve. Each Residual class of the complement is one of the classes, of the moduli of this sieve, not covered by it (see `to_dnf`). As the complement of a large modulus has as many Residuals as classes not covered, the union is built balanced rather than as a chain, such that its depth grows with the logarithm of the number of Residuals; building it still takes time and memory proportional to that number.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@1");
+    /// assert_eq!((!&s).to_string(), "Sieve{!(3@0|4@1)}");
+    /// assert_eq!(s.complement_expanded().to_string(), "Sieve{12@2|12@4|(12@7|12@8)|(12@10|12@11)}");
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the modulus of an intersection overflows.
+    pub fn complement_expanded(&self) -> Self {
+        let mut sieves: Vec<Self> = dnf::dnf(&SieveNode::Inversion(Box::new(self.root.clone())))
+            .into_iter()
+            .map(|r| Self::unit(r.modulus, r.shift))
+            .collect();
+        // join adjacent pairs, such that the depth of the union grows with the logarithm of the number of Residuals
+        while sieves.len() > 1 {
+            let mut joined = Vec::with_capacity((sieves.len() + 1) / 2);
+            let mut parts = sieves.into_iter();
+            while let Some(lhs) = parts.next() {
+                joined.push(match parts.next() {
+                    Some(rhs) => lhs | rhs,
+                    None => lhs,
+                });
+            }
+            sieves = joined;
+        }
+        sieves.pop().unwrap_or_else(Self::empty)
+    }
+
+    /// Return the (modulus, shift) pairs of the Residuals of the flat union produced by `to_dnf`, such that the structure of a sieve can be read without parsing its Display. An empty sieve has no pairs.
+    /// ```
+    /// let s = xensieve::Sieve::new("(4@0|6@2)&!(3@0)");
+    /// assert_eq!(s.residuals(), vec![(6, 2), (12, 4)]);
+    /// assert_eq!(xensieve::Sieve::new("!(0@0)").residuals(), vec![(1, 0)]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the modulus of an intersection overflows.
+    pub fn residuals(&self) -> Vec<(u64, u64)> {
+        dnf::dnf(&self.root)
+            .into_iter()
+            .map(|r| (r.modulus, r.shift))
+            .collect()
+    }
+
+    /// Return the membership condition of this sieve as an SMT-LIB assertion over the integer variable `var`, such that a solver can search for values satisfying this sieve together with other constraints. Each Residual is a congruence of `mod`, which in SMT-LIB is non-negative for a positive divisor; a Residual of modulus zero is `false`. The variable must be declared, as with `(declare-const x Int)`, before the assertion.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|!(4@1)");
+    /// assert_eq!(s.to_smtlib("x"), "(assert (or (= (mod x 3) 0) (not (= (mod x 4) 1))))");
+    /// ```
+    pub fn to_smtlib(&self, var: &str) -> String {
+        format!("(assert {})", self.root.to_smtlib(var))
+    }
+
+    /// Return the membership condition of this sieve as a MiniZinc predicate named `name`, over one integer argument, such that sieve constraints can be embedded in larger constraint models. As `mod` in MiniZinc takes the sign of the dividend, each Residual is tested on the non-negative remainder; a Residual of modulus zero is `false`.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|!(4@1)");
+    /// assert_eq!(
+    ///     s.to_minizinc("onset"),
+    ///     "predicate onset(var int: x) = (((x mod 3) + 3) mod 3 = 0 \\/ not (((x mod 4) + 4) mod 4 = 1));"
+    /// );
+    /// ```
+    pub fn to_minizinc(&self, name: &str) -> String {
+        format!(
+            "predicate {name}(var int: x) = {};",
+            self.root.to_minizinc("x")
+        )
+    }
+
+    /// Return an equivalent sieve that is a union of the fewest possible Residuals. The members of one period are covered exactly by classes with moduli dividing the period; the search is exhaustive, and may be slow for long periods with many members.
+    /// ```
+    /// let s = xensieve::Sieve::new("12@0|12@3|12@4|12@6|12@8|12@9");
+    /// assert_eq!(s.minimize().to_string(), "Sieve{3@0|4@0}");
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the period overflows.
+    pub fn minimize(&self) -> Self {
+        let period = match self.period() {
+            Some(p) => p,
+            None => {
+                self.root.cycle().expect("period overflows");
+                // without non-zero moduli, a sieve is empty or full
+                return if self.contains(0) {
+                    Self::all()
+                } else {
+                    Self::empty()
+                };
+            }
+        };
+        let members: Vec<u64> = self
+            .scan(0..period as i128)
+            .into_iter()
+            .map(|v| v as u64)
+            .collect();
+        Self::from_residuals(
+            minimize::minimize(period, &members)
+                .into_iter()
+                .map(|r| (r.modulus, r.shift)),
+        )
+    }
+
+    /// Return a sieve, for an answering voice, built from the complement of this sieve with its density adjusted toward `target_density`. The complement, as a union of Residuals (see `to_dnf`), is changed one Residual at a time, dropping a Residual of the complement or adding one of the other classes of the same moduli, choosing the change that brings the density nearest the target, until no change brings it nearer.
+    /// ```
+    /// let s = xensieve::Sieve::new("4@0");
+    /// assert_eq!(s.balanced_complement(0.5).to_string(), "Sieve{4@2|4@3}");
+    /// assert_eq!(s.balanced_complement(0.75).to_string(), "Sieve{4@1|4@2|4@3}");
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `target_density` is not between zero and one, or if the period of the complement overflows.
+    pub fn balanced_complement(&self, target_density: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&target_density),
+            "target density must be between zero and one"
+        );
+        let complement = dnf::dnf(&SieveNode::Inversion(Box::new(self.root.clone())));
+        let candidates = balance::candidates(&complement);
+        Self::from_residuals(
+            balance::balance(complement, candidates, target_density)
+                .into_iter()
+                .map(|r| (r.modulus, r.shift)),
+        )
+    }
+
+    /// Return a Piecewise whose density tracks an envelope over the range. The range is divided into windows of `window` values; for each window, this sieve, as a union of Residuals (see `to_dnf`), is thinned or thickened toward the density given by `envelope` at the start of the window, dropping its Residuals or adding other classes of the same moduli as `balanced_complement`. The first window's sieve is in effect before the range, and the last window's after it.
+    /// ```
+    /// let s = xensieve::Sieve::new("4@0|4@1");
+    /// let p = s.track_density(0..16, 8, |v| if v < 8 { 0.25 } else { 0.75 });
+    /// assert_eq!(p.scan(0..16), vec![1, 5, 8, 9, 10, 12, 13, 14]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `window` is zero, if the range is empty, if the envelope returns a density that is not between zero and one, or if the period of a window's sieve overflows.
+    pub fn track_density(
+        &self,
+        range: std::ops::Range<i128>,
+        window: u64,
+        envelope: impl Fn(i128) -> f64,
+    ) -> Piecewise {
+        assert!(window > 0, "window must be greater than zero");
+        assert!(range.end > range.start, "range must not be empty");
+        let residuals = dnf::dnf(&self.root);
+        let candidates = balance::candidates(&residuals);
+        let section = |start: i128| {
+            let target = envelope(start);
+            assert!(
+                (0.0..=1.0).contains(&target),
+                "target density must be between zero and one"
+            );
+            Self::from_residuals(
+                balance::balance(residuals.clone(), candidates.clone(), target)
+                    .into_iter()
+                    .map(|r| (r.modulus, r.shift)),
+            )
+        };
+        let mut piecewise = Piecewise::new(section(range.start));
+        let mut start = range.start.saturating_add(window as i128);
+        while start < range.end {
+            piecewise.push(start, section(start));
+            start = start.saturating_add(window as i128);
+        }
+        piecewise
+    }
+
+    /// Return the Plan that would be used to scan the range for members: the evaluation strategy with the lowest estimated cost, and the reason for its selection.
+    /// ```
+    /// let s = xensieve::Sieve::new("10000@3");
+    /// assert_eq!(s.plan(0..1_000_000).strategy, xensieve::Strategy::ProgressionMerge);
+    /// ```
+    pub fn plan(&self, range: std::ops::Range<i128>) -> Plan {
+        Plan::new(&self.root, &range)
+    }
+
+    /// Return the members within the range, using the evaluation strategy selected by `plan`.
+    /// ```
+    /// let s = xensieve::Sieve::new("(5@0|4@2)&!30@10");
+    /// assert_eq!(s.scan(0..20), vec![0, 2, 5, 6, 14, 15, 18]);
+    /// ```
+    pub fn scan(&self, range: std::ops::Range<i128>) -> Vec<i128> {
+        self.plan(range.clone()).execute(&self.root, range)
+    }
+
+    /// Return the period of this sieve: the least common multiple of all non-zero moduli, after which the pattern of members repeats. Returns `None` if there are no non-zero moduli (as for the empty Sieve), or if the period overflows.
+    /// ```
+    /// let s = xensieve::Sieve::new("(4@0|6@2)&!(10@0)");
+    /// assert_eq!(s.period(), Some(60));
+    /// assert_eq!(xensieve::Sieve::empty().period(), None);
+    /// ```
+    pub fn period(&self) -> Option<u64> {
+        let mut residuals = Vec::new();
+        self.root.residuals(&mut residuals);
+        if residuals.iter().all(|r| r.modulus == 0) {
+            return None;
+        }
+        self.root.cycle()
+    }
+
+    /// Return the period of this sieve, as `period`, but as a `u128` reported with the narrowest Precision in which it fits: `I64` if the period and every modulus fit `i64`, otherwise `I128`. Returns `None` if there are no non-zero moduli, or if the period overflows `i128`.
+    /// ```
+    /// let s = xensieve::Sieve::new("4@0|6@2");
+    /// assert_eq!(s.period_adaptive().unwrap().value, 12);
+    /// let s = xensieve::Sieve::new("4294967291@0|4294967279@0|3@1");
+    /// let p = s.period_adaptive().unwrap();
+    /// assert_eq!(p.precision, xensieve::Precision::I128);
+    /// assert_eq!(p.value, 4294967291 * 4294967279 * 3);
+    /// assert_eq!(s.period(), None);
+    /// ```
+    pub fn period_adaptive(&self) -> Option<Promoted<u128>> {
+        let mut residuals = Vec::new();
+        self.root.residuals(&mut residuals);
+        if residuals.iter().all(|r| r.modulus == 0) {
+            return None;
+        }
+        precision::lcm(residuals.iter().map(|r| r.modulus))
+    }
+
+    /// Return the narrowest Precision in which membership of every value in the range can be evaluated: the values, the moduli, and each difference of a value and a shift must be representable. Use this to select an element type before iterating.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@1");
+    /// assert_eq!(s.precision(0..1000), xensieve::Precision::I64);
+    /// assert_eq!(s.precision(0..i64::MAX as i128 + 2), xensieve::Precision::I128);
+    /// ```
+    pub fn precision(&self, range: std::ops::Range<i128>) -> Precision {
+        let mut residuals = Vec::new();
+        self.root.residuals(&mut residuals);
+        let shift = residuals.iter().map(|r| r.shift as i128).max().unwrap_or(0);
+        let narrow = i64::MIN as i128..=i64::MAX as i128;
+        if residuals.iter().all(|r| r.modulus <= i64::MAX as u64)
+            && (range.start >= range.end
+                || (narrow.contains(&range.start.saturating_sub(shift))
+                    && narrow.contains(&(range.end - 1))))
+        {
+            Precision::I64
+        } else {
+            Precision::I128
+        }
+    }
+
+    /// Return the phase of a value within the period: the position `value mod period`, and, if that position is a member, its zero-based index among the members of the period. Returns `None` if the sieve has no period.
+    /// ```
+    /// let s = xensieve::Sieve::new("12@0|12@4|12@7");
+    /// assert_eq!(s.phase(31), Some((7, Some(2))));
+    /// assert_eq!(s.phase(-8), Some((4, Some(1))));
+    /// assert_eq!(s.phase(5), Some((5, None)));
+    /// ```
+    pub fn phase(&self, value: i128) -> Option<(u64, Option<usize>)> {
+        let period = self.period()?;
+        let position = value.rem_euclid(period as i128);
+        if !self.contains(position) {
+            return Some((position as u64, None));
+        }
+        let mut index = 0;
+        let mut start = 0;
+        while let Some(v) = self.first_in(start..position) {
+            index += 1;
+            start = v + 1;
+        }
+        Some((position as u64, Some(index)))
+    }
+
+    /// Return the density of this sieve: the fraction of the integers of one period that are members. A sieve without a period is either empty, with a density of zero, or full, with a density of one.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// assert_eq!(s.density(), 0.5);
+    /// assert_eq!(xensieve::Sieve::new("!(0@0)").density(), 1.0);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the period overflows.
+    pub fn density(&self) -> f64 {
+        match self.period() {
+            Some(p) => self.scan(0..p as i128).len() as f64 / p as f64,
+            None => {
+                self.root.cycle().expect("period overflows");
+                if self.contains(0) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    /// Return the coverage of the range: the fraction of the integers of the range that are members. An empty range has a coverage of zero.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// assert_eq!(s.coverage(0..5), 0.6);
+    /// assert_eq!(s.coverage(5..5), 0.0);
+    /// ```
+    pub fn coverage(&self, range: std::ops::Range<i128>) -> f64 {
+        if range.end <= range.start {
+            return 0.0;
+        }
+        let len = (range.end - range.start) as f64;
+        self.scan(range).len() as f64 / len
+    }
+
+    /// Return an SVG drawing of this sieve: its formula above a strip of one cell per value of the range, filled for members. This is suited to the rich display of notebooks, such as `_repr_svg_` in Python bindings.
+    /// ```
+    /// let svg = xensieve::Sieve::new("3@0|4@1").to_svg(0..12);
+    /// assert!(svg.starts_with("<svg"));
+    /// assert_eq!(svg.matches("fill=\"black\"").count(), 6);
+    /// ```
+    pub fn to_svg(&self, range: std::ops::Range<i128>) -> String {
+        render::svg(&self.root, range)
+    }
+
+    /// Return an HTML fragment of this sieve: its formula, as code, followed by the drawing of `to_svg`. This is suited to the rich display of notebooks, such as `_repr_html_` in Python bindings.
+    /// ```
+    /// let html = xensieve::Sieve::new("3@0&4@1").to_html(0..12);
+    /// assert!(html.starts_with("<div><code>3@0&amp;4@1</code>"));
+    /// ```
+    pub fn to_html(&self, range: std::ops::Range<i128>) -> String {
+        render::html(&self.root, range)
+    }
+
+    /// Return the number of members in the range, without testing each value: members are counted by stepping from one to the next over no more than one period, and when the range spans whole periods the count of one period is multiplied by their number, with the members of the remaining partial period added.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@1");
+    /// assert_eq!(s.count(0..12), 6);
+    /// assert_eq!(s.count(0..12_000_000_000), 6_000_000_000);
+    /// assert_eq!(s.count(-5..5), 4);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the period or the count overflows.
+    pub fn count(&self, range: std::ops::Range<i128>) -> u64 {
+        if range.start >= range.end {
+            return 0;
+        }
+        let period = self.root.cycle().expect("period overflows") as u128;
+        let len = range.end.abs_diff(range.start);
+        let count = if len <= period {
+            Some(self.root.count_members(range.start, range.end))
+        } else {
+            // len exceeds period, so one period from start is within range
+            let per_period = self
+                .root
+                .count_members(range.start, range.start + period as i128);
+            let remainder = (len % period) as i128;
+            let partial = self
+                .root
+                .count_members(range.start, range.start + remainder);
+            (len / period)
+                .checked_mul(per_period)
+                .and_then(|c| c.checked_add(partial))
+        };
+        count
+            .and_then(|c| u64::try_from(c).ok())
+            .expect("count overflows")
+    }
+
+    /// Return a columnar Table of this sieve over the range, with one row for each value: the value, its state, the interval from the previous member, and the Residuals that contain it, for statistical analysis in dataframe libraries.
+    /// ```
+    /// let t = xensieve::Sieve::new("5@2|3@0").to_table(0..15);
+    /// assert_eq!(t.len(), 15);
+    /// assert_eq!(t.residuals[12], "5@2 3@0");
+    /// ```
+    pub fn to_table(&self, range: std::ops::Range<i128>) -> Table {
+        Table::new(self, range)
+    }
+
+    /// Return a density envelope over the range: for each value of the range, the number of members in the window of `window` values starting at that value. Windows extend beyond the end of the range as needed.
+    /// ```
+    /// let s = xensieve::Sieve::new("4@0|4@1");
+    /// assert_eq!(s.density_profile(0..6, 3), vec![2, 1, 1, 2, 2, 1]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `window` is zero.
+    pub fn density_profile(&self, range: std::ops::Range<i128>, window: u64) -> Vec<usize> {
+        assert!(window > 0, "window must be greater than zero");
+        if range.end <= range.start {
+            return Vec::new();
+        }
+        let members = self.scan(range.start..range.end.saturating_add(window as i128));
+        let mut lo = 0;
+        let mut hi = 0;
+        range
+            .map(|v| {
+                while lo < members.len() && members[lo] < v {
+                    lo += 1;
+                }
+                while hi < members.len() && members[hi] < v + window as i128 {
+                    hi += 1;
+                }
+                hi - lo
+            })
+            .collect()
+    }
+
+    /// Return the smallest member within the range, or `None` if the range has no members. Members are found analytically from the Residuals rather than by testing each value of the range; as the pattern repeats every cycle, at most one cycle of the range is searched.
+    /// ```
+    /// let s = xensieve::Sieve::new("1000@999|7@3&5@0");
+    /// assert_eq!(s.first_in(0..1_000_000), Some(10));
+    /// assert_eq!(s.first_in(11..1_000_000), Some(45));
+    /// assert_eq!(s.first_in(0..10), None);
+    /// ```
+    pub fn first_in(&self, range: std::ops::Range<i128>) -> Option<i128> {
+        let end = match self.root.cycle() {
+            Some(c) => range.end.min(range.start.saturating_add(c as i128)),
+            None => range.end,
+        };
+        self.root.next_member(range.start, end)
+    }
+
+    /// Return the smallest member strictly greater than `value`, or `None` if there is none. As with `first_in`, the next member of each Residual is computed directly rather than by testing each value in turn, and no more than one period is searched; only the complements of inversions are found by stepping through values.
+    /// ```
+    /// let s = xensieve::Sieve::new("1000@7|999@3");
+    /// assert_eq!(s.next_above(7), Some(1002));
+    /// assert_eq!(s.next_above(-1), Some(3));
+    /// assert_eq!(xensieve::Sieve::empty().next_above(0), None);
+    /// ```
+    pub fn next_above(&self, value: i128) -> Option<i128> {
+        let start = value.checked_add(1)?;
+        self.first_in(start..i128::MAX)
+    }
+
+    /// Return the greatest member strictly less than `value`, or `None` if there is none. The search is that of `next_above`, over the reflection of this sieve.
+    /// ```
+    /// let s = xensieve::Sieve::new("1000@7|999@3");
+    /// assert_eq!(s.prev_below(1002), Some(7));
+    /// assert_eq!(s.prev_below(3), Some(-993));
+    /// assert_eq!(xensieve::Sieve::empty().prev_below(0), None);
+    /// ```
+    pub fn prev_below(&self, value: i128) -> Option<i128> {
+        let reflected = Self::from_node(self.root.reflect());
+        reflected.next_above(value.checked_neg()?).map(|v| -v)
+    }
+
+    /// Iterate the members of this sieve less than or equal to `start`, in descending order, such as for retrograde material. As with `prev_below`, each member is found by the search of `next_above` over the reflection of this sieve, rather than by testing each value in turn.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@1");
+    /// assert_eq!(s.iter_value_down(9).take(5).collect::<Vec<_>>(), vec![9, 6, 5, 3, 1]);
+    /// assert_eq!(xensieve::Sieve::empty().iter_value_down(9).count(), 0);
+    /// assert_eq!(xensieve::Sieve::new("5@2&10@3").iter_value_down(0).next(), None);
+    /// ```
+    pub fn iter_value_down(&self, start: i128) -> impl Iterator<Item = i128> {
+        let reflected = self.root.reflect();
+        let cycle = reflected.cycle().map(|c| c as i128);
+        let mut pos = start.checked_neg();
+        std::iter::from_fn(move || {
+            let p = pos?;
+            // as the pattern repeats, a search of one cycle past pos finds a member if there is one
+            let end = cycle.and_then(|c| p.checked_add(c)).unwrap_or(i128::MAX);
+            let v = reflected.next_member(p, end)?;
+            pos = v.checked_add(1);
+            Some(-v)
+        })
+    }
+
+    /// Return the member at zero-based index `n` among the members at or after `origin`, or `None` if the sieve has no members or the value overflows. Where the period is small, the members of one period from `origin` are counted once, whole periods are skipped arithmetically, and only the members up to the remainder of `n` divided by that count are stepped through; where the period is large, each of the `n` members is stepped through, as counting a period would cost more.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@1");
+    /// assert_eq!(s.nth(0, 0), Some(0));
+    /// assert_eq!(s.nth(0, 3), Some(5));
+    /// assert_eq!(s.nth(0, 6_000_000), Some(12_000_000));
+    /// assert_eq!(s.nth(-5, 0), Some(-3));
+    /// assert_eq!(xensieve::Sieve::new("!(0@0)").nth(0, 3), Some(3));
+    /// ```
+    pub fn nth(&self, origin: i128, n: u64) -> Option<i128> {
+        let period = self.root.cycle()?;
+        let (mut pos, n) = if period <= VALUES_COUNT_LIMIT {
+            let period = period as i128;
+            let count = self.root.count_members(origin, origin.checked_add(period)?) as u64;
+            if count == 0 {
+                return None;
+            }
+            let skip = ((n / count) as i128).checked_mul(period)?;
+            (origin.checked_add(skip)?, n % count)
+        } else {
+            (origin, n)
+        };
+        // as the pattern repeats, a search of one period finds a member if there is one
+        let next = |pos: i128| match pos.checked_add(period as i128) {
+            Some(end) => self.root.next_member(pos, end),
+            None => self
+                .root
+                .next_member(pos, i128::MAX)
+                .or_else(|| Some(i128::MAX).filter(|v| self.root.contains(*v))),
+        };
+        for _ in 0..n {
+            pos = next(pos)?.checked_add(1)?;
+        }
+        next(pos)
+    }
+
+    /// Return `true` if an intersection of Residuals within this sieve, each side of which has members, has no members, as for `5@2&10@3`. Such a collapse is often unintended, and silently yields no values; with the `warnings` feature enabled, a warning is also emitted with the `log` crate when such an intersection is formed with the `&` operator, provided a logger is enabled for warnings. Only intersections of unions and intersections of Residuals, of limited size, are detected; use `is_empty` to test any sieve.
+    /// ```
+    /// assert!(xensieve::Sieve::new("5@2&10@3|4@1").collapsed_empty());
+    /// assert!(!xensieve::Sieve::new("5@2&10@7").collapsed_empty());
+    /// ```
+    pub fn collapsed_empty(&self) -> bool {
+        self.root.collapsed()
+    }
+
+    /// Return `true` if this sieve has no members, such as an intersection that collapses to the null class `0@0`. Members are searched for over one period.
+    /// ```
+    /// assert!(xensieve::Sieve::new("6@0&4@1").is_empty());
+    /// assert!(!xensieve::Sieve::new("6@0&4@2").is_empty());
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the period overflows.
+    pub fn is_empty(&self) -> bool {
+        let period = self.root.cycle().expect("period overflows");
+        !self.any_in(0..period as i128)
+    }
+
+    /// Return `true` if this sieve contains every value, as does `1@0`.
+    /// ```
+    /// assert!(xensieve::Sieve::new("2@0|2@1").is_full());
+    /// assert!(!xensieve::Sieve::new("3@0|3@1").is_full());
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the period overflows.
+    pub fn is_full(&self) -> bool {
+        (!self).is_empty()
+    }
+
+    /// Return `true` if this sieve and `other` have the same members, regardless of how they are expressed. Membership is compared over the combined period of both sieves, searching only the members of their symmetric difference.
+    /// ```
+    /// let s1 = xensieve::Sieve::new("!(3@0|4@0)");
+    /// let s2 = xensieve::Sieve::new("!3@0&!4@0");
+    /// assert!(s1.is_equivalent(&s2));
+    /// assert!(!s1.is_equivalent(&xensieve::Sieve::new("!(3@0)")));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the combined period overflows.
+    pub fn is_equivalent(&self, other: &Sieve) -> bool {
+        (self ^ other).is_empty()
+    }
+
+    /// Return `true` if every member of this sieve is a member of `other`.
+    /// ```
+    /// let s = xensieve::Sieve::new("12@0|12@7");
+    /// assert!(s.is_subset_of(&xensieve::Sieve::new("12@0|12@2|12@4|12@5|12@7|12@9|12@11")));
+    /// assert!(!s.is_subset_of(&xensieve::Sieve::new("2@0")));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the combined period overflows.
+    pub fn is_subset_of(&self, other: &Sieve) -> bool {
+        (self - other).is_empty()
+    }
+
+    /// Return `true` if every member of `other` is a member of this sieve.
+    ///
+    /// # Panics
+    /// Panics if the combined period overflows.
+    pub fn is_superset_of(&self, other: &Sieve) -> bool {
+        other.is_subset_of(self)
+    }
+
+    /// Return `true` if this sieve and `other` have no members in common.
+    /// ```
+    /// assert!(xensieve::Sieve::new("6@0").is_disjoint_with(&xensieve::Sieve::new("4@1")));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the combined period overflows.
+    pub fn is_disjoint_with(&self, other: &Sieve) -> bool {
+        (self & other).is_empty()
+    }
+
+    /// Return `true` if any member is within the range.
+    /// ```
+    /// let s = xensieve::Sieve::new("7@3&5@0");
+    /// assert_eq!(s.any_in(0..10), false);
+    /// assert_eq!(s.any_in(0..11), true);
+    /// ```
+    pub fn any_in(&self, range: std::ops::Range<i128>) -> bool {
+        self.first_in(range).is_some()
+    }
+
+    /// Iterate over the cycles of the period that overlap the range, yielding the index of each cycle (the floor of a value divided by the period) with the members of the range within that cycle. Cycles without members are yielded with an empty Vec. Sieves without a period yield nothing.
+    /// ```
+    /// let s = xensieve::Sieve::new("4@0|4@1");
+    /// let c: Vec<_> = s.iter_cycles(2..14).collect();
+    /// assert_eq!(c, vec![(0, vec![]), (1, vec![4, 5]), (2, vec![8, 9]), (3, vec![12, 13])]);
+    /// ```
+    pub fn iter_cycles(
+        &self,
+        range: std::ops::Range<i128>,
+    ) -> impl Iterator<Item = (i128, Vec<i128>)> + '_ {
+        let (period, cycles) = match self.period() {
+            Some(p) if range.start < range.end => {
+                let p = p as i128;
+                (
+                    p,
+                    range.start.div_euclid(p)..(range.end - 1).div_euclid(p) + 1,
+                )
+            }
+            _ => (1, 0..0),
+        };
+        cycles.map(move |c| {
+            let start = range.start.max(c * period);
+            let end = range.end.min((c + 1) * period);
+            (c, self.scan(start..end))
+        })
+    }
+
+    /// Return candidate Meters for this sieve taken as a rhythm: for each member of the period taken as a downbeat, the longest hierarchy of nested pulses (such as `2@0`, `4@0`, `12@0`), with widths dividing the period, that are wholly contained in the sieve. Candidates are ordered by decreasing number of levels, then by downbeat. Sieves without a period have no candidates.
+    /// ```
+    /// let s = xensieve::Sieve::new("12@0|12@3|4@0|2@0");
+    /// let m = s.infer_meter();
+    /// assert_eq!((m[0].downbeat, m[0].levels.clone()), (0, vec![2, 4, 12]));
+    /// assert_eq!(m[0].time_signature(2), Some((3, 2)));
+    /// ```
+    pub fn infer_meter(&self) -> Vec<Meter> {
+        match self.period() {
+            Some(period) => meter::infer(period, |v| self.contains(v)),
+            None => Vec::new(),
+        }
+    }
+
+    /// Return a Pyramid of this sieve over the range, supporting fast queries for any member within a sub-range.
+    /// ```
+    /// let s = xensieve::Sieve::new("1000@999");
+    /// let p = s.pyramid(0..100_000);
+    /// assert_eq!(p.any_in(0..999), false);
+    /// assert_eq!(p.any_in(0..1000), true);
+    /// ```
+    pub fn pyramid(&self, range: std::ops::Range<i128>) -> Pyramid {
+        Pyramid::new(range.clone(), self.scan(range))
+    }
+
+    /// Return the members within the range, reusing the states of any nodes of this sieve that have been evaluated over the same range and stored in the SegmentCache. After a small edit to a large sieve, only the changed subtrees and their ancestors are evaluated.
+    /// ```
+    /// let mut cache = xensieve::SegmentCache::new();
+    /// let s1 = xensieve::Sieve::new("(5@0|4@2)&!30@10");
+    /// let s2 = xensieve::Sieve::new("(5@0|4@2)&!30@20");
+    /// assert_eq!(s1.reevaluate_with_cache(0..12, &mut cache), vec![0, 2, 5, 6]);
+    /// assert_eq!(s2.reevaluate_with_cache(0..12, &mut cache), vec![0, 2, 5, 6, 10]);
+    /// assert_eq!(cache.hits(), 1);
+    /// ```
+    pub fn reevaluate_with_cache(
+        &self,
+        range: std::ops::Range<i128>,
+        cache: &mut SegmentCache,
+    ) -> Vec<i128> {
+        let states = cache.states(&self.root, &range);
+        range
+            .zip(states.iter())
+            .filter_map(|(v, s)| if *s { Some(v) } else { None })
+            .collect()
+    }
+
+    /// Return a Sieve with each Residual's modulus and shift scaled by `numerator / denominator`, rounding non-integer results by the provided policy, and `true` if no rounding was necessary. Where the scaling is exact, the members of the result are the members of this sieve, scaled; otherwise the result approximates them. A rounded modulus is never less than one.
+    /// ```
+    /// use xensieve::{Rounding, Sieve};
+    /// let (s, exact) = Sieve::new("4@0|6@2").scale_rational(3, 2, Rounding::Nearest);
+    /// assert_eq!((s.to_string(), exact), (String::from("Sieve{6@0|9@3}"), true));
+    /// let (s, exact) = Sieve::new("5@1").scale_rational(3, 2, Rounding::Floor);
+    /// assert_eq!((s.to_string(), exact), (String::from("Sieve{7@1}"), false));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `numerator` or `denominator` is zero, or if a scaled modulus overflows.
+    pub fn scale_rational(
+        &self,
+        numerator: u64,
+        denominator: u64,
+        rounding: Rounding,
+    ) -> (Self, bool) {
+        assert!(numerator > 0, "numerator must be greater than zero");
+        assert!(denominator > 0, "denominator must be greater than zero");
+        let mut exact = true;
+        let root = self
+            .root
+            .scale_rational(numerator, denominator, rounding, &mut exact);
+        (Self::from_node(root), exact)
+    }
+
+    /// Construct a self-similar Sieve of `depth` levels, where each member of the pattern, scaled by `scale_factor`, is expanded into a copy of the pattern's first `scale_factor` positions. A value is contained if each of its lower base-`scale_factor` digits is in the Sieve, and the remaining higher part is in the Sieve.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|3@2").nest(3, 3);
+    /// assert_eq!(s.iter_value(0..27).collect::<Vec<_>>(), vec![0, 2, 6, 8, 18, 20, 24, 26])
+    /// ```
+    ///
+    /// As each level dilates its Residuals into as many Residuals as its scale, the nested Sieve grows exponentially with `depth`; the total number of Residuals is limited to `NEST_LIMIT` (4096).
+    ///
+    /// # Panics
+    /// Panics if `scale_factor` is less than two, `depth` is zero, or the nested Sieve would have more than `NEST_LIMIT` Residuals.
+    pub fn nest(&self, scale_factor: u64, depth: u32) -> Self {
+        assert!(scale_factor >= 2, "scale factor must be at least two");
+        assert!(depth > 0, "depth must be greater than zero");
+        // the pattern of a single digit: the members of the first scale_factor positions
+        let digit = Self::from_points(
+            (0..scale_factor as i128).filter(|v| self.contains(*v)),
+            scale_factor,
+        )
+        .root;
+        let (mut digit_residuals, mut self_residuals) = (Vec::new(), Vec::new());
+        digit.residuals(&mut digit_residuals);
+        self.root.residuals(&mut self_residuals);
+        let mut factor: u64 = 1;
+        let mut total = 0u64;
+        let mut levels: Vec<SieveNode> = Vec::new();
+        for _ in 1..depth {
+            total = total.saturating_add((digit_residuals.len() as u64).saturating_mul(factor));
+            assert!(
+                total <= NEST_LIMIT,
+                "nested sieve exceeds {NEST_LIMIT} Residuals"
+            );
+            levels.push(digit.dilate(factor));
+            factor = factor.checked_mul(scale_factor).expect("modulus overflow");
+        }
+        total = total.saturating_add((self_residuals.len() as u64).saturating_mul(factor));
+        assert!(
+            total <= NEST_LIMIT,
+            "nested sieve exceeds {NEST_LIMIT} Residuals"
+        );
+        // the highest level is the Sieve itself
+        let root = levels
+            .into_iter()
+            .fold(self.root.dilate(factor), |node, level| {
+                SieveNode::Intersection(Box::new(node), Box::new(level))
+            });
+        Self::from_node(root)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// The iterator returned by `iter_value`.
+/// ```
+/// let s = xensieve::Sieve::new("3@0|4@0");
+/// let mut s_iter = s.iter_value(17..);
+/// assert_eq!(s_iter.next().unwrap(), 18);
+/// assert_eq!(s_iter.next().unwrap(), 20);
+/// ```
+pub struct IterValue<I>
+where
+    I: Iterator,
+    I::Item: NumericElement,
+{
+    iterator: I,
+    sieve_node: SieveNode,
+    progressions: Option<progression::Progressions>,
+}
+
+impl<I> Iterator for IterValue<I>
+where
+    I: Iterator,
+    I::Item: NumericElement,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.progressions.as_mut() {
+            Some(progressions) => self
+                .iterator
+                .by_ref()
+                .find(|&p| progressions.contains(p.to_i128())),
+            None => self
+                .iterator
+                .by_ref()
+                .find(|&p| self.sieve_node.contains(p.to_i128())),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iterator.size_hint().1)
+    }
+}
+
+impl<I> DoubleEndedIterator for IterValue<I>
+where
+    I: DoubleEndedIterator,
+    I::Item: NumericElement,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let sieve_node = &self.sieve_node;
+        self.iterator
+            .by_ref()
+            .rev()
+            .find(|&p| sieve_node.contains(p.to_i128()))
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// The iterator returned by `iter_state`.
+/// ```
+/// let s = xensieve::Sieve::new("3@0|4@0");
+/// let mut s_iter = s.iter_state(17..);
+/// assert_eq!(s_iter.next().unwrap(), false);
+/// assert_eq!(s_iter.next().unwrap(), true);
+/// assert_eq!(s_iter.next().unwrap(), false);
+/// assert_eq!(s_iter.next().unwrap(), true);
+/// ```
+pub struct IterState<I>
+where
+    I: Iterator,
+    I::Item: NumericElement,
+{
+    iterator: I,
+    sieve_node: SieveNode,
+}
+
+impl<I> Iterator for IterState<I>
+where
+    I: Iterator, // the values returned by iterator
+    I::Item: NumericElement,
+{
+    type Item = bool; // the value returned
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.iterator.next() {
+            Some(p) => Some(self.sieve_node.contains(p.to_i128())),
+            None => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iterator.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for IterState<I>
+where
+    I: ExactSizeIterator,
+    I::Item: NumericElement,
+{
+}
+
+//------------------------------------------------------------------------------
+
+enum PositionLast {
+    Init,
+    Value(i128),
+}
+
+/// The iterator returned by `iter_interval` and `iter_interval_abs`.
+/// ```
+/// let s = xensieve::Sieve::new("3@0|4@0");
+/// let mut s_iter = s.iter_interval(17..);
+/// assert_eq!(s_iter.next().unwrap(), 2);
+/// assert_eq!(s_iter.next().unwrap(), 1);
+/// assert_eq!(s_iter.next().unwrap(), 3);
 /// ```
 pub struct IterInterval<I>
 where
-    I: Iterator<Item = i128>,
+    I: Iterator,
+    I::Item: NumericElement,
 {
     iterator: I,
     sieve_node: SieveNode,
     last: PositionLast,
+    absolute: bool,
+}
+
+impl<I> Iterator for IterInterval<I>
+where
+    I: Iterator,
+    I::Item: NumericElement,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for p in self.iterator.by_ref() {
+            let p = p.to_i128();
+            // while let Some(p) = self.iterator.next() {
+            if self.sieve_node.contains(p) {
+                match self.last {
+                    PositionLast::Init => {
+                        // drop the first value
+                        self.last = PositionLast::Value(p);
+                        continue;
+                    }
+                    PositionLast::Value(last) => {
+                        let post = if self.absolute && p < last {
+                            last.saturating_sub(p)
+                        } else {
+                            p.saturating_sub(last)
+                        };
+                        self.last = PositionLast::Value(p);
+                        return Some(I::Item::saturating_from_i128(post));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iterator.size_hint().1)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// The largest period for which `Values` counts its members when created, and `Sieve::nth` counts the members of one period rather than stepping through each member.
+const VALUES_COUNT_LIMIT: u64 = 1 << 16;
+
+/// The iterator returned by `values`.
+/// ```
+/// let s = xensieve::Sieve::new("3@0|4@0");
+/// let mut s_iter = s.values(17..=24);
+/// assert_eq!(s_iter.size_hint(), (4, Some(4)));
+/// assert_eq!(s_iter.next(), Some(18));
+/// assert_eq!(s_iter.size_hint(), (3, Some(3)));
+/// assert_eq!(s.values(0i64..).nth(1_000_000_000), Some(2_000_000_000));
+/// ```
+pub struct Values<'a, T> {
+    sieve: &'a Sieve,
+    pos: Option<i128>,
+    last: i128,
+    remaining: Option<u64>,
+    cycle: Option<i128>,
+    element: std::marker::PhantomData<T>,
+}
+
+impl<'a, T: NumericElement> Values<'a, T> {
+    fn new(sieve: &'a Sieve, bounds: Option<(i128, i128)>) -> Self {
+        let (pos, last) = match bounds {
+            Some((first, last)) => (Some(first), last),
+            None => (None, 0),
+        };
+        let remaining = match bounds {
+            None => Some(0),
+            Some((first, last))
+                if last.abs_diff(first) < u64::MAX as u128
+                    && sieve
+                        .root
+                        .cycle()
+                        .map_or(false, |p| p <= VALUES_COUNT_LIMIT) =>
+            {
+                Some(match last.checked_add(1) {
+                    Some(end) => sieve.count(first..end),
+                    None => sieve.count(first..last) + sieve.contains(last) as u64,
+                })
+            }
+            _ => None,
+        };
+        Self {
+            sieve,
+            pos,
+            last,
+            remaining,
+            cycle: sieve.root.cycle().map(|c| c as i128),
+            element: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, T: NumericElement> Iterator for Values<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let root = &self.sieve.root;
+        let pos = self.pos?;
+        // as the pattern repeats, a search of one cycle past pos finds a member if there is one
+        let limit = self.cycle.and_then(|c| pos.checked_add(c));
+        let v = match (self.last.checked_add(1), limit) {
+            (Some(end), Some(limit)) => root.next_member(pos, end.min(limit)),
+            (Some(end), None) => root.next_member(pos, end),
+            (None, Some(limit)) => root.next_member(pos, limit),
+            (None, None) => root
+                .next_member(pos, self.last)
+                .or_else(|| Some(self.last).filter(|v| self.sieve.contains(*v))),
+        };
+        self.pos = v.and_then(|v| v.checked_add(1));
+        if let Some(r) = self.remaining.as_mut() {
+            *r = r.saturating_sub(v.is_some() as u64);
+        }
+        v.map(|v| T::from_i128(v).unwrap())
+    }
+
+    /// Return the member `n` places ahead. Where the period is small, as for `size_hint`, the member is found with `Sieve::nth`, from the members of one period, rather than by stepping through the intermediate members.
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n == 0
+            || !self
+                .sieve
+                .root
+                .cycle()
+                .map_or(false, |p| p <= VALUES_COUNT_LIMIT)
+        {
+            for _ in 0..n {
+                self.next()?;
+            }
+            return self.next();
+        }
+        let v = self
+            .sieve
+            .nth(self.pos?, n as u64)
+            .filter(|v| *v <= self.last);
+        match v {
+            Some(v) => {
+                self.pos = v.checked_add(1);
+                if let Some(r) = self.remaining.as_mut() {
+                    *r = r.saturating_sub(n as u64 + 1);
+                }
+            }
+            None => {
+                self.pos = None;
+                self.remaining = Some(0);
+            }
+        }
+        v.map(|v| T::from_i128(v).unwrap())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining.and_then(|r| usize::try_from(r).ok());
+        match (remaining, self.pos) {
+            (Some(r), _) => (r, Some(r)),
+            (None, None) => (0, Some(0)),
+            (None, Some(pos)) => (
+                0,
+                (self.last.abs_diff(pos).checked_add(1)).and_then(|n| usize::try_from(n).ok()),
+            ),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// The iterator returned by `states`.
+pub struct States<'a> {
+    sieve: &'a Sieve,
+    bounds: Option<(i128, i128)>,
+}
+
+impl<'a> Iterator for States<'a> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (pos, last) = self.bounds?;
+        self.bounds = if pos < last {
+            Some((pos + 1, last))
+        } else {
+            None
+        };
+        Some(self.sieve.contains(pos))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.bounds {
+            Some((pos, last)) => match last
+                .abs_diff(pos)
+                .checked_add(1)
+                .and_then(|n| usize::try_from(n).ok())
+            {
+                Some(n) => (n, Some(n)),
+                None => (usize::MAX, None),
+            },
+            None => (0, Some(0)),
+        }
+    }
 }
 
-impl<I> Iterator for IterInterval<I>
-where
-    I: Iterator<Item = i128>,
-{
-    type Item = i128;
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_residual_a() {
+        let r1 = Residual::new(3, 0);
+        assert_eq!(r1.to_string(), String::from("3@0"));
+    }
+
+    #[test]
+    fn test_residual_b() {
+        let r1 = Residual::new(0, 2);
+        assert_eq!(r1.to_string(), "0@0");
+    }
+
+    //--------------------------------------------------------------------------
+    #[test]
+    fn test_residual_to_string_a() {
+        let r1 = Residual::new(3, 0);
+        assert_eq!(r1.to_string(), "3@0");
+    }
+
+    #[test]
+    fn test_residual_to_string_b() {
+        let r1 = Residual::new(8, 3);
+        assert_eq!(r1.to_string(), "8@3");
+    }
+
+    #[test]
+    fn test_residual_to_string_c() {
+        let r1 = Residual::new(5, 8);
+        assert_eq!(r1.to_string(), "5@3");
+    }
+
+    #[test]
+    fn test_residual_to_string_d() {
+        let r1 = Residual::new(5, 9);
+        assert_eq!(r1.to_string(), "5@4");
+    }
+
+    #[test]
+    fn test_residual_to_string_e() {
+        let r1 = Residual::new(5, 10);
+        assert_eq!(r1.to_string(), "5@0");
+    }
+
+    //--------------------------------------------------------------------------
+
+    // #[test]
+    // fn test_residual_not_a() {
+    //     let r1 = Residual::new(5, 10);
+    //     assert_eq!(r1.to_string(), String::from("!5@0"));
+    //     let r2 = !r1;
+    //     assert_eq!(r2.to_string(), "5@0");
+    //     let r3 = !r2;
+    //     assert_eq!(r3.to_string(), "!5@0");
+    // }
+
+    #[test]
+    fn test_residual_eq_a() {
+        let r1 = Residual::new(5, 2);
+        let r2 = Residual::new(5, 3);
+        assert_eq!(r1 == r2, false);
+        assert_eq!(r1 != r2, true);
+    }
+
+    #[test]
+    fn test_residual_eq_b() {
+        let r1 = Residual::new(5, 2);
+        let r2 = Residual::new(5, 2);
+        assert_eq!(r1 == r2, true);
+        assert_eq!(r1 != r2, false);
+    }
+
+    #[test]
+    fn test_residual_ord_a() {
+        let r1 = Residual::new(5, 2);
+        let r2 = Residual::new(5, 3);
+        assert!(r1 < r2);
+    }
+
+    #[test]
+    fn test_residual_ord_b() {
+        let r1 = Residual::new(2, 3);
+        let r2 = Residual::new(5, 3);
+        assert!(r1 < r2);
+    }
+
+    #[test]
+    fn test_residual_ord_c() {
+        let r1 = Residual::new(5, 3);
+        let r2 = Residual::new(5, 3);
+        assert!(r1 == r2);
+    }
+
+    //--------------------------------------------------------------------------
+
+    #[test]
+    fn test_residual_bitand_a() {
+        let r1 = Residual::new(4, 0);
+        let r2 = Residual::new(3, 0);
+        assert_eq!((r1 & r2).to_string(), "12@0");
+    }
+
+    #[test]
+    fn test_residual_bitand_b() {
+        let r1 = Residual::new(4, 0);
+        let r2 = Residual::new(3, 1);
+        assert_eq!((r1 & r2).to_string(), "12@4");
+    }
+
+    #[test]
+    fn test_residual_bitand_c() {
+        let r1 = Residual::new(5, 2);
+        let r2 = Residual::new(10, 3);
+        assert_eq!((r1 & r2).to_string(), "0@0");
+    }
+
+    #[test]
+    fn test_residual_bitand_d() {
+        let r1 = Residual::new(3, 2);
+        let r2 = Residual::new(3, 1);
+        assert_eq!((r1 & r2).to_string(), "0@0");
+    }
+
+    #[test]
+    #[should_panic(expected = "intersection of 4294967311@0 and 4294967357@0")]
+    fn test_residual_bitand_e() {
+        let _ = Residual::new(4294967311, 0) & Residual::new(4294967357, 0);
+    }
+
+    #[test]
+    fn test_residual_try_intersection_a() {
+        let r1 = Residual::new(4294967291, 5);
+        let r2 = Residual::new(4294967279, 9);
+        let r = r1.try_intersection(&r2).unwrap();
+        assert_eq!(r.modulus(), 4294967291 * 4294967279);
+        assert!(r1.contains(r.shift() as i128) && r2.contains(r.shift() as i128));
+        let e = r.try_intersection(&Residual::new(3, 1)).unwrap_err();
+        assert!(matches!(e, SieveError::Overflow(_)));
+        assert!(e.to_string().contains("least common multiple"), "{e}");
+        assert_eq!(
+            Residual::new(u64::MAX, 3).try_intersection(&Residual::new(u64::MAX, 3)),
+            Ok(Residual::new(u64::MAX, 3))
+        );
+    }
+
+    //--------------------------------------------------------------------------
+
+    #[test]
+    fn test_residual_contains_a() {
+        let r1 = Residual::new(3, 0);
+        assert_eq!(r1.contains(-3), true);
+        assert_eq!(r1.contains(-2), false);
+        assert_eq!(r1.contains(-1), false);
+        assert_eq!(r1.contains(0), true);
+        assert_eq!(r1.contains(1), false);
+        assert_eq!(r1.contains(2), false);
+        assert_eq!(r1.contains(3), true);
+        assert_eq!(r1.contains(4), false);
+        assert_eq!(r1.contains(5), false);
+    }
+
+    #[test]
+    fn test_residual_contains_b() {
+        let r1 = Residual::new(0, 0);
+        assert_eq!(r1.contains(-2), false);
+        assert_eq!(r1.contains(-1), false);
+        assert_eq!(r1.contains(0), false);
+        assert_eq!(r1.contains(1), false);
+        assert_eq!(r1.contains(2), false);
+        assert_eq!(r1.contains(3), false);
+    }
+
+    #[test]
+    fn test_residual_contains_c() {
+        let r1 = Residual::new(3, 1);
+        assert_eq!(r1.contains(-3), false);
+        assert_eq!(r1.contains(-2), true);
+        assert_eq!(r1.contains(-1), false);
+        assert_eq!(r1.contains(0), false);
+        assert_eq!(r1.contains(1), true);
+        assert_eq!(r1.contains(2), false);
+        assert_eq!(r1.contains(3), false);
+        assert_eq!(r1.contains(4), true);
+    }
+
+    #[test]
+    fn test_residual_accessors_a() {
+        let r1 = Residual::new(7, 9);
+        assert_eq!(r1.modulus(), 7);
+        assert_eq!(r1.shift(), 2);
+        let r2 = Residual::new(0, 9);
+        assert_eq!(r2.modulus(), 0);
+        assert_eq!(r2.shift(), 0);
+    }
+
+    #[test]
+    fn test_residual_into_sieve_a() {
+        let s1: Sieve = Residual::new(4, 1).into();
+        assert_eq!(s1.to_string(), "Sieve{4@1}");
+        let s2 = Sieve::from(Residual::new(3, 0)) | Sieve::from(Residual::new(4, 1));
+        assert_eq!(
+            s2.iter_value(0..10).collect::<Vec<_>>(),
+            vec![0, 1, 3, 5, 6, 9]
+        );
+    }
+
+    #[test]
+    fn test_residual_next_member_a() {
+        let r1 = Residual::new(3, 1);
+        assert_eq!(r1.next_member(-5), Some(-5));
+        assert_eq!(r1.next_member(-4), Some(-2));
+        assert_eq!(r1.next_member(2), Some(4));
+        assert_eq!(r1.next_member(4), Some(4));
+        assert_eq!(Residual::new(0, 0).next_member(4), None);
+    }
+
+    //--------------------------------------------------------------------------
+
+    #[test]
+    fn test_sieve_new_a() {
+        let s1 = Sieve::new("3@1");
+        assert_eq!(s1.to_string(), "Sieve{3@1}");
+    }
+
+    #[test]
+    fn test_sieve_new_b() {
+        let s1 = Sieve::new("3@4");
+        assert_eq!(s1.to_string(), "Sieve{3@1}");
+    }
+
+    #[test]
+    fn test_sieve_new_c() {
+        let s1 = Sieve::new("5@5");
+        assert_eq!(s1.to_string(), "Sieve{5@0}");
+    }
+
+    #[test]
+    fn test_sieve_new_d() {
+        let s1 = Sieve::new("0@5");
+        assert_eq!(s1.to_string(), "Sieve{0@0}");
+    }
+
+    #[test]
+    fn test_sieve_contains_a() {
+        let r1 = Residual::new(3, 0);
+        let s1 = SieveNode::Unit(r1);
+
+        let pos = vec![-3, -2, -1, 0, 1];
+        let val = vec![true, false, false, true, false];
+        for (p, b) in pos.iter().zip(val.iter()) {
+            assert_eq!(s1.contains(*p), *b);
+        }
+    }
+
+    #[test]
+    fn test_sieve_contains_b() {
+        let r1 = Residual::new(3, 0);
+        let r2 = Residual::new(3, 1);
+        let s1 = SieveNode::Union(Box::new(SieveNode::Unit(r1)), Box::new(SieveNode::Unit(r2)));
+
+        assert_eq!(s1.contains(-2), true);
+        assert_eq!(s1.contains(-1), false);
+        assert_eq!(s1.contains(0), true);
+        assert_eq!(s1.contains(1), true);
+        assert_eq!(s1.contains(2), false);
+        assert_eq!(s1.contains(3), true);
+        assert_eq!(s1.contains(4), true);
+    }
+
+    //--------------------------------------------------------------------------
+
+    #[test]
+    fn test_sieve_iter_value_element_a() {
+        let s1 = Sieve::new("3@0|4@0");
+        let v1: Vec<i32> = s1.iter_value(-6i32..7).collect();
+        assert_eq!(v1, vec![-6, -4, -3, 0, 3, 4, 6]);
+        let v2: Vec<u8> = s1.iter_value(250u8..=255).collect();
+        assert_eq!(v2, vec![252, 255]);
+    }
+
+    #[test]
+    fn test_sieve_progression_residuals_limit_a() {
+        // 22 intersected unions would expand to 2^22 Residuals
+        let e = vec!["(2@0|3@1)"; 22].join("&");
+        let s = Sieve::new(&e);
+        assert_eq!(s.root.progression_residuals(), None);
+        let expected = Sieve::new("2@0|3@1").scan(0..20);
+        assert_eq!(s.iter_value(0..20).collect::<Vec<_>>(), expected);
+        let values: Vec<i128> = (0..300).collect();
+        let post = s.contains_batch(&values);
+        assert_eq!(
+            post.iter().filter(|b| **b).count(),
+            Sieve::new("2@0|3@1").count(0..300) as usize
+        );
+        assert_eq!(s.iter_upcoming(0, 20).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_sieve_iter_value_progression_a() {
+        for e in [
+            "10000@3",
+            "(4@1|6@0)&(5@2|3@0)|7@6",
+            "4@1&6@0|9@2",
+            "3@0-4@0",
+        ] {
+            let s = Sieve::new(e);
+            assert_eq!(
+                s.root.progression_residuals().is_some(),
+                !e.contains('-'),
+                "{e}"
+            );
+            let input: Vec<i128> = (-200..200).chain((-50..50).rev()).chain([9, 9]).collect();
+            assert_eq!(
+                s.iter_value(input.iter().copied()).collect::<Vec<_>>(),
+                input
+                    .iter()
+                    .copied()
+                    .filter(|v| s.root.contains(*v))
+                    .collect::<Vec<_>>(),
+                "{e}"
+            );
+        }
+        assert_eq!(
+            Sieve::new("10000@3")
+                .iter_value(0..100_000)
+                .collect::<Vec<_>>(),
+            (0..10).map(|i| i * 10000 + 3).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_sieve_contains_batch_classes_a() {
+        for e in ["(5@0|4@2)&(3@0|7@1)|11@3", "(5@0|4@2)&!(30@10)"] {
+            let s = Sieve::new(e);
+            let values: Vec<i64> = (-1000..1000).collect();
+            let expected: Vec<bool> = values.iter().map(|v| s.contains(*v as i128)).collect();
+            assert_eq!(s.contains_batch(&values), expected, "{e}");
+            assert_eq!(s.contains_batch(&values[..10]), expected[..10], "{e}");
+        }
+    }
+
+    #[test]
+    fn test_sieve_values_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^7@3");
+        assert_eq!(
+            s.values(-100..100).collect::<Vec<i64>>(),
+            s.iter_value(-100i64..100).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            s.values(..).collect::<Vec<i8>>(),
+            s.iter_value(i8::MIN..=i8::MAX).collect::<Vec<_>>()
+        );
+        assert_eq!(s.values(5..5).count(), 0);
+        let s = Sieve::new("1@0");
+        assert_eq!(
+            s.values(i128::MAX - 2..).collect::<Vec<_>>(),
+            vec![i128::MAX - 2, i128::MAX - 1, i128::MAX]
+        );
+        assert_eq!(s.values(u64::MAX - 1..).count(), 2);
+    }
+
+    #[test]
+    fn test_sieve_states_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^7@3");
+        assert_eq!(
+            s.states(-100i128..=100).collect::<Vec<_>>(),
+            s.iter_state(-100i128..=100).collect::<Vec<_>>()
+        );
+        assert_eq!(s.states(0u16..0).count(), 0);
+        use std::ops::Bound;
+        let full: (Bound<u8>, Bound<u8>) = (Bound::Unbounded, Bound::Unbounded);
+        assert_eq!(s.states(full).count(), 256);
+    }
+
+    #[test]
+    fn test_sieve_iter_value_rev_a() {
+        for e in ["3@0|4@0", "(5@0|4@2)&!(30@10)^7@3"] {
+            let s = Sieve::new(e);
+            let mut forward: Vec<i32> = s.iter_value(-100i32..=100).collect();
+            forward.reverse();
+            assert_eq!(
+                s.iter_value(-100i32..=100).rev().collect::<Vec<_>>(),
+                forward
+            );
+            let mut it = s.iter_value(-100i32..=100);
+            let (first, last) = (it.next(), it.next_back());
+            assert_eq!(
+                (first, last),
+                (forward.last().copied(), forward.first().copied())
+            );
+            assert_eq!(it.count(), forward.len() - 2);
+        }
+    }
+
+    #[test]
+    fn test_sieve_iter_value_down_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^7@3");
+        let mut expected: Vec<i128> = s.scan(-200..51);
+        expected.reverse();
+        assert_eq!(
+            s.iter_value_down(50)
+                .take_while(|v| *v >= -200)
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(Sieve::new("1@0").iter_value_down(i128::MIN).count(), 0);
+    }
+
+    #[test]
+    fn test_sieve_iter_value_down_b() {
+        // a collapsed intersection is searched over one cycle only
+        assert_eq!(Sieve::new("5@2&10@3").iter_value_down(0).next(), None);
+        assert_eq!(
+            Sieve::new("5@2&10@3").iter_value_down(i128::MAX).next(),
+            None
+        );
+        let s = Sieve::new("5@2&10@3|7@1");
+        assert_eq!(
+            s.iter_value_down(0).take(2).collect::<Vec<_>>(),
+            vec![-6, -13]
+        );
+    }
+
+    #[test]
+    fn test_sieve_find_interval_pattern_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^7@3");
+        let members = s.scan(-100..100);
+        for pattern in [vec![1], vec![4, 1], vec![1, 3, 1], vec![60]] {
+            let expected: Vec<i128> = members
+                .windows(pattern.len() + 1)
+                .filter(|w| {
+                    w.windows(2)
+                        .map(|p| p[1] - p[0])
+                        .eq(pattern.iter().copied())
+                })
+                .map(|w| w[0])
+                .collect();
+            assert_eq!(
+                s.find_interval_pattern(&pattern, -100..100),
+                expected,
+                "{pattern:?}"
+            );
+        }
+        assert_eq!(s.find_interval_pattern(&[], 0..10), s.scan(0..10));
+        assert_eq!(Sieve::empty().find_interval_pattern(&[1], 0..10), vec![]);
+    }
+
+    #[test]
+    fn test_sieve_size_hint_a() {
+        use std::ops::Bound;
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^7@3");
+        let mut it = s.values(-100i128..100);
+        let n = s.scan(-100..100).len();
+        for i in 0..=n {
+            assert_eq!(it.size_hint(), (n - i, Some(n - i)));
+            assert_eq!(it.next().is_some(), i < n);
+        }
+        let full: (Bound<i128>, Bound<i128>) = (Bound::Unbounded, Bound::Unbounded);
+        assert_eq!(s.values(full).size_hint(), (0, None));
+        assert!(s.values(u8::MIN..).collect::<Vec<u8>>().capacity() > 0);
+        assert_eq!(
+            s.values(i128::MAX - 3..).size_hint().0,
+            s.values(i128::MAX - 3..).count()
+        );
+        assert_eq!(Sieve::new("1@0").values(i128::MAX - 3..).count(), 4);
+        assert_eq!(s.values(5i128..5).size_hint(), (0, Some(0)));
+
+        let it = s.iter_state(0i32..10);
+        assert_eq!(it.len(), 10);
+        assert_eq!(s.iter_value(0i128..10).size_hint(), (0, Some(10)));
+        assert_eq!(s.iter_interval(0i128..10).size_hint(), (0, Some(10)));
+        assert_eq!(s.states(full).size_hint(), (usize::MAX, None));
+        let mut it = s.states(-3i8..=3);
+        it.next();
+        assert_eq!(it.size_hint(), (6, Some(6)));
+    }
+
+    #[test]
+    fn test_sieve_values_nth_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^7@3");
+        let members = s.scan(-300..300);
+        for step in [0, 1, 2, 7, 40] {
+            let mut it = s.values(-300..300);
+            let mut expected = members.iter().copied().skip(step).step_by(step + 1);
+            loop {
+                let (v, e) = (it.nth(step), expected.next());
+                assert_eq!(v, e, "{step}");
+                if v.is_none() {
+                    break;
+                }
+                assert_eq!(
+                    it.size_hint().0,
+                    members.len() - members.iter().position(|m| Some(*m) == v).unwrap() - 1
+                );
+            }
+        }
+        assert_eq!(s.values(0..10).nth(100), None);
+        let s = Sieve::new("1000003@0|1000033@1");
+        assert_eq!(s.values(0..).nth(2), Some(1000003));
+    }
+
+    #[test]
+    fn test_sieve_values_nth_b() {
+        // nth agrees with stepping by next over full, empty, and sparse sieves
+        for e in [
+            "!(0@0)",
+            "1@0",
+            "0@0",
+            "5@2&10@3",
+            "1000@7|999@3",
+            "(5@0|4@2)&!(30@10)^7@3",
+        ] {
+            let s = Sieve::new(e);
+            for k in [0, 1, 3, 10, 2000] {
+                // Skip calls nth, so the expected member is found with next alone
+                let mut it = s.values(-5i128..5000);
+                let expected = (0..k)
+                    .try_for_each(|_| it.next().map(|_| ()))
+                    .and_then(|_| it.next());
+                assert_eq!(s.values(-5i128..5000).nth(k), expected, "{e} {k}");
+            }
+        }
+        assert_eq!(Sieve::new("!(0@0)").values(0..10).nth(3), Some(3));
+    }
+
+    #[test]
+    fn test_sieve_values_b() {
+        // an empty intersection is not searched to the end of the range
+        let s = Sieve::new("2@0&2@1|3@0");
+        assert_eq!(s.values(0u64..).take(3).count(), 3);
+        assert_eq!(s.values(0u64..).take(3).collect::<Vec<_>>(), vec![0, 3, 6]);
+        assert_eq!(Sieve::new("2@0&2@1").values(0u64..).next(), None);
+        assert_eq!(Sieve::new("2@0&2@1").values(..).next(), None::<i128>);
+        assert_eq!(
+            Sieve::new("3@0").values(i128::MAX - 10..).last(),
+            Some(i128::MAX - 1)
+        );
+    }
+
+    #[test]
+    fn test_sieve_iter_segment_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^7@3|3@1");
+        let members = s.scan(-100..100);
+        let runs: Vec<(i128, u64)> = s.iter_segment(-100..100).collect();
+        let mut expected: Vec<(i128, u64)> = Vec::new();
+        for m in members {
+            match expected.last_mut() {
+                Some((first, len)) if *first + *len as i128 == m => *len += 1,
+                _ => expected.push((m, 1)),
+            }
+        }
+        assert_eq!(runs, expected);
+        assert_eq!(
+            Sieve::new("1@0").iter_segment(-5..5).collect::<Vec<_>>(),
+            vec![(-5, 10)]
+        );
+        assert_eq!(Sieve::empty().iter_segment(-5..5).count(), 0);
+        assert_eq!(Sieve::new("1@0").iter_segment(5..5).count(), 0);
+    }
+
+    #[test]
+    fn test_sieve_iter_segment_b() {
+        // empty intersections and symmetric differences are searched over one cycle only
+        let range = 0..1_000_000_000_000_000;
+        assert_eq!(
+            Sieve::new("3@0^3@0").iter_segment(range.clone()).next(),
+            None
+        );
+        assert_eq!(
+            Sieve::new("5@2&10@3").iter_segment(range.clone()).next(),
+            None
+        );
+        assert_eq!(
+            Sieve::new("5@2&10@3|(3@0^3@0)|999@998")
+                .iter_segment(range)
+                .next(),
+            Some((998, 1))
+        );
+    }
+
+    #[test]
+    fn test_sieve_iter_upcoming_a() {
+        for e in ["(5@0|4@2)&(3@0|7@1)|11@3", "(5@0|4@2)&!(30@10)^7@3", "0@0"] {
+            let s = Sieve::new(e);
+            for now in (-100..100).step_by(7) {
+                assert_eq!(
+                    s.iter_upcoming(now, 16).collect::<Vec<_>>(),
+                    s.scan(now..now + 16),
+                    "{e} {now}"
+                );
+            }
+            assert_eq!(s.iter_upcoming(3, 0).count(), 0);
+        }
+        assert_eq!(
+            Sieve::new("3@0")
+                .iter_upcoming(i128::MAX - 4, 10)
+                .collect::<Vec<_>>(),
+            vec![i128::MAX - 4, i128::MAX - 1]
+        );
+    }
+
+    #[test]
+    fn test_sieve_iter_upcoming_b() {
+        // sieves that are not progressions are searched over one period per step
+        assert_eq!(
+            Sieve::new("3@0^3@0").iter_upcoming(0, u64::MAX).next(),
+            None
+        );
+        assert_eq!(
+            Sieve::new("5@2-5@2").iter_upcoming(0, u64::MAX).next(),
+            None
+        );
+        let s = Sieve::new("(3@0^6@0)-9@3");
+        assert_eq!(
+            s.iter_upcoming(-5, u64::MAX).take(4).collect::<Vec<_>>(),
+            vec![-3, 9, 15, 27]
+        );
+    }
+
+    #[test]
+    fn test_sieve_iter_state_element_a() {
+        let s1 = Sieve::new("3@0|4@0");
+        let v1: Vec<bool> = s1.iter_state(0u16..5).collect();
+        assert_eq!(v1, vec![true, false, false, true, true]);
+    }
+
+    #[test]
+    fn test_sieve_iter_interval_element_a() {
+        let s1 = Sieve::new("3@0|4@0");
+        let v1: Vec<i16> = s1.iter_interval((0i16..=6).rev()).collect();
+        assert_eq!(v1, vec![-2, -1, -3]);
+        let v2: Vec<u32> = s1.iter_interval_abs((0u32..=6).rev()).collect();
+        assert_eq!(v2, vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn test_sieve_iter_interval_element_b() {
+        // intervals not representable in the element type saturate
+        let s1 = Sieve::new("3@0|4@0");
+        let v1: Vec<u32> = s1.iter_interval((0u32..=6).rev()).collect();
+        assert_eq!(v1, vec![0, 0, 0]);
+        let s2 = Sieve::new("1000@872|1000@127");
+        let v2: Vec<i8> = s2.iter_interval(-128i8..=127).collect();
+        assert_eq!(v2, vec![127]);
+        let s3 = Sieve::new("1@0");
+        let v3: Vec<i128> = s3
+            .iter_interval([i128::MIN, i128::MAX, i128::MIN].into_iter())
+            .collect();
+        assert_eq!(v3, vec![i128::MAX, i128::MIN]);
+    }
+
+    #[test]
+    fn test_sieve_node_next_member_a() {
+        // searches of inversions and differences are bounded to one cycle
+        let n = Sieve::new("!(1@0)").root;
+        assert_eq!(n.next_member(0, i128::MAX), None);
+        let n = Sieve::new("3@0-1@0").root;
+        assert_eq!(n.next_member(i128::MIN, i128::MAX), None);
+        let n = Sieve::new("!(3@0|3@1)").root;
+        assert_eq!(n.next_member(-9, i128::MAX), Some(-7));
+        let n = Sieve::new("!(1000@0)-3@0").root;
+        assert_eq!(n.next_member(0, i128::MAX), Some(1));
+        assert_eq!(n.next_member(i128::MAX - 2, i128::MAX), Some(i128::MAX - 2));
+    }
+
+    #[test]
+    fn test_sieve_first_in_a() {
+        for e in [
+            "3@0|4@1",
+            "6@0&4@0",
+            "6@1&4@0",
+            "!(3@0|5@1)",
+            "(5@0|4@2)&!(30@10)",
+            "7@2^3@1",
+            "12@0|12@7-4@3",
+            "0@0",
+            "!(0@0)&2@1",
+        ] {
+            let s1 = Sieve::new(e);
+            for start in -30..30 {
+                for end in start..40 {
+                    assert_eq!(
+                        s1.first_in(start..end),
+                        s1.iter_value(start..end).next(),
+                        "{e} {start}..{end}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sieve_first_in_b() {
+        let s1 = Sieve::new("1000003@5&1000033@7");
+        assert!(s1.any_in(0..i128::MAX));
+        let s2 = Sieve::new("6@1&4@0");
+        assert!(!s2.any_in(i128::MIN..i128::MAX));
+    }
+
+    #[test]
+    fn test_sieve_unit_a() {
+        assert_eq!(Sieve::unit(4, 9).to_string(), "Sieve{4@1}");
+        assert_eq!(Sieve::unit(0, 9).to_string(), "Sieve{0@0}");
+        assert_eq!(Sieve::empty().to_string(), "Sieve{0@0}");
+        assert_eq!(Sieve::all().to_string(), "Sieve{1@0}");
+    }
+
+    #[test]
+    fn test_sieve_from_residuals_a() {
+        let s1 = Sieve::from_residuals(vec![(5, 0), (4, 2), (3, 1)]);
+        assert_eq!(s1.to_string(), "Sieve{5@0|4@2|3@1}");
+        assert_eq!(
+            s1.iter_value(0..11).collect::<Vec<_>>(),
+            vec![0, 1, 2, 4, 5, 6, 7, 10]
+        );
+        let s2 = Sieve::from_residuals(Vec::new());
+        assert_eq!(s2.to_string(), "Sieve{0@0}");
+    }
+
+    #[test]
+    fn test_sieve_channels_a() {
+        let s1 = Sieve::new("4@0|(6@1-3@0)|!(!(4@0)|2@1)");
+        assert_eq!(
+            s1.channels(),
+            vec![Residual::new(4, 0), Residual::new(6, 1)]
+        );
+        let s2 = Sieve::new("!(3@0)");
+        assert_eq!(s2.channels(), vec![]);
+    }
+
+    #[test]
+    fn test_sieve_iter_event_a() {
+        let s1 = Sieve::new("4@0|6@1^3@0");
+        let e1: Vec<_> = s1.iter_event(0..8).collect();
+        assert_eq!(
+            e1,
+            vec![
+                Event {
+                    value: 0,
+                    channels: vec![0, 2]
+                },
+                Event {
+                    value: 1,
+                    channels: vec![1]
+                },
+                Event {
+                    value: 3,
+                    channels: vec![2]
+                },
+                Event {
+                    value: 4,
+                    channels: vec![0]
+                },
+                Event {
+                    value: 6,
+                    channels: vec![2]
+                },
+                Event {
+                    value: 7,
+                    channels: vec![1]
+                },
+            ]
+        );
+        let s2 = Sieve::new("!(3@0)");
+        let e2: Vec<_> = s2.iter_event(0..3).collect();
+        assert_eq!(
+            e2[0],
+            Event {
+                value: 1,
+                channels: vec![]
+            }
+        );
+    }
+
+    #[test]
+    fn test_sieve_default_a() {
+        let s1 = Sieve::default();
+        assert_eq!(s1.to_string(), "Sieve{0@0}");
+        assert_eq!(s1.iter_value(-5..5).count(), 0);
+    }
+
+    #[test]
+    fn test_sieve_try_from_a() {
+        let s1 = Sieve::try_from("3@0|5@1").unwrap();
+        assert_eq!(s1.to_string(), "Sieve{3@0|5@1}");
+        let s2: Result<Sieve, _> = "3@0|".try_into();
+        assert_eq!(s2.unwrap_err(), SieveError::MissingOperand);
+        let s3 = Sieve::try_from(String::from("Sieve{3@0&!(4@0)}")).unwrap();
+        assert_eq!(s3.to_string(), "Sieve{3@0&!(4@0)}");
+    }
+
+    #[test]
+    fn test_sieve_iter_by_residual_a() {
+        let s1 = Sieve::new("(3@0|5@1)&!(15@0)");
+        let stems: Vec<_> = s1
+            .iter_by_residual(-5..17)
+            .into_iter()
+            .map(|(r, i)| (r.to_string(), i.collect::<Vec<_>>()))
+            .collect();
+        assert_eq!(
+            stems,
+            vec![
+                (
+                    String::from("3@0"),
+                    vec![(-3, false), (3, false), (6, true), (9, false), (12, false)]
+                ),
+                (
+                    String::from("5@1"),
+                    vec![(-4, false), (1, false), (6, true), (11, false), (16, false)]
+                ),
+            ]
+        );
+        // every member is in some stem
+        let mut values: Vec<i128> = stems
+            .iter()
+            .flat_map(|(_, v)| v.iter().map(|(v, _)| *v))
+            .collect();
+        values.sort_unstable();
+        values.dedup();
+        assert_eq!(values, s1.iter_value(-5..17).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_sieve_iter_by_residual_b() {
+        let s1 = Sieve::new("0@0|!(2@0)");
+        let stems = s1.iter_by_residual(0..10);
+        assert_eq!(stems.len(), 1);
+        assert_eq!(stems.into_iter().next().unwrap().1.count(), 0);
+    }
+
+    #[test]
+    fn test_sieve_eq_a() {
+        assert_eq!(Sieve::new("(3@0|4@1)&!5@2"), Sieve::new("(3@3|4@1)&!(5@7)"));
+        assert_ne!(Sieve::new("3@0|4@1"), Sieve::new("3@0^4@1"));
+        assert_ne!(Sieve::new("3@0-4@1"), Sieve::new("3@0&!(4@1)"));
+        assert_eq!(Sieve::new("0@5"), Sieve::empty());
+    }
+
+    #[test]
+    fn test_sieve_hash_a() {
+        use std::collections::HashMap;
+        let mut map: HashMap<Sieve, usize> = HashMap::new();
+        map.insert(Sieve::new("3@0|4@1"), 1);
+        map.insert(Sieve::new("4@1|3@0"), 2);
+        map.insert(Sieve::new("3@3|4@1"), 3);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&Sieve::new("3@0|4@1")], 3);
+    }
+
+    #[test]
+    fn test_sieve_scale_rational_a() {
+        let s1 = Sieve::new("(4@0|6@2)&!(12@0)");
+        let (s2, exact) = s1.scale_rational(3, 2, Rounding::Floor);
+        assert!(exact);
+        assert_eq!(s2.to_string(), "Sieve{(6@0|9@3)&!(18@0)}");
+        let scaled: Vec<i128> = s1.iter_value(0..40).map(|v| v * 3 / 2).collect();
+        assert_eq!(s2.iter_value(0..60).collect::<Vec<_>>(), scaled);
+    }
+
+    #[test]
+    fn test_sieve_scale_rational_b() {
+        let s1 = Sieve::new("5@3|0@0");
+        assert_eq!(
+            s1.scale_rational(3, 2, Rounding::Floor),
+            (Sieve::new("7@4|0@0"), false)
+        );
+        assert_eq!(
+            s1.scale_rational(3, 2, Rounding::Ceiling),
+            (Sieve::new("8@5|0@0"), false)
+        );
+        assert_eq!(
+            s1.scale_rational(3, 2, Rounding::Nearest),
+            (Sieve::new("8@5|0@0"), false)
+        );
+        assert_eq!(
+            s1.scale_rational(1, 10, Rounding::Floor),
+            (Sieve::new("1@0|0@0"), false)
+        );
+    }
+
+    #[test]
+    fn test_sieve_period_a() {
+        assert_eq!(Sieve::new("3@0").period(), Some(3));
+        assert_eq!(Sieve::new("3@0|0@0").period(), Some(3));
+        assert_eq!(Sieve::new("!(0@0)").period(), None);
+        assert_eq!(Sieve::new("3@0^4@1-6@5").period(), Some(12));
+        let s1 = Sieve::new("18446744073709551557@0|18446744073709551533@0");
+        assert_eq!(s1.period(), None);
+    }
+
+    #[test]
+    fn test_sieve_period_b() {
+        let s1 = Sieve::new("(5@0|4@2)&!(30@10)");
+        let p = s1.period().unwrap() as i128;
+        assert_eq!(p, 60);
+        let a: Vec<i128> = s1.iter_value(0..p).collect();
+        let b: Vec<i128> = s1.iter_value(p..2 * p).map(|v| v - p).collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_sieve_implied_grid_a() {
+        assert_eq!(Sieve::new("6@0|9@0").implied_grid(0..100), Some(3));
+        assert_eq!(Sieve::new("6@0|9@0").implied_grid(0..9), Some(6));
+        assert_eq!(Sieve::new("6@1|4@3").implied_grid(-20..20), Some(2));
+        assert_eq!(Sieve::new("6@1|4@0").implied_grid(-20..20), Some(1));
+        assert_eq!(Sieve::new("8@5").implied_grid(0..100), Some(8));
+        assert_eq!(Sieve::new("8@5").implied_grid(0..13), None);
+        assert_eq!(Sieve::empty().implied_grid(0..100), None);
+    }
+
+    #[test]
+    fn test_sieve_is_equivalent_a() {
+        let s1 = Sieve::new("3@0|4@0");
+        assert!(s1.is_equivalent(&Sieve::new("4@0|3@0")));
+        assert!(s1.is_equivalent(&Sieve::new("12@0|12@3|12@4|12@6|12@8|12@9")));
+        assert!(!s1.is_equivalent(&Sieve::new("12@0|12@3|12@4|12@6|12@8")));
+        assert!(Sieve::new("6@0&4@1").is_equivalent(&Sieve::empty()));
+        assert!(Sieve::new("!(0@0)").is_equivalent(&Sieve::all()));
+        assert!(Sieve::new("5@2-5@2").is_equivalent(&Sieve::empty()));
+    }
+
+    #[test]
+    fn test_sieve_phase_a() {
+        let s1 = Sieve::new("(5@0|4@2)&!(30@10)");
+        let members: Vec<i128> = s1.iter_value(0..60).collect();
+        for v in -120..120 {
+            let (position, index) = s1.phase(v).unwrap();
+            assert_eq!(position as i128, v.rem_euclid(60));
+            assert_eq!(index, members.iter().position(|m| *m == position as i128));
+        }
+    }
+
+    #[test]
+    fn test_sieve_phase_b() {
+        assert_eq!(Sieve::empty().phase(3), None);
+        assert_eq!(Sieve::new("4@1").phase(9), Some((1, Some(0))));
+    }
+
+    #[test]
+    fn test_sieve_collapsed_empty_a() {
+        for (e, collapsed) in [
+            ("5@2&10@3", true),
+            ("(4@0|6@0)&(4@1|6@3)", true),
+            ("!(5@2&10@3)", true),
+            ("(5@2&10@3)&3@0", true),
+            ("5@2&10@7", false),
+            ("0@0&3@1", false),
+            ("5@2&!(5@2)", false),
+            ("3@0|4@1", false),
+        ] {
+            let s = Sieve::new(e);
+            assert_eq!(s.collapsed_empty(), collapsed, "{e}");
+        }
+        let s = Sieve::new("6@0") & Sieve::new("4@1");
+        assert!(s.collapsed_empty());
+        assert!(s.is_empty());
+    }
+
+    #[cfg(feature = "warnings")]
+    #[test]
+    fn test_sieve_collapsed_warning_a() {
+        use std::sync::Mutex;
+
+        struct Capture(Mutex<Vec<String>>);
+
+        impl log::Log for Capture {
+            fn enabled(&self, metadata: &log::Metadata) -> bool {
+                metadata.level() <= log::Level::Warn
+            }
+            fn log(&self, record: &log::Record) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+            fn flush(&self) {}
+        }
+
+        static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+        log::set_logger(&CAPTURE).unwrap();
+        log::set_max_level(log::LevelFilter::Warn);
+
+        let _ = Sieve::new("6@0") & Sieve::new("4@1");
+        let _ = Sieve::new("6@0") & Sieve::new("4@2");
+        let records = CAPTURE.0.lock().unwrap();
+        assert!(records.contains(&String::from(
+            "the intersection of 6@0 and 4@1 has no members"
+        )));
+        assert!(!records.iter().any(|r| r.contains("4@2")));
+    }
+
+    #[test]
+    fn test_sieve_is_empty_a() {
+        assert!(Sieve::empty().is_empty());
+        assert!(Sieve::new("!(1@0)").is_empty());
+        assert!(Sieve::new("(6@0|10@0)&15@1&!(2@0)").is_empty());
+        assert!(!Sieve::new("3@0-(3@0&5@0)").is_empty());
+        assert!(!Sieve::new("1000003@5&1000033@7").is_empty());
+    }
+
+    #[test]
+    fn test_sieve_is_full_a() {
+        assert!(Sieve::all().is_full());
+        assert!(Sieve::new("!(0@0)").is_full());
+        assert!(Sieve::new("3@0|!(3@0)").is_full());
+        assert!(!Sieve::new("2@0|3@1|6@5").is_full());
+        assert!(Sieve::new("2@0|3@1|6@5|6@3").is_full());
+    }
+
+    #[test]
+    fn test_sieve_iter_cycles_a() {
+        let s1 = Sieve::new("5@0|3@1");
+        let c1: Vec<_> = s1.iter_cycles(-16..31).collect();
+        assert_eq!(
+            c1.iter().map(|(c, _)| *c).collect::<Vec<_>>(),
+            vec![-2, -1, 0, 1, 2]
+        );
+        assert_eq!(c1[0].1, vec![]);
+        assert_eq!(c1[2].1, vec![0, 1, 4, 5, 7, 10, 13]);
+        assert_eq!(c1[4].1, vec![30]);
+        for (c, values) in c1.iter() {
+            assert!(values.iter().all(|v| v.div_euclid(15) == *c));
+        }
+        assert_eq!(
+            c1.into_iter().flat_map(|(_, v)| v).collect::<Vec<_>>(),
+            s1.iter_value(-16..31).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_sieve_iter_cycles_b() {
+        assert_eq!(Sieve::empty().iter_cycles(0..100).count(), 0);
+        let start = 10;
+        assert_eq!(Sieve::new("3@0").iter_cycles(start..start).count(), 0);
+    }
+
+    #[test]
+    fn test_sieve_is_subset_of_a() {
+        let s1 = Sieve::new("6@0|10@0");
+        let s2 = Sieve::new("2@0");
+        assert!(s1.is_subset_of(&s2));
+        assert!(!s2.is_subset_of(&s1));
+        assert!(s2.is_superset_of(&s1));
+        assert!(!s1.is_superset_of(&s2));
+        assert!(s1.is_subset_of(&s1));
+        assert!(Sieve::empty().is_subset_of(&s1));
+        assert!(s1.is_subset_of(&Sieve::all()));
+    }
+
+    #[test]
+    fn test_sieve_is_disjoint_with_a() {
+        let s1 = Sieve::new("6@0|10@0");
+        assert!(s1.is_disjoint_with(&Sieve::new("2@1")));
+        assert!(!s1.is_disjoint_with(&Sieve::new("15@5")));
+        assert!(s1.is_disjoint_with(&!&s1));
+        assert!(Sieve::empty().is_disjoint_with(&Sieve::empty()));
+    }
+
+    #[test]
+    fn test_sieve_to_dnf_a() {
+        for e in [
+            "3@0|4@1",
+            "!(3@0|4@1)",
+            "(5@0|4@2)&!(30@10)",
+            "6@1^4@3",
+            "12@0|12@7-4@3",
+            "!(0@0)",
+            "0@0",
+            "3@0&!(3@0)",
+        ] {
+            let s1 = Sieve::new(e);
+            let s2 = s1.to_dnf();
+            assert!(s2.root.union_residuals().is_some(), "{e}");
+            assert!(s1.is_equivalent(&s2), "{e} -> {s2}");
+        }
+    }
+
+    #[test]
+    fn test_sieve_to_dnf_b() {
+        assert_eq!(Sieve::new("!(3@0)").to_dnf().to_string(), "Sieve{3@1|3@2}");
+        assert_eq!(
+            Sieve::new("6@0|3@0|1@0&5@2").to_dnf().to_string(),
+            "Sieve{3@0|5@2}"
+        );
+        assert_eq!(Sieve::new("3@0-3@0").to_dnf().to_string(), "Sieve{0@0}");
+    }
+
+    #[test]
+    fn test_sieve_complement_expanded_a() {
+        for e in [
+            "3@0|4@1",
+            "!(5@2)",
+            "(5@0|4@2)&!(30@10)",
+            "6@1^4@3",
+            "0@0",
+            "1@0",
+        ] {
+            let s1 = Sieve::new(e);
+            let s2 = s1.complement_expanded();
+            assert!(s2.root.union_residuals().is_some(), "{e}");
+            assert!(s2.is_equivalent(&!&s1), "{e} -> {s2}");
+        }
+    }
+
+    #[test]
+    fn test_sieve_complement_expanded_b() {
+        assert_eq!(
+            Sieve::new("!(5@2)").complement_expanded().to_string(),
+            "Sieve{5@2}"
+        );
+        assert_eq!(
+            Sieve::new("2@1").complement_expanded().to_string(),
+            "Sieve{2@0}"
+        );
+        assert_eq!(Sieve::all().complement_expanded().to_string(), "Sieve{0@0}");
+    }
+
+    #[test]
+    fn test_sieve_complement_expanded_c() {
+        let s = Sieve::new("100003@0").complement_expanded();
+        assert_eq!(s.root.union_residuals().unwrap().len(), 100002);
+        assert!(!s.contains(0));
+        assert!(s.contains(1));
+        assert!(s.contains(100002));
+        assert!(!s.contains(-100003));
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        for p in self.iterator.by_ref() {
-            // while let Some(p) = self.iterator.next() {
-            if self.sieve_node.contains(p) {
-                match self.last {
-                    PositionLast::Init => {
-                        // drop the first value
-                        self.last = PositionLast::Value(p);
-                        continue;
-                    }
-                    PositionLast::Value(last) => {
-                        let post = p - last;
-                        self.last = PositionLast::Value(p);
-                        return Some(post);
-                    }
+    #[test]
+    fn test_sieve_translate_a() {
+        for e in [
+            "3@0|4@1",
+            "!(5@2)",
+            "(5@0|4@2)&!(30@10)",
+            "6@1^4@3",
+            "12@0-4@0",
+            "0@0",
+        ] {
+            let s1 = Sieve::new(e);
+            for offset in [-25, -1, 0, 7, 60] {
+                let s2 = s1.translate(offset);
+                for v in -40..40 {
+                    assert_eq!(s1.contains(v), s2.contains(v + offset), "{e} {offset} {v}");
                 }
             }
         }
-        None
     }
-}
 
-//------------------------------------------------------------------------------
+    #[test]
+    fn test_sieve_density_a() {
+        assert_eq!(Sieve::new("5@2").density(), 0.2);
+        assert_eq!(Sieve::new("!(5@2)").density(), 0.8);
+        assert_eq!(Sieve::new("6@0&4@1").density(), 0.0);
+        assert_eq!(Sieve::empty().density(), 0.0);
+        assert_eq!(Sieve::all().density(), 1.0);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_sieve_coverage_a() {
+        let s = Sieve::new("2@0");
+        assert_eq!(s.coverage(0..10), 0.5);
+        assert_eq!(s.coverage(0..1), 1.0);
+        assert_eq!(s.coverage(1..2), 0.0);
+        assert_eq!(s.coverage(-3..0), 1.0 / 3.0);
+        assert_eq!(s.coverage(10..10), 0.0);
+    }
 
     #[test]
-    fn test_residual_a() {
-        let r1 = Residual::new(3, 0);
-        assert_eq!(r1.to_string(), String::from("3@0"));
+    fn test_sieve_density_profile_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)");
+        let p = s.density_profile(-10..40, 7);
+        assert_eq!(p.len(), 50);
+        for (v, count) in (-10..40).zip(p) {
+            assert_eq!(count, s.iter_value(v..v + 7).count(), "{v}");
+        }
+        assert_eq!(s.density_profile(4..4, 7), vec![]);
     }
 
     #[test]
-    fn test_residual_b() {
-        let r1 = Residual::new(0, 2);
-        assert_eq!(r1.to_string(), "0@0");
+    #[should_panic(expected = "window must be greater than zero")]
+    fn test_sieve_density_profile_b() {
+        Sieve::new("3@0").density_profile(0..5, 0);
     }
 
-    //--------------------------------------------------------------------------
     #[test]
-    fn test_residual_to_string_a() {
-        let r1 = Residual::new(3, 0);
-        assert_eq!(r1.to_string(), "3@0");
+    fn test_sieve_from_values_a() {
+        for e in [
+            "3@0|4@1",
+            "!(5@2)",
+            "(5@0|4@2)&!(30@10)",
+            "6@1^4@3",
+            "0@0",
+            "1@0",
+        ] {
+            let s1 = Sieve::new(e);
+            let period = s1.period().unwrap_or(1);
+            let s2 = Sieve::from_values(s1.scan(0..period as i128), period);
+            assert!(s2.is_equivalent(&s1), "{e} -> {s2}");
+        }
     }
 
     #[test]
-    fn test_residual_to_string_b() {
-        let r1 = Residual::new(8, 3);
-        assert_eq!(r1.to_string(), "8@3");
+    fn test_sieve_from_values_b() {
+        assert_eq!(Sieve::from_values(vec![-1, 4], 5).to_string(), "Sieve{5@4}");
+        assert_eq!(Sieve::from_values(vec![], 5).to_string(), "Sieve{0@0}");
+        assert_eq!(Sieve::from_values(vec![7], 1).to_string(), "Sieve{1@0}");
     }
 
     #[test]
-    fn test_residual_to_string_c() {
-        let r1 = Residual::new(5, 8);
-        assert_eq!(r1.to_string(), "5@3");
+    fn test_sieve_track_density_a() {
+        let s = Sieve::new("3@0|5@1");
+        let p = s.track_density(0..150, 30, |v| v as f64 / 150.0);
+        assert_eq!(p.sections().len(), 5);
+        let mut last = -1.0;
+        for (_, section) in p.sections() {
+            let d = section.density();
+            assert!(d >= last);
+            last = d;
+        }
+        let p = s.track_density(0..10, 100, |_| 7.0 / 15.0);
+        assert_eq!(p.sections().len(), 1);
+        assert!(p.sections()[0].1.is_equivalent(&s));
     }
 
     #[test]
-    fn test_residual_to_string_d() {
-        let r1 = Residual::new(5, 9);
-        assert_eq!(r1.to_string(), "5@4");
+    #[should_panic(expected = "target density must be between zero and one")]
+    fn test_sieve_track_density_b() {
+        Sieve::new("3@0").track_density(0..10, 5, |_| 1.5);
     }
 
     #[test]
-    fn test_residual_to_string_e() {
-        let r1 = Residual::new(5, 10);
-        assert_eq!(r1.to_string(), "5@0");
+    fn test_sieve_from_pattern_a() {
+        let s1 = Sieve::new("(5@0|4@2)&!(30@10)");
+        let pattern: Vec<bool> = s1.iter_state(0..60).collect();
+        assert!(Sieve::from_pattern(&pattern).is_equivalent(&s1));
+        assert_eq!(
+            Sieve::from_pattern(&[false, false]).to_string(),
+            "Sieve{0@0}"
+        );
+        assert_eq!(Sieve::from_pattern(&[true]).to_string(), "Sieve{1@0}");
     }
 
-    //--------------------------------------------------------------------------
+    #[test]
+    fn test_sieve_from_pattern_str_a() {
+        assert_eq!(
+            Sieve::from_pattern_str("X-x-").unwrap().to_string(),
+            "Sieve{2@0}"
+        );
+        assert_eq!(
+            Sieve::from_pattern_str("x.o.").unwrap_err(),
+            SieveError::UnknownToken('o')
+        );
+        assert_eq!(Sieve::from_pattern_str(" ").unwrap_err(), SieveError::Empty);
+    }
 
-    // #[test]
-    // fn test_residual_not_a() {
-    //     let r1 = Residual::new(5, 10);
-    //     assert_eq!(r1.to_string(), String::from("!5@0"));
-    //     let r2 = !r1;
-    //     assert_eq!(r2.to_string(), "5@0");
-    //     let r3 = !r2;
-    //     assert_eq!(r3.to_string(), "!5@0");
-    // }
+    #[test]
+    fn test_sieve_from_intervals_a() {
+        let s = Sieve::from_intervals(3, &[3, 1, 2], 6);
+        assert_eq!(s.to_string(), "Sieve{3@0|6@1}");
+        assert_eq!(Sieve::from_intervals(-1, &[4], 4).to_string(), "Sieve{4@3}");
+        let pentatonic = Sieve::from_intervals(1, &[2, 3, 2, 2, 3], 12);
+        assert_eq!(
+            pentatonic.iter_interval(0..24).collect::<Vec<_>>(),
+            vec![2, 3, 2, 2, 3, 2, 3, 2, 2]
+        );
+    }
 
     #[test]
-    fn test_residual_eq_a() {
-        let r1 = Residual::new(5, 2);
-        let r2 = Residual::new(5, 3);
-        assert_eq!(r1 == r2, false);
-        assert_eq!(r1 != r2, true);
+    #[should_panic(expected = "intervals must sum to the period")]
+    fn test_sieve_from_intervals_b() {
+        Sieve::from_intervals(0, &[2, 2, 1], 12);
     }
 
     #[test]
-    fn test_residual_eq_b() {
-        let r1 = Residual::new(5, 2);
-        let r2 = Residual::new(5, 2);
-        assert_eq!(r1 == r2, true);
-        assert_eq!(r1 != r2, false);
+    #[should_panic(expected = "intervals must be greater than zero")]
+    fn test_sieve_from_intervals_c() {
+        Sieve::from_intervals(0, &[0, 12], 12);
     }
 
     #[test]
-    fn test_residual_ord_a() {
-        let r1 = Residual::new(5, 2);
-        let r2 = Residual::new(5, 3);
-        assert!(r1 < r2);
+    fn test_sieve_from_iter_interval_a() {
+        let s1 = Sieve::new("12@1|12@4|12@9");
+        let post = Sieve::from_iter_interval(1, s1.iter_interval(1..13), 12);
+        assert_eq!(post.to_string(), "Sieve{12@1|12@4|12@9}");
+        let s2 = Sieve::from_iter_interval(-3, vec![4, -2, 5], 4);
+        assert_eq!(s2.to_string(), "Sieve{4@0|2@1}");
+        assert_eq!(
+            Sieve::from_iter_interval(5, vec![], 5).to_string(),
+            "Sieve{5@0}"
+        );
     }
 
     #[test]
-    fn test_residual_ord_b() {
-        let r1 = Residual::new(2, 3);
-        let r2 = Residual::new(5, 3);
-        assert!(r1 < r2);
+    fn test_sieve_from_segments_a() {
+        let s = Sieve::from_segments(&[&[0u8, 2], &[], &[1]]);
+        assert_eq!(s.to_string(), "Sieve{5@0|5@2|5@4}");
+        let s = Sieve::from_segments(&[&[0, 1, 2], &[0, 1, 2]]);
+        assert_eq!(s.to_string(), "Sieve{1@0}");
+        let empty: &[i64] = &[];
+        assert_eq!(Sieve::from_segments(&[empty]).to_string(), "Sieve{0@0}");
+        assert_eq!(Sieve::from_segments::<i32>(&[]).to_string(), "Sieve{0@0}");
     }
 
     #[test]
-    fn test_residual_ord_c() {
-        let r1 = Residual::new(5, 3);
-        let r2 = Residual::new(5, 3);
-        assert!(r1 == r2);
+    #[should_panic(expected = "segment values must not be negative")]
+    fn test_sieve_from_segments_b() {
+        Sieve::from_segments(&[&[0, -1]]);
     }
 
-    //--------------------------------------------------------------------------
+    #[test]
+    fn test_sieve_euclidean_a() {
+        assert_eq!(Sieve::euclidean(4, 16, 0).to_string(), "Sieve{4@0}");
+        assert_eq!(Sieve::euclidean(4, 16, 2).to_string(), "Sieve{4@2}");
+        assert_eq!(Sieve::euclidean(0, 5, 1).to_string(), "Sieve{0@0}");
+        assert_eq!(Sieve::euclidean(5, 5, 3).to_string(), "Sieve{1@0}");
+        let s = Sieve::euclidean(5, 8, 9);
+        assert_eq!(s.iter_value(0..8).collect::<Vec<_>>(), vec![1, 3, 4, 6, 7]);
+        assert_eq!(s.period(), Some(8));
+    }
 
     #[test]
-    fn test_residual_bitand_a() {
-        let r1 = Residual::new(4, 0);
-        let r2 = Residual::new(3, 0);
-        assert_eq!((r1 & r2).to_string(), "12@0");
+    #[should_panic(expected = "pulses must not be greater than steps")]
+    fn test_sieve_euclidean_b() {
+        Sieve::euclidean(9, 8, 0);
     }
 
     #[test]
-    fn test_residual_bitand_b() {
-        let r1 = Residual::new(4, 0);
-        let r2 = Residual::new(3, 1);
-        assert_eq!((r1 & r2).to_string(), "12@4");
+    fn test_sieve_scales_a() {
+        let c = |s: Sieve| s.iter_value(0..12).collect::<Vec<_>>();
+        assert_eq!(c(Sieve::major_scale(0)), vec![0, 2, 4, 5, 7, 9, 11]);
+        assert_eq!(c(Sieve::major_scale(14)), c(Sieve::major_scale(2)));
+        assert_eq!(c(Sieve::natural_minor(9)), c(Sieve::major_scale(0)));
+        assert_eq!(c(Sieve::harmonic_minor(0)), vec![0, 2, 3, 5, 7, 8, 11]);
+        assert_eq!(c(Sieve::melodic_minor(2)), vec![1, 2, 4, 5, 7, 9, 11]);
     }
 
     #[test]
-    fn test_residual_bitand_c() {
-        let r1 = Residual::new(5, 2);
-        let r2 = Residual::new(10, 3);
-        assert_eq!((r1 & r2).to_string(), "0@0");
+    fn test_sieve_messiaen_mode_a() {
+        let counts: Vec<usize> = (1..=7)
+            .map(|n| Sieve::messiaen_mode(n, 0).iter_value(0..12).count())
+            .collect();
+        assert_eq!(counts, vec![6, 8, 9, 8, 6, 8, 10]);
+        // the number of distinct transpositions of each mode
+        let transpositions: Vec<u64> = (1..=7)
+            .map(|n| {
+                let m = Sieve::messiaen_mode(n, 0);
+                (1..=12)
+                    .find(|t| Sieve::messiaen_mode(n, *t).is_equivalent(&m))
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(transpositions, vec![2, 3, 4, 6, 6, 6, 6]);
     }
 
     #[test]
-    fn test_residual_bitand_d() {
-        let r1 = Residual::new(3, 2);
-        let r2 = Residual::new(3, 1);
-        assert_eq!((r1 & r2).to_string(), "0@0");
+    #[should_panic(expected = "mode must be from 1 to 7")]
+    fn test_sieve_messiaen_mode_b() {
+        Sieve::messiaen_mode(8, 0);
     }
 
-    //--------------------------------------------------------------------------
+    #[test]
+    fn test_sieve_coprime_to_a() {
+        assert_eq!(Sieve::coprime_to(&[]).to_string(), "Sieve{1@0}");
+        assert_eq!(Sieve::coprime_to(&[1]).to_string(), "Sieve{1@0}");
+        assert_eq!(Sieve::coprime_to(&[8, 4]).to_string(), "Sieve{!(2@0)}");
+        let s = Sieve::coprime_to(&[210]);
+        for v in 0..420 {
+            assert_eq!(s.contains(v), util::gcd(v, 210, 0) == Ok(1), "{v}");
+        }
+        // primes above the moduli are members
+        for p in Sieve::primes_up_to(200).into_iter().filter(|p| *p > 7) {
+            assert!(s.contains(p as i128));
+        }
+    }
 
     #[test]
-    fn test_residual_contains_a() {
-        let r1 = Residual::new(3, 0);
-        assert_eq!(r1.contains(-3), true);
-        assert_eq!(r1.contains(-2), false);
-        assert_eq!(r1.contains(-1), false);
-        assert_eq!(r1.contains(0), true);
-        assert_eq!(r1.contains(1), false);
-        assert_eq!(r1.contains(2), false);
-        assert_eq!(r1.contains(3), true);
-        assert_eq!(r1.contains(4), false);
-        assert_eq!(r1.contains(5), false);
+    fn test_sieve_fit_a() {
+        let s1 = Sieve::new("(5@0|4@2)&!(30@10)");
+        let values = s1.scan(0..60);
+        let s2 = Sieve::fit(values.clone(), 0, 0);
+        assert_eq!(s2.scan(0..60), values);
+        for (max_extra, max_missing) in [(0, 3), (3, 0), (5, 5)] {
+            let s3 = Sieve::fit(values.clone(), max_extra, max_missing);
+            let realized = s3.scan(values[0]..values[values.len() - 1] + 1);
+            let extra = realized.iter().filter(|v| !values.contains(v)).count();
+            let missing = values.iter().filter(|v| !realized.contains(v)).count();
+            assert!(extra <= max_extra && missing <= max_missing);
+            assert!(s3.residuals().len() <= s2.residuals().len());
+        }
     }
 
     #[test]
-    fn test_residual_contains_b() {
-        let r1 = Residual::new(0, 0);
-        assert_eq!(r1.contains(-2), false);
-        assert_eq!(r1.contains(-1), false);
-        assert_eq!(r1.contains(0), false);
-        assert_eq!(r1.contains(1), false);
-        assert_eq!(r1.contains(2), false);
-        assert_eq!(r1.contains(3), false);
+    fn test_sieve_fit_b() {
+        assert_eq!(Sieve::fit(vec![], 0, 0).to_string(), "Sieve{0@0}");
+        assert_eq!(Sieve::fit(vec![-7], 0, 0).to_string(), "Sieve{1@0}");
+        assert_eq!(Sieve::fit(vec![-7, -2], 0, 0).to_string(), "Sieve{5@3}");
     }
 
     #[test]
-    fn test_residual_contains_c() {
-        let r1 = Residual::new(3, 1);
-        assert_eq!(r1.contains(-3), false);
-        assert_eq!(r1.contains(-2), true);
-        assert_eq!(r1.contains(-1), false);
-        assert_eq!(r1.contains(0), false);
-        assert_eq!(r1.contains(1), true);
-        assert_eq!(r1.contains(2), false);
-        assert_eq!(r1.contains(3), false);
-        assert_eq!(r1.contains(4), true);
+    fn test_sieve_fit_c() {
+        let s = Sieve::try_fit(vec![0, FIT_SPAN_LIMIT as i128 - 1], 0, 0).unwrap();
+        assert_eq!(
+            s.scan(0..FIT_SPAN_LIMIT as i128),
+            vec![0, FIT_SPAN_LIMIT as i128 - 1]
+        );
+        assert_eq!(
+            Sieve::try_fit(vec![0, FIT_SPAN_LIMIT as i128], 0, 0).unwrap_err(),
+            SieveError::WindowTooLarge {
+                span: FIT_SPAN_LIMIT as u128 + 1,
+                limit: FIT_SPAN_LIMIT
+            }
+        );
+        assert_eq!(
+            Sieve::try_fit(vec![i128::MIN, i128::MAX], 0, 0)
+                .unwrap_err()
+                .to_string(),
+            format!("window of {} values exceeds the limit of 4096", u128::MAX)
+        );
     }
 
-    //--------------------------------------------------------------------------
+    #[test]
+    #[should_panic]
+    fn test_sieve_fit_d() {
+        let _ = Sieve::fit(vec![i128::MIN, 0], 0, 0);
+    }
 
     #[test]
-    fn test_sieve_new_a() {
-        let s1 = Sieve::new("3@1");
-        assert_eq!(s1.to_string(), "Sieve{3@1}");
+    fn test_sieve_residuals_a() {
+        assert_eq!(Sieve::new("5@7|3@0").residuals(), vec![(3, 0), (5, 2)]);
+        assert_eq!(Sieve::new("!(2@0)&3@0").residuals(), vec![(6, 3)]);
+        assert_eq!(Sieve::empty().residuals(), vec![]);
+        let s = Sieve::new("(4@0|6@2)^5@1");
+        assert!(Sieve::from_residuals(s.residuals()).is_equivalent(&s));
     }
 
     #[test]
-    fn test_sieve_new_b() {
-        let s1 = Sieve::new("3@4");
-        assert_eq!(s1.to_string(), "Sieve{3@1}");
+    fn test_sieve_to_smtlib_a() {
+        assert_eq!(
+            Sieve::new("3@0&4@1^5@2-6@3").to_smtlib("t"),
+            "(assert (xor (and (= (mod t 3) 0) (= (mod t 4) 1)) (and (= (mod t 5) 2) (not (= (mod t 6) 3)))))"
+        );
+        assert_eq!(
+            Sieve::new("0@0|1@0").to_smtlib("x"),
+            "(assert (or false (= (mod x 1) 0)))"
+        );
     }
 
     #[test]
-    fn test_sieve_new_c() {
-        let s1 = Sieve::new("5@5");
-        assert_eq!(s1.to_string(), "Sieve{5@0}");
+    fn test_sieve_to_minizinc_a() {
+        assert_eq!(
+            Sieve::new("2@1&3@0^0@0-5@4").to_minizinc("p"),
+            "predicate p(var int: x) = ((((x mod 2) + 2) mod 2 = 1 /\\ ((x mod 3) + 3) mod 3 = 0) xor (false /\\ not (((x mod 5) + 5) mod 5 = 4)));"
+        );
     }
 
     #[test]
-    fn test_sieve_new_d() {
-        let s1 = Sieve::new("0@5");
-        assert_eq!(s1.to_string(), "Sieve{0@0}");
+    fn test_sieve_next_above_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^7@3");
+        let values = s.iter_value(-100..100).collect::<Vec<_>>();
+        for w in values.windows(2) {
+            assert_eq!(s.next_above(w[0]), Some(w[1]));
+            assert_eq!(s.prev_below(w[1]), Some(w[0]));
+            if w[1] - w[0] > 1 {
+                assert_eq!(s.next_above(w[0] + 1), Some(w[1]));
+                assert_eq!(s.prev_below(w[1] - 1), Some(w[0]));
+            }
+        }
     }
 
     #[test]
-    fn test_sieve_contains_a() {
-        let r1 = Residual::new(3, 0);
-        let s1 = SieveNode::Unit(r1);
+    fn test_sieve_next_above_b() {
+        let s = Sieve::new("!(3@0)");
+        assert_eq!(s.next_above(2), Some(4));
+        assert_eq!(s.prev_below(1), Some(-1));
+        assert_eq!(s.next_above(i128::MAX), None);
+        assert_eq!(s.prev_below(i128::MIN), None);
+        assert_eq!(Sieve::new("6@0&4@1").next_above(0), None);
+    }
 
-        let pos = vec![-3, -2, -1, 0, 1];
-        let val = vec![true, false, false, true, false];
-        for (p, b) in pos.iter().zip(val.iter()) {
-            assert_eq!(s1.contains(*p), *b);
+    #[test]
+    fn test_sieve_nth_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^7@3");
+        for origin in [-61, 0, 17] {
+            let values = s.iter_value(origin..origin + 1000).collect::<Vec<_>>();
+            for (i, v) in values.iter().enumerate() {
+                assert_eq!(s.nth(origin, i as u64), Some(*v));
+            }
         }
+        assert_eq!(Sieve::empty().nth(0, 0), None);
+        assert_eq!(Sieve::new("6@0&4@1").nth(0, 0), None);
+        assert_eq!(Sieve::new("1@0").nth(i128::MAX - 2, 2), Some(i128::MAX));
+        assert_eq!(Sieve::new("1@0").nth(i128::MAX - 2, 3), None);
     }
 
     #[test]
-    fn test_sieve_contains_b() {
-        let r1 = Residual::new(3, 0);
-        let r2 = Residual::new(3, 1);
-        let s1 = SieveNode::Union(Box::new(SieveNode::Unit(r1)), Box::new(SieveNode::Unit(r2)));
+    fn test_sieve_nth_b() {
+        // a large period is not scanned
+        let s = Sieve::new("!(1000003@0)&1000033@1");
+        assert_eq!(s.nth(0, 0), Some(1));
+        assert_eq!(s.nth(0, 2), Some(2000067));
+        let s = Sieve::new("1000003@0&1000033@1");
+        let first = s.nth(0, 0).unwrap();
+        assert!(s.contains(first));
+        assert_eq!(s.nth(0, 1), Some(first + 1000003 * 1000033));
+        assert_eq!(Sieve::new("1@0").nth(i128::MAX - 2, 2), Some(i128::MAX));
+    }
 
-        assert_eq!(s1.contains(-2), true);
-        assert_eq!(s1.contains(-1), false);
-        assert_eq!(s1.contains(0), true);
-        assert_eq!(s1.contains(1), true);
-        assert_eq!(s1.contains(2), false);
-        assert_eq!(s1.contains(3), true);
-        assert_eq!(s1.contains(4), true);
+    #[test]
+    fn test_sieve_nth_c() {
+        // sieves without non-zero moduli
+        assert_eq!(Sieve::new("!(0@0)").nth(0, 3), Some(3));
+        assert_eq!(Sieve::new("!(0@0)").nth(-7, 0), Some(-7));
+        assert_eq!(Sieve::new("0@0").nth(0, 3), None);
     }
 
-    //--------------------------------------------------------------------------
+    #[test]
+    fn test_sieve_count_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^7@3");
+        for (a, b) in [(0, 0), (-61, 200), (17, 18), (5, 3), (-1000, -17)] {
+            assert_eq!(s.count(a..b), s.iter_value(a..b).count() as u64, "{a}..{b}");
+        }
+        assert_eq!(Sieve::empty().count(0..100), 0);
+        assert_eq!(Sieve::new("!(0@0)").count(0..100), 100);
+    }
+
+    #[test]
+    fn test_sieve_count_b() {
+        // a short range is not scanned over the whole (large) period
+        let s = Sieve::new("!(1000003@0)|1000033@1");
+        assert_eq!(s.count(0..10), 9);
+        assert_eq!(s.count(1000000..1000010), 9);
+    }
+
+    #[test]
+    fn test_sieve_contains_program_a() {
+        // beyond the depth of a Program, the tree is evaluated
+        let deep = (1..200).fold(String::from("2@0"), |e, i| format!("{}@1|({e})", i + 2));
+        let mut s = Sieve::new(&deep);
+        assert!(s.program.is_none());
+        s |= Sieve::new("1000@999");
+        for v in 0..1000 {
+            assert_eq!(s.contains(v), s.root.contains(v), "{v}");
+        }
+        let s = (Sieve::new("3@0") - Sieve::new("6@0")) | !Sieve::new("4@1");
+        assert!(s.program.is_some());
+        for v in -24..24 {
+            assert_eq!(s.contains(v), s.root.contains(v), "{v}");
+        }
+    }
+
+    #[test]
+    fn test_sieve_warm_a() {
+        let s1 = Sieve::new("(5@0|4@2)&!(30@10)^(70001@3|7@1)");
+        let mut s2 = s1.clone();
+        s2.warm();
+        assert_eq!(s1, s2);
+        for v in (-300..300).chain(69990..70010) {
+            assert_eq!(s2.contains(v), s1.contains(v), "{v}");
+        }
+        s2 |= Sieve::new("11@0");
+        assert!(!s2.is_warm());
+        assert!(s2.contains(11));
+        assert!(!(!&s1).is_warm());
+    }
+
+    #[test]
+    fn test_sieve_balanced_complement_a() {
+        let s1 = Sieve::new("3@0|5@1");
+        // the complement has density 8/15
+        let s2 = s1.balanced_complement(8.0 / 15.0);
+        assert!(s2.is_equivalent(&!&s1));
+        let s3 = s1.balanced_complement(0.3);
+        assert!(s3.is_subset_of(&!&s1));
+        let d = s3.iter_value(0..15).count() as f64 / 15.0;
+        assert!((d - 0.3).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_sieve_balanced_complement_b() {
+        let s1 = Sieve::new("4@0");
+        let s2 = s1.balanced_complement(1.0);
+        assert!(s2.is_full());
+        assert!(s1.balanced_complement(0.0).is_empty());
+    }
+
+    #[test]
+    fn test_sieve_minimize_a() {
+        for e in [
+            "3@0|4@1",
+            "!(3@0|4@1)",
+            "(5@0|4@2)&!(30@10)",
+            "6@1^4@3",
+            "!(0@0)",
+            "0@0",
+            "3@0&!(3@0)",
+        ] {
+            let s1 = Sieve::new(e);
+            let s2 = s1.minimize();
+            let r = s2.root.union_residuals().unwrap();
+            assert!(s1.is_equivalent(&s2), "{e} -> {s2}");
+            assert!(r.len() <= s1.to_dnf().root.union_residuals().unwrap().len());
+        }
+    }
+
+    #[test]
+    fn test_sieve_minimize_b() {
+        assert_eq!(
+            Sieve::new("!(3@0)").minimize().to_string(),
+            "Sieve{3@1|3@2}"
+        );
+        assert_eq!(Sieve::new("!(0@0)").minimize().to_string(), "Sieve{1@0}");
+        assert_eq!(
+            Sieve::new("6@1^4@3").minimize().to_string(),
+            "Sieve{12@1|12@3|12@11}"
+        );
+    }
 
     #[test]
     fn test_sieve_operators_a() {
@@ -717,4 +4871,140 @@ mod tests {
         let s3 = !&s1;
         assert_eq!(s3.to_string(), "Sieve{!(3@1)}");
     }
+
+    //--------------------------------------------------------------------------
+
+    #[test]
+    fn test_sieve_node_density_estimate_a() {
+        let s1 = Sieve::new("2@0&3@0");
+        assert_eq!(s1.root.density_estimate(), 1.0 / 6.0);
+        let s2 = Sieve::new("!4@0|0@0");
+        assert_eq!(s2.root.density_estimate(), 0.75);
+        let s3 = Sieve::new("2@0^2@1");
+        assert_eq!(s3.root.density_estimate(), 0.5);
+    }
+
+    #[test]
+    fn test_sieve_optimize_order_a() {
+        let s1 = Sieve::new("(2@0|11@3)&(!5@0^3@1)&13@4");
+        let s2 = s1.optimize_order();
+        assert_eq!(s2.to_string(), "Sieve{13@4&((2@0|11@3)&(!(5@0)^3@1))}");
+        assert_eq!(
+            s1.iter_value(-100..100).collect::<Vec<_>>(),
+            s2.iter_value(-100..100).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_sieve_optimize_order_b() {
+        let s1 = Sieve::new("(2@0|3@0)&5@0");
+        let p1 = s1.profile(0..1000);
+        let p2 = s1.optimize_order().profile(0..1000);
+        let evaluations = |p: &Vec<NodeProfile>| p.iter().map(|n| n.evaluations).sum::<u64>();
+        assert!(evaluations(&p2) < evaluations(&p1));
+    }
+
+    #[test]
+    fn test_sieve_operators_f() {
+        let s1 = Sieve::new("3@1");
+        let s2 = Sieve::new("4@0");
+        let s3 = &s1 | &(!&s2);
+        assert_eq!(s3.to_string(), "Sieve{3@1|!(4@0)}");
+        assert_eq!(s1.to_string(), "Sieve{3@1}");
+        assert_eq!(s2.to_string(), "Sieve{4@0}");
+    }
+
+    #[test]
+    fn test_sieve_operators_g() {
+        let s1 = Sieve::new("3@1");
+        let s2 = Sieve::new("4@0");
+        assert_eq!((s1.clone() & &s2).to_string(), "Sieve{3@1&4@0}");
+        assert_eq!((&s1 & s2.clone()).to_string(), "Sieve{3@1&4@0}");
+        assert_eq!((s1.clone() | &s2).to_string(), "Sieve{3@1|4@0}");
+        assert_eq!((&s1 | s2.clone()).to_string(), "Sieve{3@1|4@0}");
+        assert_eq!((s1.clone() ^ &s2).to_string(), "Sieve{3@1^4@0}");
+        assert_eq!((&s1 ^ !s2).to_string(), "Sieve{3@1^!(4@0)}");
+    }
+
+    #[test]
+    fn test_sieve_operators_h() {
+        let s1 = Sieve::new("3@0");
+        let s2 = Sieve::new("5@1");
+        let s3 = &s1 - &s2;
+        assert_eq!(s3.to_string(), "Sieve{3@0-5@1}");
+        assert_eq!(
+            s3.iter_value(0..20).collect::<Vec<_>>(),
+            (&s1 & !&s2).iter_value(0..20).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            s3.iter_value(0..20).collect::<Vec<_>>(),
+            vec![0, 3, 9, 12, 15, 18]
+        );
+        assert_eq!((s1.clone() - &s2).to_string(), "Sieve{3@0-5@1}");
+        assert_eq!((&s1 - s2.clone()).to_string(), "Sieve{3@0-5@1}");
+    }
+
+    #[test]
+    fn test_sieve_new_difference_a() {
+        let s1 = Sieve::new("3@0 - 5@1 | 4@0");
+        assert_eq!(s1.to_string(), "Sieve{3@0-5@1|4@0}");
+        assert_eq!(
+            s1.iter_value(0..13).collect::<Vec<_>>(),
+            vec![0, 3, 4, 8, 9, 12]
+        );
+        let s2 = Sieve::new("3@0 - (5@1 | 4@0)");
+        assert_eq!(s2.to_string(), "Sieve{3@0-(5@1|4@0)}");
+        assert_eq!(s2.iter_value(0..13).collect::<Vec<_>>(), vec![3, 9]);
+    }
+
+    #[test]
+    fn test_sieve_display_round_trip_a() {
+        for e in [
+            "3@0-5@1-7@2",
+            "3@0-(5@1-7@2)",
+            "(3@0|4@1)&!(5@0)",
+            "3@0&(4@1^5@2)|6@0",
+            "3@0^(4@1^5@2)",
+            "!(3@0-4@1)&(2@0|5@1)",
+        ] {
+            let s1 = Sieve::new(e);
+            let s2: Sieve = s1.to_string().parse().unwrap();
+            assert_eq!(s1.to_string(), s2.to_string());
+            assert_eq!(
+                s1.iter_value(0..120).collect::<Vec<_>>(),
+                s2.iter_value(0..120).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_sieve_operators_assign_a() {
+        let mut s1 = Sieve::new("12@0");
+        for shift in [2, 4, 5] {
+            s1 |= Sieve::new(&format!("12@{shift}"));
+        }
+        assert_eq!(s1.to_string(), "Sieve{12@0|12@2|12@4|12@5}");
+        assert_eq!(s1.iter_value(0..12).collect::<Vec<_>>(), vec![0, 2, 4, 5]);
+    }
+
+    #[test]
+    fn test_sieve_operators_assign_b() {
+        let s2 = Sieve::new("2@0");
+        let mut s1 = Sieve::new("3@0");
+        s1 &= &s2;
+        assert_eq!(s1.to_string(), "Sieve{3@0&2@0}");
+        s1 ^= &s2;
+        assert_eq!(s1.to_string(), "Sieve{3@0&2@0^2@0}");
+        s1 &= !s2;
+        assert_eq!(s1.to_string(), "Sieve{(3@0&2@0^2@0)&!(2@0)}");
+        assert_eq!(s1.iter_value(0..12).collect::<Vec<_>>(), vec![]);
+    }
+
+    #[test]
+    fn test_sieve_operators_assign_c() {
+        let mut s1 = Sieve::new("3@0");
+        s1 |= Sieve::new("4@0");
+        s1 ^= Sieve::new("6@0");
+        assert_eq!(s1.iter_value(0..13).collect::<Vec<_>>(), vec![3, 4, 8, 9]);
+    }
 }
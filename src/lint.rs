@@ -0,0 +1,255 @@
+use std::fmt;
+
+use crate::parser;
+use crate::Residual;
+use crate::Sieve;
+use crate::SieveError;
+use crate::SieveNode;
+
+/// The period above which a Sieve is reported as having a large period.
+const PERIOD_LIMIT: u64 = 1 << 20;
+
+/// The largest period over which a sub-expression is tested for containing every integer.
+const UNIVERSAL_LIMIT: u64 = 1 << 16;
+
+/// The kind of a suspicious construct reported by `lint`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LintKind {
+    /// An intersection whose operands each have members, but which has none, such as `5@2&10@3`.
+    CollapsedEmpty,
+    /// An operand of a union or intersection that does not change its result, such as `6@0` in `3@0|6@0`.
+    Redundant,
+    /// A Residual whose shift is not less than its modulus, such as `3@5`, which is the same class as `3@2`.
+    UnreducedShift,
+    /// A sub-expression that contains every integer, equivalent to `1@0`.
+    Universal,
+    /// A Sieve whose period is unusually large, or overflows.
+    LargePeriod,
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            LintKind::CollapsedEmpty => "collapsed-empty",
+            LintKind::Redundant => "redundant",
+            LintKind::UnreducedShift => "unreduced-shift",
+            LintKind::Universal => "universal",
+            LintKind::LargePeriod => "large-period",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// A suspicious construct in a Sieve expression, as returned by `lint`.
+///
+/// # Fields
+/// * `kind` - The LintKind.
+/// * `expression` - The sub-expression the Lint applies to.
+/// * `message` - A description of the construct.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lint {
+    pub kind: LintKind,
+    pub expression: String,
+    pub message: String,
+}
+
+impl Lint {
+    fn new(kind: LintKind, expression: impl fmt::Display, message: String) -> Self {
+        Self {
+            kind,
+            expression: expression.to_string(),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.kind, self.expression, self.message)
+    }
+}
+
+/// Return `true` if `part` has members and every Residual of `part` is a subset of a Residual of `whole`, such that `part` is a subset of `whole`; `false` where either is not a union or intersection of Residuals. Operands without members are reported as collapsed rather than redundant.
+fn is_subset(part: &SieveNode, whole: &SieveNode) -> bool {
+    match (part.progression_residuals(), whole.progression_residuals()) {
+        (Some(p), Some(w)) => {
+            p.iter().any(|r| r.modulus() > 0)
+                && p.iter().all(|r| w.iter().any(|o| r.is_subset_of(o)))
+        }
+        _ => false,
+    }
+}
+
+/// Return `true` if the node contains every integer of one period, where the period is small enough to test.
+fn is_universal(node: &SieveNode) -> bool {
+    match node.cycle().filter(|p| *p <= UNIVERSAL_LIMIT) {
+        Some(period) => (0..period as i128).all(|v| node.contains(v)),
+        None => false,
+    }
+}
+
+/// Collect the Lints of the node and its descendants; `within_universal` is `true` if an ancestor is already reported as universal.
+fn lint_node(node: &SieveNode, within_universal: bool, post: &mut Vec<Lint>) {
+    let universal = !within_universal && !matches!(node, SieveNode::Unit(_)) && is_universal(node);
+    if universal {
+        post.push(Lint::new(
+            LintKind::Universal,
+            node,
+            "contains every integer; equivalent to 1@0".to_string(),
+        ));
+    }
+    match node {
+        SieveNode::Unit(_) => {}
+        SieveNode::Intersection(lhs, rhs) | SieveNode::Union(lhs, rhs) => {
+            let union = matches!(node, SieveNode::Union(..));
+            if matches!(node, SieveNode::Intersection(..)) && SieveNode::collapses(lhs, rhs) {
+                post.push(Lint::new(
+                    LintKind::CollapsedEmpty,
+                    node,
+                    format!("{lhs} and {rhs} each have members, but their intersection has none"),
+                ));
+            } else {
+                // for a union, a subset is redundant; for an intersection, a superset is redundant
+                let redundant = if is_subset(rhs, lhs) {
+                    Some(if union { rhs } else { lhs })
+                } else if is_subset(lhs, rhs) {
+                    Some(if union { lhs } else { rhs })
+                } else {
+                    None
+                };
+                if let Some(r) = redundant {
+                    let (relation, operator) = if union {
+                        ("a subset", "union")
+                    } else {
+                        ("a superset", "intersection")
+                    };
+                    post.push(Lint::new(
+                        LintKind::Redundant,
+                        r,
+                        format!("is {relation} of the other operand of the {operator} {node}"),
+                    ));
+                }
+            }
+            lint_node(lhs, within_universal || universal, post);
+            lint_node(rhs, within_universal || universal, post);
+        }
+        SieveNode::SymmetricDifference(lhs, rhs) | SieveNode::Difference(lhs, rhs) => {
+            lint_node(lhs, within_universal || universal, post);
+            lint_node(rhs, within_universal || universal, post);
+        }
+        SieveNode::Inversion(part) => lint_node(part, within_universal || universal, post),
+    }
+}
+
+/// Return the suspicious constructs of a Sieve expression: intersections that collapse to empty, redundant operands of unions and intersections, shifts not less than their modulus, sub-expressions equivalent to `1@0`, and unusually large periods. Lints are returned in order of the expression, with a large period last; an expression that cannot be parsed returns the SieveError of `Sieve::try_new`. Use this for editor integration or checks of project files.
+///
+/// ```
+/// let lints = xensieve::lint("3@5|6@2|5@2&10@3").unwrap();
+/// let kinds: Vec<_> = lints.iter().map(|l| l.kind.to_string()).collect();
+/// assert_eq!(kinds, vec!["unreduced-shift", "redundant", "collapsed-empty"]);
+/// assert_eq!(lints[1].expression, "6@2");
+/// assert!(xensieve::lint("3@0|4@1").unwrap().is_empty());
+/// ```
+pub fn lint(expr: &str) -> Result<Vec<Lint>, SieveError> {
+    let sieve = Sieve::try_new(expr)?;
+    let mut post = Vec::new();
+    let literals = parser::evaluate_literals(parser::strip_display(expr))?;
+    for token in parser::infix_to_postfix(&literals)? {
+        if let Ok((m, s)) = parser::residual_to_ints(&token) {
+            if m > 0 && s >= m {
+                post.push(Lint::new(
+                    LintKind::UnreducedShift,
+                    &token,
+                    format!(
+                        "shift is not less than the modulus; equivalent to {}",
+                        Residual::new(m, s)
+                    ),
+                ));
+            }
+        }
+    }
+    lint_node(&sieve.root, false, &mut post);
+    match sieve.root.cycle() {
+        Some(period) if period > PERIOD_LIMIT => post.push(Lint::new(
+            LintKind::LargePeriod,
+            &sieve.root,
+            format!("period of {period} exceeds {PERIOD_LIMIT}"),
+        )),
+        None => post.push(Lint::new(
+            LintKind::LargePeriod,
+            &sieve.root,
+            format!("period overflows {}", u64::MAX),
+        )),
+        _ => {}
+    }
+    Ok(post)
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(expr: &str) -> Vec<(LintKind, String)> {
+        lint(expr)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.kind, l.expression))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_a() {
+        assert_eq!(
+            kinds("3@0|6@0"),
+            vec![(LintKind::Redundant, "6@0".to_string())]
+        );
+        assert_eq!(
+            kinds("6@0|3@0"),
+            vec![(LintKind::Redundant, "6@0".to_string())]
+        );
+        assert_eq!(
+            kinds("3@0&6@0"),
+            vec![(LintKind::Redundant, "3@0".to_string())]
+        );
+        assert_eq!(
+            kinds("4@1|4@1"),
+            vec![(LintKind::Redundant, "4@1".to_string())]
+        );
+        assert_eq!(kinds("(3@0|6@1)-6@0"), vec![]);
+    }
+
+    #[test]
+    fn test_lint_b() {
+        assert_eq!(
+            kinds("(3@0|3@1|3@2)|5@0"),
+            vec![(LintKind::Universal, "3@0|3@1|3@2|5@0".to_string())]
+        );
+        assert_eq!(
+            kinds("!(2@0)^2@0"),
+            vec![(LintKind::Universal, "!(2@0)^2@0".to_string())]
+        );
+        assert_eq!(kinds("1@0"), vec![]);
+    }
+
+    #[test]
+    fn test_lint_c() {
+        assert_eq!(
+            kinds("7@9|(4@0&6@1)"),
+            vec![
+                (LintKind::UnreducedShift, "7@9".to_string()),
+                (LintKind::CollapsedEmpty, "4@0&6@1".to_string()),
+            ]
+        );
+        let lints = lint("1048573@0|1048571@0").unwrap();
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].kind, LintKind::LargePeriod);
+        assert!(lints[0].to_string().starts_with("large-period: "));
+        assert!(lint("3@0|").is_err());
+    }
+}
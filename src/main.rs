@@ -1,5 +1,206 @@
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::io::Write;
+use std::ops::Range;
+
+use xensieve::Sieve;
+
+const HELP: &str = "\
+<name> = <expr>   define a named sieve; names may be used in later expressions
+<expr>            show the sieve
+values <expr>     show the members within the range
+draw <expr>       draw the members within the range, as x for members and . for others
+range <a>..<b>    set the range used by values and draw, of at most 65536 values
+list              show all named sieves
+help              show this help
+quit              exit";
+
+const COMMANDS: [&str; 7] = ["values", "draw", "range", "list", "help", "quit", "exit"];
+
+/// The largest number of values in the range, such that values and draw produce output of bounded size.
+const RANGE_LIMIT: u128 = 1 << 16;
+
+/// The state of an interactive session: named sieves and the range in which they are realized.
+struct Session {
+    names: BTreeMap<String, Sieve>,
+    range: Range<i128>,
+}
+
+impl Session {
+    fn new() -> Self {
+        Self {
+            names: BTreeMap::new(),
+            range: 0..24,
+        }
+    }
+
+    /// Parse an expression, replacing each name with its sieve, in parentheses.
+    fn parse(&self, expr: &str) -> Result<Sieve, String> {
+        let chars: Vec<char> = expr.chars().collect();
+        let mut resolved = String::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            // names start with a letter not continuing a literal, such as the x of 0x1F
+            let continues = i > 0 && (chars[i - 1].is_ascii_alphanumeric() || chars[i - 1] == '_');
+            if c.is_ascii_alphabetic() && !continues {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let name: String = chars[start..i].iter().collect();
+                match self.names.get(&name) {
+                    Some(s) => resolved.push_str(&format!("({})", expression(s))),
+                    None => return Err(format!("unknown name: {name}")),
+                }
+                continue;
+            }
+            resolved.push(c);
+            i += 1;
+        }
+        Sieve::try_new(&resolved).map_err(|e| e.to_string())
+    }
+
+    /// Evaluate one line of input, returning the text to show.
+    fn eval(&mut self, line: &str) -> Result<String, String> {
+        let line = line.trim();
+        if let Some((name, expr)) = line.split_once('=') {
+            let name = name.trim();
+            let valid = name
+                .chars()
+                .next()
+                .map_or(false, |c| c.is_ascii_alphabetic())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid || COMMANDS.contains(&name) {
+                return Err(format!("invalid name: {name}"));
+            }
+            let sieve = self.parse(expr)?;
+            let reply = format!("{name} = {sieve}");
+            self.names.insert(name.to_string(), sieve);
+            return Ok(reply);
+        }
+        let (command, rest) = match line.split_once(char::is_whitespace) {
+            Some((c, r)) => (c, r.trim()),
+            None => (line, ""),
+        };
+        match command {
+            "" => Ok(String::new()),
+            "help" => Ok(HELP.to_string()),
+            "list" => Ok(self
+                .names
+                .iter()
+                .map(|(n, s)| format!("{n} = {s}"))
+                .collect::<Vec<_>>()
+                .join("\n")),
+            "range" => {
+                let invalid = || format!("invalid range: {rest}");
+                let (start, end) = rest.split_once("..").ok_or_else(invalid)?;
+                let start: i128 = start.trim().parse().map_err(|_| invalid())?;
+                let end: i128 = end.trim().parse().map_err(|_| invalid())?;
+                if end > start && end.abs_diff(start) > RANGE_LIMIT {
+                    return Err(format!(
+                        "range of {} values exceeds the limit of {RANGE_LIMIT}",
+                        end.abs_diff(start)
+                    ));
+                }
+                self.range = start..end;
+                Ok(format!("range = {start}..{end}"))
+            }
+            "values" => {
+                let sieve = self.parse(rest)?;
+                Ok(format!("{:?}", sieve.scan(self.range.clone())))
+            }
+            "draw" => {
+                let sieve = self.parse(rest)?;
+                Ok(sieve
+                    .iter_state(self.range.clone())
+                    .map(|s| if s { 'x' } else { '.' })
+                    .collect())
+            }
+            _ => self.parse(line).map(|s| s.to_string()),
+        }
+    }
+}
+
+/// Return the string notation of a sieve, without the `Sieve{}` of Display.
+fn expression(sieve: &Sieve) -> String {
+    let s = sieve.to_string();
+    s["Sieve{".len()..s.len() - 1].to_string()
+}
+
+/// Run an interactive session, reading lines from standard input until end of input or `quit`. Lines are read as entered, without line editing or history, such that no dependency is needed; a terminal wrapper such as `rlwrap` can provide them.
+#[rustfmt::skip]
+fn repl() { // cov-excl-line
+    let mut session = Session::new(); // cov-excl-line
+    let stdin = std::io::stdin(); // cov-excl-line
+    let mut lines = stdin.lock().lines(); // cov-excl-line
+    loop { // cov-excl-line
+        print!("> "); // cov-excl-line
+        std::io::stdout().flush().ok(); // cov-excl-line
+        let line = match lines.next() { // cov-excl-line
+            Some(Ok(line)) => line, // cov-excl-line
+            _ => break, // cov-excl-line
+        }; // cov-excl-line
+        if matches!(line.trim(), "quit" | "exit") { // cov-excl-line
+            break; // cov-excl-line
+        } // cov-excl-line
+        match session.eval(&line) { // cov-excl-line
+            Ok(s) if s.is_empty() => {} // cov-excl-line
+            Ok(s) => println!("{s}"), // cov-excl-line
+            Err(e) => println!("error: {e}"), // cov-excl-line
+        } // cov-excl-line
+    } // cov-excl-line
+} // cov-excl-line
+
 /// CLI entry point.
 #[rustfmt::skip]
 fn main() { // cov-excl-line
-    println!("xensieve"); // cov-excl-line
+    match std::env::args().nth(1).as_deref() { // cov-excl-line
+        Some("repl") => repl(), // cov-excl-line
+        _ => println!("xensieve"), // cov-excl-line
+    } // cov-excl-line
 } // cov-excl-line
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_eval_a() {
+        let mut s = Session::new();
+        assert_eq!(s.eval("a = 3@0").unwrap(), "a = Sieve{3@0}");
+        assert_eq!(s.eval("b = a | 4@1").unwrap(), "b = Sieve{3@0|4@1}");
+        assert_eq!(s.eval("!b & 0x2@0").unwrap(), "Sieve{!(3@0|4@1)&2@0}");
+        assert_eq!(s.eval("range 0..12").unwrap(), "range = 0..12");
+        assert_eq!(s.eval("values b").unwrap(), "[0, 1, 3, 5, 6, 9]");
+        assert_eq!(s.eval("draw b").unwrap(), "xx.x.xx..x..");
+        assert_eq!(
+            s.eval("list").unwrap(),
+            "a = Sieve{3@0}\nb = Sieve{3@0|4@1}"
+        );
+        assert_eq!(s.eval("  ").unwrap(), "");
+    }
+
+    #[test]
+    fn test_session_eval_b() {
+        let mut s = Session::new();
+        assert_eq!(s.eval("c | 3@0").unwrap_err(), "unknown name: c");
+        assert_eq!(s.eval("draw = 3@0").unwrap_err(), "invalid name: draw");
+        assert_eq!(s.eval("1a = 3@0").unwrap_err(), "invalid name: 1a");
+        assert_eq!(s.eval("range 4").unwrap_err(), "invalid range: 4");
+        assert_eq!(
+            s.eval("range 0..65537").unwrap_err(),
+            "range of 65537 values exceeds the limit of 65536"
+        );
+        assert_eq!(
+            s.eval("range -170141183460469231731687303715884105728..0")
+                .unwrap_err(),
+            "range of 170141183460469231731687303715884105728 values exceeds the limit of 65536"
+        );
+        assert_eq!(s.eval("range 0..65536").unwrap(), "range = 0..65536");
+        assert_eq!(s.eval("range 5..0").unwrap(), "range = 5..0");
+        assert!(s.eval("values 3@").is_err());
+    }
+}
@@ -0,0 +1,470 @@
+use std::fmt;
+
+use crate::Preset;
+use crate::PresetError;
+use crate::Sieve;
+
+/// A JSON value of a manifest field: a string, a number (kept as its text), an array of numbers, or any other value, which is parsed but not kept.
+#[derive(Debug, PartialEq)]
+enum Value {
+    Str(String),
+    Num(String),
+    Array(Vec<String>),
+    Other,
+}
+
+/// A recursive descent parser of JSON text, reading characters from a position.
+struct Parser {
+    chars: Vec<char>,
+    i: usize,
+}
+
+impl Parser {
+    fn invalid(&self) -> PresetError {
+        PresetError::Field(format!("malformed JSON at {}", self.i))
+    }
+
+    fn skip(&mut self) {
+        while self.i < self.chars.len() && self.chars[self.i].is_whitespace() {
+            self.i += 1;
+        }
+    }
+
+    /// Return the next character that is not whitespace, without consuming it.
+    fn peek(&mut self) -> Option<char> {
+        self.skip();
+        self.chars.get(self.i).copied()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), PresetError> {
+        if self.peek() == Some(c) {
+            self.i += 1;
+            Ok(())
+        } else {
+            Err(self.invalid())
+        }
+    }
+
+    /// Read four hexadecimal digits of a `\u` escape.
+    fn hex(&mut self) -> Result<u32, PresetError> {
+        let digits: String = self.chars.iter().skip(self.i).take(4).collect();
+        if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(self.invalid());
+        }
+        self.i += 4;
+        u32::from_str_radix(&digits, 16).map_err(|_| self.invalid())
+    }
+
+    fn string(&mut self) -> Result<String, PresetError> {
+        self.expect('"')?;
+        let mut post = String::new();
+        loop {
+            let c = *self.chars.get(self.i).ok_or_else(|| self.invalid())?;
+            self.i += 1;
+            match c {
+                '"' => return Ok(post),
+                '\\' => {
+                    let e = *self.chars.get(self.i).ok_or_else(|| self.invalid())?;
+                    self.i += 1;
+                    match e {
+                        '"' | '\\' | '/' => post.push(e),
+                        'b' => post.push('\u{8}'),
+                        'f' => post.push('\u{c}'),
+                        'n' => post.push('\n'),
+                        'r' => post.push('\r'),
+                        't' => post.push('\t'),
+                        'u' => {
+                            let mut code = self.hex()?;
+                            // a high surrogate must be followed by an escaped low surrogate
+                            if (0xD800..0xDC00).contains(&code) {
+                                if self.chars.get(self.i) != Some(&'\\')
+                                    || self.chars.get(self.i + 1) != Some(&'u')
+                                {
+                                    return Err(self.invalid());
+                                }
+                                self.i += 2;
+                                let low = self.hex()?;
+                                if !(0xDC00..0xE000).contains(&low) {
+                                    return Err(self.invalid());
+                                }
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            }
+                            post.push(char::from_u32(code).ok_or_else(|| self.invalid())?);
+                        }
+                        _ => return Err(self.invalid()),
+                    }
+                }
+                c if (c as u32) < 0x20 => return Err(self.invalid()),
+                c => post.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<String, PresetError> {
+        self.skip();
+        let start = self.i;
+        while self.i < self.chars.len()
+            && (self.chars[self.i].is_ascii_digit() || "+-.eE".contains(self.chars[self.i]))
+        {
+            self.i += 1;
+        }
+        if self.i == start {
+            return Err(self.invalid());
+        }
+        Ok(self.chars[start..self.i].iter().collect())
+    }
+
+    /// Read the comma-separated items of an array or object, after its opening character, through its closing character.
+    fn items(
+        &mut self,
+        close: char,
+        mut item: impl FnMut(&mut Self) -> Result<(), PresetError>,
+    ) -> Result<(), PresetError> {
+        if self.peek() == Some(close) {
+            self.i += 1;
+            return Ok(());
+        }
+        loop {
+            item(self)?;
+            match self.peek() {
+                Some(',') => self.i += 1,
+                Some(c) if c == close => {
+                    self.i += 1;
+                    return Ok(());
+                }
+                _ => return Err(self.invalid()),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Vec<(String, Value)>, PresetError> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        self.items('}', |p| {
+            let key = p.string()?;
+            p.expect(':')?;
+            fields.push((key, p.value()?));
+            Ok(())
+        })?;
+        Ok(fields)
+    }
+
+    fn value(&mut self) -> Result<Value, PresetError> {
+        match self.peek() {
+            Some('"') => Ok(Value::Str(self.string()?)),
+            Some('{') => self.object().map(|_| Value::Other),
+            Some('[') => {
+                self.i += 1;
+                let mut items = Vec::new();
+                self.items(']', |p| {
+                    items.push(p.value()?);
+                    Ok(())
+                })?;
+                let numbers: Option<Vec<String>> = items
+                    .into_iter()
+                    .map(|v| match v {
+                        Value::Num(n) => Some(n),
+                        _ => None,
+                    })
+                    .collect();
+                Ok(numbers.map_or(Value::Other, Value::Array))
+            }
+            Some('t' | 'f' | 'n') => {
+                for literal in ["true", "false", "null"] {
+                    if self.chars[self.i..].starts_with(&literal.chars().collect::<Vec<_>>()) {
+                        self.i += literal.len();
+                        return Ok(Value::Other);
+                    }
+                }
+                Err(self.invalid())
+            }
+            _ => Ok(Value::Num(self.number()?)),
+        }
+    }
+}
+
+/// Parse a JSON object into its fields in order. Strings, numbers, and arrays of numbers are kept; any other value, such as a boolean, null, a nested object, or an array of other values, is parsed and kept as `Value::Other`.
+fn parse_object(text: &str) -> Result<Vec<(String, Value)>, PresetError> {
+    let mut p = Parser {
+        chars: text.chars().collect(),
+        i: 0,
+    };
+    let fields = p.object()?;
+    p.skip();
+    if p.i != p.chars.len() {
+        return Err(p.invalid());
+    }
+    Ok(fields)
+}
+
+//------------------------------------------------------------------------------
+
+/// A reproducibility manifest for generated material: a Preset, with the version of this crate, the numeric type, and the seeds of any random choices used to realize it, encoded as JSON such that a realization can be exactly reproduced later.
+///
+/// # Fields
+/// * `preset` - The Sieve and its realization options.
+/// * `version` - The version of this crate that produced the material.
+/// * `numeric_type` - The name of the integer type values were realized in, such as `i128`.
+/// * `seeds` - The seeds of random choices, such as of `Conflict::Random` or `Pattern::Random`, in the order used.
+///
+/// ```
+/// let p = xensieve::Preset::new(xensieve::Sieve::new("3@0|4@1"), 0..12);
+/// let mut m = xensieve::Manifest::new(p);
+/// m.seeds = vec![42];
+/// let json = m.to_json();
+/// assert!(json.starts_with("{\"version\":\""));
+/// let m2 = xensieve::Manifest::from_json(&json).unwrap();
+/// assert_eq!(m2, m);
+/// assert_eq!(m2.realize(), vec![0, 1, 3, 5, 6, 9]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Manifest {
+    pub preset: Preset,
+    pub version: String,
+    pub numeric_type: String,
+    pub seeds: Vec<u64>,
+}
+
+impl Manifest {
+    /// Construct a Manifest of a Preset, recording the version of this crate, the `i128` numeric type, and no seeds.
+    pub fn new(preset: Preset) -> Self {
+        Self {
+            preset,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            numeric_type: "i128".to_string(),
+            seeds: Vec::new(),
+        }
+    }
+
+    /// Return the members of the Sieve within the range of the Preset.
+    pub fn realize(&self) -> Vec<i128> {
+        self.preset.sieve.scan(self.preset.range.clone())
+    }
+
+    /// Return the manifest as a JSON object.
+    pub fn to_json(&self) -> String {
+        let p = &self.preset;
+        let seeds: Vec<String> = self.seeds.iter().map(|s| s.to_string()).collect();
+        format!(
+            "{{\"version\":\"{}\",\"numeric_type\":\"{}\",\"sieve\":\"{}\",\"range\":[{},{}],\"reference\":{},\"edo\":{},\"tempo\":{},\"steps_per_beat\":{},\"seeds\":[{}]}}",
+            escape(&self.version),
+            escape(&self.numeric_type),
+            p.sieve.root,
+            p.range.start,
+            p.range.end,
+            p.reference,
+            p.edo,
+            p.tempo,
+            p.steps_per_beat,
+            seeds.join(","),
+        )
+    }
+
+    /// Construct a Manifest from a JSON object, as produced by `to_json`. Fields other than `sieve` may be omitted, taking the defaults of `Preset::new` and `Manifest::new`, and unknown fields, of any JSON value, are ignored.
+    pub fn from_json(value: &str) -> Result<Self, PresetError> {
+        let mut sieve = None;
+        let mut manifest = Self::new(Preset::new(Sieve::empty(), 0..0));
+        let mut seen: Vec<String> = Vec::new();
+        for (key, value) in parse_object(value)? {
+            if seen.contains(&key) {
+                return Err(PresetError::Field(key));
+            }
+            let invalid = || PresetError::Field(key.clone());
+            let p = &mut manifest.preset;
+            match (key.as_str(), &value) {
+                ("version", Value::Str(s)) => manifest.version = s.clone(),
+                ("numeric_type", Value::Str(s)) => manifest.numeric_type = s.clone(),
+                ("sieve", Value::Str(s)) => {
+                    sieve = Some(Sieve::try_new(s).map_err(PresetError::Sieve)?)
+                }
+                ("range", Value::Array(items)) if items.len() == 2 => {
+                    let start = items[0].parse().map_err(|_| invalid())?;
+                    let end = items[1].parse().map_err(|_| invalid())?;
+                    p.range = start..end;
+                }
+                ("reference", Value::Num(n)) => p.reference = n.parse().map_err(|_| invalid())?,
+                ("edo", Value::Num(n)) => {
+                    p.edo = n.parse().map_err(|_| invalid())?;
+                    if p.edo == 0 {
+                        return Err(invalid());
+                    }
+                }
+                ("tempo", Value::Num(n)) => {
+                    p.tempo = n.parse().map_err(|_| invalid())?;
+                    if !p.tempo.is_finite() || p.tempo <= 0.0 {
+                        return Err(invalid());
+                    }
+                }
+                ("steps_per_beat", Value::Num(n)) => {
+                    p.steps_per_beat = n.parse().map_err(|_| invalid())?;
+                    if p.steps_per_beat == 0 {
+                        return Err(invalid());
+                    }
+                }
+                ("seeds", Value::Array(items)) => {
+                    manifest.seeds = items
+                        .iter()
+                        .map(|s| s.parse())
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| invalid())?;
+                }
+                (
+                    "version" | "numeric_type" | "sieve" | "range" | "reference" | "edo" | "tempo"
+                    | "steps_per_beat" | "seeds",
+                    _,
+                ) => return Err(invalid()),
+                // fields from other versions
+                _ => {}
+            }
+            seen.push(key);
+        }
+        manifest.preset.sieve = sieve.ok_or_else(|| PresetError::Field("sieve".to_string()))?;
+        Ok(manifest)
+    }
+}
+
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_json())
+    }
+}
+
+/// Return the text with the characters special to JSON strings, and control characters, escaped.
+fn escape(text: &str) -> String {
+    let mut post = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => post.push_str("\\\""),
+            '\\' => post.push_str("\\\\"),
+            '\n' => post.push_str("\\n"),
+            '\r' => post.push_str("\\r"),
+            '\t' => post.push_str("\\t"),
+            c if (c as u32) < 0x20 => post.push_str(&format!("\\u{:04x}", c as u32)),
+            c => post.push(c),
+        }
+    }
+    post
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_object_a() {
+        assert_eq!(
+            parse_object(" { \"a\" : \"x\\\"y\", \"b\": -1.5e3, \"c\": [ 1 , 2 ], \"d\": [] } ")
+                .unwrap(),
+            vec![
+                ("a".to_string(), Value::Str("x\"y".to_string())),
+                ("b".to_string(), Value::Num("-1.5e3".to_string())),
+                (
+                    "c".to_string(),
+                    Value::Array(vec!["1".to_string(), "2".to_string()])
+                ),
+                ("d".to_string(), Value::Array(vec![])),
+            ]
+        );
+        assert_eq!(parse_object("{}").unwrap(), vec![]);
+        for s in [
+            "",
+            "{",
+            "{\"a\"}",
+            "{\"a\":}",
+            "{\"a\":1,}",
+            "{\"a\":1} x",
+            "{\"a\":[1,}",
+        ] {
+            assert!(parse_object(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_parse_object_b() {
+        assert_eq!(
+            parse_object(
+                "{\"a\": true, \"b\": {\"c\": [1, {\"d\": null}]}, \"e\": [false], \"f\": \"x\\ny\\u00e9\\ud834\\udd1e\\t\"}"
+            )
+            .unwrap(),
+            vec![
+                ("a".to_string(), Value::Other),
+                ("b".to_string(), Value::Other),
+                ("e".to_string(), Value::Other),
+                ("f".to_string(), Value::Str("x\ny\u{e9}\u{1d11e}\t".to_string())),
+            ]
+        );
+        for s in [
+            "{\"a\":tru}",
+            "{\"a\":{\"b\":1}",
+            "{\"a\":\"\\x\"}",
+            "{\"a\":\"\\u12\"}",
+            "{\"a\":\"\\ud834\"}",
+            "{\"a\":\"x\ny\"}",
+        ] {
+            assert!(parse_object(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_escape_a() {
+        assert_eq!(escape("a\"b\\c\nd\u{1}"), "a\\\"b\\\\c\\nd\\u0001");
+        let s = "x\"\\\n\r\t\u{1f}y";
+        let json = format!("{{\"a\":\"{}\"}}", escape(s));
+        assert_eq!(
+            parse_object(&json).unwrap(),
+            vec![("a".to_string(), Value::Str(s.to_string()))]
+        );
+    }
+
+    #[test]
+    fn test_manifest_from_json_a() {
+        let mut p = Preset::new(Sieve::new("(5@0|4@2)&!(30@10)"), -12..36);
+        p.tempo = 72.5;
+        let mut m = Manifest::new(p);
+        m.version = "0.1.0".to_string();
+        m.numeric_type = "u16".to_string();
+        m.seeds = vec![7, u64::MAX];
+        let json = m.to_json();
+        assert_eq!(
+            json,
+            format!("{{\"version\":\"0.1.0\",\"numeric_type\":\"u16\",\"sieve\":\"(5@0|4@2)&!(30@10)\",\"range\":[-12,36],\"reference\":60,\"edo\":12,\"tempo\":72.5,\"steps_per_beat\":4,\"seeds\":[7,{}]}}", u64::MAX)
+        );
+        assert_eq!(Manifest::from_json(&json).unwrap(), m);
+    }
+
+    #[test]
+    fn test_manifest_from_json_b() {
+        let m = Manifest::from_json("{\"sieve\": \"5@2\", \"swing\": 0.6}").unwrap();
+        assert_eq!(m.preset.sieve.to_string(), "Sieve{5@2}");
+        for json in [
+            "{\"sieve\":\"5@2\",\"flag\":true}",
+            "{\"sieve\":\"5@2\",\"extra\":{\"a\":1}}",
+            "{\"sieve\":\"5@2\",\"note\":\"a\\nb\"}",
+        ] {
+            let m = Manifest::from_json(json).unwrap();
+            assert_eq!(m.preset.sieve.to_string(), "Sieve{5@2}", "{json}");
+        }
+        let mut m = Manifest::new(Preset::new(Sieve::new("5@2"), 0..10));
+        m.version = "a\nb".to_string();
+        assert_eq!(Manifest::from_json(&m.to_json()).unwrap(), m);
+        assert_eq!(m.numeric_type, "i128");
+        assert_eq!(
+            Manifest::from_json("{\"edo\": 12}").unwrap_err(),
+            PresetError::Field("sieve".to_string())
+        );
+        assert_eq!(
+            Manifest::from_json("{\"sieve\": \"5@2\", \"seeds\": [-1]}").unwrap_err(),
+            PresetError::Field("seeds".to_string())
+        );
+        assert_eq!(
+            Manifest::from_json("{\"sieve\": \"5@2\", \"sieve\": \"5@2\"}").unwrap_err(),
+            PresetError::Field("sieve".to_string())
+        );
+        assert_eq!(
+            Manifest::from_json("{\"sieve\": 5}").unwrap_err(),
+            PresetError::Field("sieve".to_string())
+        );
+    }
+}
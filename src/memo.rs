@@ -0,0 +1,103 @@
+use crate::CompiledSieve;
+use crate::Residual;
+use crate::SieveNode;
+
+/// The largest local period for which a node stores a table of membership.
+const TABLE_LIMIT: u64 = 1 << 16;
+
+/// A memoized tree of a Sieve, as stored by `Sieve::warm`: each node whose local period (the least common multiple of the moduli beneath it) is no greater than `TABLE_LIMIT` is replaced by a table of membership over that period, such that it is answered by one lookup rather than by walking its subtree. Nodes with larger periods keep their operator, with memoized operands.
+#[derive(Clone, Debug)]
+pub(crate) enum Memo {
+    Table(CompiledSieve),
+    Unit(Residual),
+    Intersection(Box<Memo>, Box<Memo>),
+    Union(Box<Memo>, Box<Memo>),
+    SymmetricDifference(Box<Memo>, Box<Memo>),
+    Difference(Box<Memo>, Box<Memo>),
+    Inversion(Box<Memo>),
+}
+
+impl Memo {
+    pub(crate) fn new(node: &SieveNode) -> Self {
+        if let Some(period) = node.cycle() {
+            if period <= TABLE_LIMIT {
+                return Memo::Table(CompiledSieve::from_node(node));
+            }
+        }
+        let pair =
+            |lhs: &SieveNode, rhs: &SieveNode| (Box::new(Memo::new(lhs)), Box::new(Memo::new(rhs)));
+        match node {
+            SieveNode::Unit(residual) => Memo::Unit(*residual),
+            SieveNode::Intersection(lhs, rhs) => {
+                let (a, b) = pair(lhs, rhs);
+                Memo::Intersection(a, b)
+            }
+            SieveNode::Union(lhs, rhs) => {
+                let (a, b) = pair(lhs, rhs);
+                Memo::Union(a, b)
+            }
+            SieveNode::SymmetricDifference(lhs, rhs) => {
+                let (a, b) = pair(lhs, rhs);
+                Memo::SymmetricDifference(a, b)
+            }
+            SieveNode::Difference(lhs, rhs) => {
+                let (a, b) = pair(lhs, rhs);
+                Memo::Difference(a, b)
+            }
+            SieveNode::Inversion(part) => Memo::Inversion(Box::new(Memo::new(part))),
+        }
+    }
+
+    pub(crate) fn contains(&self, value: i128) -> bool {
+        match self {
+            Memo::Table(table) => table.contains(value),
+            Memo::Unit(residual) => residual.contains(value),
+            Memo::Intersection(lhs, rhs) => lhs.contains(value) && rhs.contains(value),
+            Memo::Union(lhs, rhs) => lhs.contains(value) || rhs.contains(value),
+            Memo::SymmetricDifference(lhs, rhs) => lhs.contains(value) ^ rhs.contains(value),
+            Memo::Difference(lhs, rhs) => lhs.contains(value) && !rhs.contains(value),
+            Memo::Inversion(part) => !part.contains(value),
+        }
+    }
+
+    /// Return the number of tables.
+    #[cfg(test)]
+    fn tables(&self) -> usize {
+        match self {
+            Memo::Table(_) => 1,
+            Memo::Unit(_) => 0,
+            Memo::Intersection(lhs, rhs)
+            | Memo::Union(lhs, rhs)
+            | Memo::SymmetricDifference(lhs, rhs)
+            | Memo::Difference(lhs, rhs) => lhs.tables() + rhs.tables(),
+            Memo::Inversion(part) => part.tables(),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sieve;
+
+    #[test]
+    fn test_memo_new_a() {
+        // the union of the two large moduli exceeds the limit, and is not a table
+        let s = Sieve::new("(5@0|4@2)&!(30@10)|65537@3&(65539@1|3@0)");
+        let m = Memo::new(&s.root);
+        assert_eq!(m.tables(), 2);
+        for v in (-200..200).chain(65530..65560) {
+            assert_eq!(m.contains(v), s.contains(v), "{v}");
+        }
+    }
+
+    #[test]
+    fn test_memo_new_b() {
+        let m = Memo::new(&Sieve::new("3@0|4@1").root);
+        assert!(matches!(m, Memo::Table(_)));
+        let m = Memo::new(&Sieve::new("70001@5").root);
+        assert!(matches!(m, Memo::Unit(_)));
+    }
+}
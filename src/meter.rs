@@ -0,0 +1,172 @@
+use crate::util;
+
+/// A candidate meter for a rhythmic Sieve: a hierarchy of nested pulses, each contained within the Sieve, sharing a downbeat.
+///
+/// # Fields
+/// * `downbeat` - The position, within the period, shared by every pulse.
+/// * `levels` - The pulse widths, in steps, from the finest beat level to the measure; each divides the next.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Meter {
+    pub downbeat: u64,
+    pub levels: Vec<u64>,
+}
+
+impl Meter {
+    /// Return the measure, the widest pulse, in steps.
+    pub fn measure(&self) -> u64 {
+        self.levels[self.levels.len() - 1]
+    }
+
+    /// Return a time signature, as (numerator, denominator), for the measure, where `steps_per_quarter` steps make a quarter note. The denominator notates the beat level below the measure or, in a compound meter, where the beat divides into three or is a dotted value, the third of the beat. If that level is not a power-of-two note value, or there is no level below the measure, the smallest power-of-two denominator from 4 to 64 that gives a whole numerator is used. Returns `None` if there is no such denominator.
+    ///
+    /// ```
+    /// let m = xensieve::Meter { downbeat: 0, levels: vec![1, 2, 6] };
+    /// assert_eq!(m.time_signature(2), Some((3, 4)));
+    /// let m = xensieve::Meter { downbeat: 0, levels: vec![1, 3, 6] };
+    /// assert_eq!(m.time_signature(2), Some((6, 8)));
+    /// ```
+    pub fn time_signature(&self, steps_per_quarter: u64) -> Option<(u64, u64)> {
+        assert!(
+            steps_per_quarter > 0,
+            "steps_per_quarter must be greater than zero"
+        );
+        let measure = self.measure() as u128;
+        let whole = 4 * steps_per_quarter as u128;
+        let n = self.levels.len();
+        // the note value, as a denominator, of a width in steps, if a power of two
+        let value = |width: u128| {
+            if whole % width == 0 && (whole / width).is_power_of_two() && whole / width <= 64 {
+                Some(whole / width)
+            } else {
+                None
+            }
+        };
+        let unit = if n >= 3 && self.levels[n - 2] / self.levels[n - 3] == 3 {
+            Some(self.levels[n - 3] as u128)
+        } else if n >= 2 {
+            let beat = self.levels[n - 2] as u128;
+            if value(beat).is_none() && beat % 3 == 0 {
+                Some(beat / 3)
+            } else {
+                Some(beat)
+            }
+        } else {
+            None
+        };
+        if let Some(unit) = unit {
+            if let Some(denominator) = value(unit) {
+                return Some(((measure / unit) as u64, denominator as u64));
+            }
+        }
+        let mut denominator = 4;
+        while denominator <= 64 {
+            if (measure * denominator) % whole == 0 {
+                return Some(((measure * denominator / whole) as u64, denominator as u64));
+            }
+            denominator *= 2;
+        }
+        None
+    }
+}
+
+/// Return the longest chain of values, each dividing the next, from ascending values; ties prefer the lexicographically smallest chain.
+fn longest_chain(values: &[u64]) -> Vec<u64> {
+    // for each value, the length of the longest chain ending there and the previous index
+    let mut best: Vec<(usize, Option<usize>)> = Vec::with_capacity(values.len());
+    for (i, v) in values.iter().enumerate() {
+        let mut entry = (1, None);
+        for (j, u) in values[..i].iter().enumerate() {
+            if v % u == 0 && best[j].0 + 1 > entry.0 {
+                entry = (best[j].0 + 1, Some(j));
+            }
+        }
+        best.push(entry);
+    }
+    let mut end = None;
+    for (i, (len, _)) in best.iter().enumerate() {
+        if end.map_or(true, |e: usize| *len > best[e].0) {
+            end = Some(i);
+        }
+    }
+    let mut chain = Vec::new();
+    while let Some(i) = end {
+        chain.push(values[i]);
+        end = best[i].1;
+    }
+    chain.reverse();
+    chain
+}
+
+/// Return candidate Meters for a Sieve of the provided period and membership test: for each member of the period taken as a downbeat, the longest chain of nested pulses, with widths dividing the period, that are wholly contained. Candidates are ordered by decreasing number of levels, then by downbeat.
+pub(crate) fn infer(period: u64, contains: impl Fn(i128) -> bool) -> Vec<Meter> {
+    let widths = util::divisors(period);
+    let mut meters: Vec<Meter> = (0..period)
+        .filter(|s| contains(*s as i128))
+        .map(|s| {
+            let contained: Vec<u64> = widths
+                .iter()
+                .copied()
+                .filter(|d| (0..period / d).all(|k| contains((s + k * d) as i128)))
+                .collect();
+            Meter {
+                downbeat: s,
+                levels: longest_chain(&contained),
+            }
+        })
+        .collect();
+    meters.sort_by(|a, b| {
+        b.levels
+            .len()
+            .cmp(&a.levels.len())
+            .then(a.downbeat.cmp(&b.downbeat))
+    });
+    meters
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_chain_a() {
+        assert_eq!(longest_chain(&[2, 4, 6, 12]), vec![2, 4, 12]);
+        assert_eq!(longest_chain(&[3, 4, 12]), vec![3, 12]);
+        assert_eq!(longest_chain(&[5]), vec![5]);
+        assert_eq!(longest_chain(&[]), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_infer_a() {
+        // 12@0|4@0|2@0|12@3
+        let members = [0, 2, 3, 4, 6, 8, 10];
+        let meters = infer(12, |v| members.contains(&v.rem_euclid(12)));
+        assert_eq!(
+            meters[0],
+            Meter {
+                downbeat: 0,
+                levels: vec![2, 4, 12]
+            }
+        );
+        assert_eq!(
+            meters[meters.len() - 1],
+            Meter {
+                downbeat: 3,
+                levels: vec![12]
+            }
+        );
+    }
+
+    #[test]
+    fn test_time_signature_a() {
+        let m = Meter {
+            downbeat: 0,
+            levels: vec![1, 3],
+        };
+        assert_eq!(m.time_signature(1), Some((3, 4)));
+        assert_eq!(m.time_signature(4), Some((3, 16)));
+        assert_eq!(m.time_signature(128), None);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::util;
+use crate::Residual;
+
+/// Search for a smallest selection of `covers` (each a list of member indices) covering all `count` members, by branch and bound, branching on the uncovered member with the fewest covers.
+fn search(
+    covers: &[Vec<usize>],
+    by_member: &[Vec<usize>],
+    covered: &mut Vec<u32>,
+    chosen: &mut Vec<usize>,
+    best: &mut Option<Vec<usize>>,
+) {
+    if best.as_ref().map_or(false, |b| chosen.len() >= b.len()) {
+        return;
+    }
+    let target = (0..covered.len())
+        .filter(|i| covered[*i] == 0)
+        .min_by_key(|i| by_member[*i].len());
+    let target = match target {
+        Some(t) => t,
+        None => {
+            *best = Some(chosen.clone());
+            return;
+        }
+    };
+    for c in by_member[target].iter() {
+        chosen.push(*c);
+        for m in covers[*c].iter() {
+            covered[*m] += 1;
+        }
+        search(covers, by_member, covered, chosen, best);
+        for m in covers[*c].iter() {
+            covered[*m] -= 1;
+        }
+        chosen.pop();
+    }
+}
+
+/// Return a smallest union of Residuals whose members, over a period, are exactly `members`, where `members` are ascending values in `0..period`. Candidates are the classes, with moduli dividing the period, wholly within the members and not within a larger such class; a smallest cover of the members by candidates is found exactly.
+pub(crate) fn minimize(period: u64, members: &[u64]) -> Vec<Residual> {
+    let is_member = |v: u64| members.binary_search(&v).is_ok();
+    let mut candidates: Vec<Residual> = Vec::new();
+    // ascending moduli, so that larger classes are found first
+    for d in util::divisors(period) {
+        for s in 0..d {
+            let r = Residual::new(d, s);
+            if (0..period / d).all(|k| is_member(s + k * d))
+                && !candidates.iter().any(|c| r.is_subset_of(c))
+            {
+                candidates.push(r);
+            }
+        }
+    }
+    let covers: Vec<Vec<usize>> = candidates
+        .iter()
+        .map(|r| {
+            (0..members.len())
+                .filter(|i| r.contains(members[*i] as i128))
+                .collect()
+        })
+        .collect();
+    let mut by_member: Vec<Vec<usize>> = vec![Vec::new(); members.len()];
+    for (c, cover) in covers.iter().enumerate() {
+        for m in cover.iter() {
+            by_member[*m].push(c);
+        }
+    }
+    let mut best = None;
+    search(
+        &covers,
+        &by_member,
+        &mut vec![0; members.len()],
+        &mut Vec::new(),
+        &mut best,
+    );
+    let mut post: Vec<Residual> = best
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| candidates[c])
+        .collect();
+    post.sort();
+    post
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strings;
+
+    #[test]
+    fn test_minimize_a() {
+        assert_eq!(
+            strings(minimize(12, &[0, 3, 4, 6, 8, 9])),
+            vec!["3@0", "4@0"]
+        );
+        assert_eq!(strings(minimize(6, &[0, 1, 2, 3, 4, 5])), vec!["1@0"]);
+        assert_eq!(strings(minimize(7, &[2])), vec!["7@2"]);
+        assert_eq!(minimize(7, &[]), vec![]);
+    }
+
+    #[test]
+    fn test_minimize_b() {
+        // 2@0|3@0 over 6: {0, 2, 3, 4}
+        assert_eq!(strings(minimize(6, &[0, 2, 3, 4])), vec!["2@0", "3@0"]);
+        // 12@0|12@4|12@8|12@3 -> 4@0|12@3
+        assert_eq!(strings(minimize(12, &[0, 3, 4, 8])), vec!["4@0", "12@3"]);
+    }
+}
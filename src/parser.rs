@@ -1,18 +1,206 @@
 use std::collections::VecDeque;
+use std::num::IntErrorKind;
+
+use crate::SieveError;
+
+/// Parse a non-negative integer literal in the provided radix, where digits may be grouped with underscores, such as `1_000_003`. Returns `None` if the literal is malformed, or `Some(None)` if it exceeds the range of `u64`.
+fn literal_to_int(value: &str, radix: u32) -> Option<Option<u64>> {
+    if value.starts_with('_') || value.ends_with('_') || value.contains("__") {
+        return None;
+    }
+    let digits: String = value.chars().filter(|c| *c != '_').collect();
+    match u64::from_str_radix(&digits, radix) {
+        Ok(n) => Some(Some(n)),
+        Err(e) if *e.kind() == IntErrorKind::PosOverflow => Some(None),
+        Err(_) => None,
+    }
+}
+
+/// Parse a decimal or, with a `0x` prefix, hexadecimal literal, as `literal_to_int`.
+fn prefixed_to_int(value: &str) -> Option<Option<u64>> {
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => literal_to_int(hex, 16),
+        None => literal_to_int(value, 10),
+    }
+}
+
+/// A recursive-descent evaluator of arithmetic on non-negative literals with `+`, `-`, `*`, and parentheses. Intermediate results are checked for overflow.
+struct Arithmetic<'a> {
+    chars: &'a [char],
+    pos: usize,
+}
+
+/// The failure of an arithmetic evaluation: a malformed expression, or an overflow.
+#[derive(Debug, PartialEq)]
+enum ArithmeticError {
+    Malformed,
+    Overflow,
+}
+
+impl<'a> Arithmetic<'a> {
+    fn peek(&mut self) -> Option<char> {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+        self.chars.get(self.pos).copied()
+    }
+
+    fn expression(&mut self) -> Result<i128, ArithmeticError> {
+        let mut value = self.term()?;
+        while let Some(op) = self.peek().filter(|c| *c == '+' || *c == '-') {
+            self.pos += 1;
+            let rhs = self.term()?;
+            value = if op == '+' {
+                value.checked_add(rhs)
+            } else {
+                value.checked_sub(rhs)
+            }
+            .ok_or(ArithmeticError::Overflow)?;
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<i128, ArithmeticError> {
+        let mut value = self.factor()?;
+        while self.peek() == Some('*') {
+            self.pos += 1;
+            let rhs = self.factor()?;
+            value = value.checked_mul(rhs).ok_or(ArithmeticError::Overflow)?;
+        }
+        Ok(value)
+    }
+
+    fn factor(&mut self) -> Result<i128, ArithmeticError> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let value = self.expression()?;
+                if self.peek() != Some(')') {
+                    return Err(ArithmeticError::Malformed);
+                }
+                self.pos += 1;
+                Ok(value)
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let start = self.pos;
+                while self.pos < self.chars.len()
+                    && (self.chars[self.pos].is_ascii_alphanumeric() || self.chars[self.pos] == '_')
+                {
+                    self.pos += 1;
+                }
+                let literal: String = self.chars[start..self.pos].iter().collect();
+                match prefixed_to_int(&literal) {
+                    Some(Some(n)) => Ok(n as i128),
+                    Some(None) => Err(ArithmeticError::Overflow),
+                    None => Err(ArithmeticError::Malformed),
+                }
+            }
+            _ => Err(ArithmeticError::Malformed),
+        }
+    }
+}
+
+/// Evaluate an arithmetic expression, such as `3*4` or `0x10+1`, to a value in the range of `u64`.
+fn evaluate(expr: &[char]) -> Result<u64, SieveError> {
+    let text: String = expr.iter().collect();
+    let mut a = Arithmetic {
+        chars: expr,
+        pos: 0,
+    };
+    let value = a.expression().and_then(|v| {
+        if a.peek().is_some() {
+            Err(ArithmeticError::Malformed)
+        } else {
+            Ok(v)
+        }
+    });
+    match value {
+        Ok(v) if v < 0 => Err(SieveError::InvalidResidual(text)),
+        Ok(v) => u64::try_from(v).map_err(|_| SieveError::Overflow(text)),
+        Err(ArithmeticError::Overflow) => Err(SieveError::Overflow(text)),
+        Err(ArithmeticError::Malformed) => Err(SieveError::InvalidResidual(text)),
+    }
+}
+
+/// Return the index of the parenthesis closing the one at `open`, if any.
+fn closing(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in chars.iter().enumerate().skip(open) {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Replace literals that are not plain decimals with their decimal values: hexadecimal literals, such as `0x10`, and parenthesized arithmetic adjacent to `@`, such as `(3*4)@(2+1)`. Other parentheses group Sieve operations and are kept.
+pub(crate) fn evaluate_literals(expr: &str) -> Result<String, SieveError> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut post = String::with_capacity(expr.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '(' {
+            if let Some(j) = closing(&chars, i) {
+                let before = post.trim_end().ends_with('@');
+                let after = chars[j + 1..].iter().find(|c| !c.is_whitespace()) == Some(&'@');
+                if before || after {
+                    post.push_str(&evaluate(&chars[i + 1..j])?.to_string());
+                    i = j + 1;
+                    continue;
+                }
+            }
+        } else if c == '0'
+            && matches!(chars.get(i + 1), Some('x') | Some('X'))
+            && !post.ends_with(|p: char| p.is_ascii_alphanumeric() || p == '_')
+        {
+            let mut j = i + 2;
+            while j < chars.len() && (chars[j].is_ascii_hexdigit() || chars[j] == '_') {
+                j += 1;
+            }
+            post.push_str(&evaluate(&chars[i..j])?.to_string());
+            i = j;
+            continue;
+        }
+        post.push(c);
+        i += 1;
+    }
+    Ok(post)
+}
 
 /// Given a Residual string representation, parse it into two integers.
-pub(crate) fn residual_to_ints(value: &str) -> Result<(u64, u64), &'static str> {
+pub(crate) fn residual_to_ints(value: &str) -> Result<(u64, u64), SieveError> {
     let parts: Vec<&str> = value.split('@').collect();
     if parts.len() != 2 {
-        return Err("Input must contain one '@' character separating two numbers.");
-    }
-    let m = parts[0]
-        .parse::<u64>()
-        .map_err(|_e| "Residual error parsing modulus")?;
-    let s = parts[1]
-        .parse::<u64>()
-        .map_err(|_e| "Residual error parsing shift")?;
-    Ok((m, s))
+        return Err(SieveError::InvalidResidual(value.to_string()));
+    }
+    let mut ints = [0; 2];
+    for (i, part) in parts.iter().enumerate() {
+        ints[i] = literal_to_int(part, 10)
+            .ok_or_else(|| SieveError::InvalidResidual(value.to_string()))?
+            .ok_or_else(|| SieveError::Overflow(part.to_string()))?;
+    }
+    Ok((ints[0], ints[1]))
+}
+
+/// Remove the `Sieve{...}` wrapper used by `Display`, if present.
+pub(crate) fn strip_display(value: &str) -> &str {
+    let trimmed = value.trim();
+    if trimmed.starts_with("Sieve{") && trimmed.ends_with('}') {
+        &trimmed[6..trimmed.len() - 1]
+    } else {
+        value
+    }
 }
 
 /// Operator precedence for `infix_to_postfix`.
@@ -20,35 +208,74 @@ pub(crate) fn residual_to_ints(value: &str) -> Result<(u64, u64), &'static str>
 fn char_to_precedence(op: char) -> i8 {
     match op {
         '!' => 4,
-        '&' => 3,
+        '&' | '-' => 3,
         '^' => 2,
         '|' => 1,
         _ => 0,
     }
 }
 
-/// Utility function to reduce repetition in `infix_to_postfix`.
+/// Utility function to reduce repetition in `infix_to_postfix`. Returns `true` if an operand was collected.
 #[inline(always)]
-fn collect_operand(post: &mut VecDeque<String>, operand: &mut String) {
+fn collect_operand(post: &mut VecDeque<String>, operand: &mut String) -> bool {
     if !operand.is_empty() {
         post.push_back(operand.clone());
         operand.clear();
+        return true;
     }
+    false
 }
 
 // Implementation of Shunting yard algorithm for Sieve expressions.
-pub(crate) fn infix_to_postfix(expr: &str) -> Result<VecDeque<String>, String> {
+pub(crate) fn infix_to_postfix(expr: &str) -> Result<VecDeque<String>, SieveError> {
     let mut post: VecDeque<String> = VecDeque::new();
     let mut operators: Vec<char> = Vec::new();
     let mut operand: String = String::new();
+    // true where an operand, `!`, or `(` is required next
+    let mut expect_operand = true;
+    // true where whitespace follows a partial operand
+    let mut spaced = false;
 
     for c in expr.chars() {
         match c {
-            '0'..='9' | '@' => operand.push(c), // operand characters
-            '!' => operators.push(c),
-            '|' | '&' | '^' => {
+            '0'..='9' | '_' | '@' => {
+                // operand characters
+                if !expect_operand && operand.is_empty() {
+                    return Err(SieveError::MissingOperator);
+                }
+                if spaced && c != '@' && !operand.ends_with('@') {
+                    // whitespace used to group digits
+                    return Err(SieveError::DigitSeparator(' '));
+                }
+                spaced = false;
+                operand.push(c);
+                continue;
+            }
+            _ if c.is_whitespace() => {
+                spaced = !operand.is_empty();
+                continue;
+            }
+            ',' | '.' | '\'' if !operand.is_empty() => {
+                return Err(SieveError::DigitSeparator(c));
+            }
+            _ => {}
+        }
+        spaced = false;
+        if collect_operand(&mut post, &mut operand) {
+            expect_operand = false;
+        }
+        match c {
+            '!' | '(' => {
+                if !expect_operand {
+                    return Err(SieveError::MissingOperator);
+                }
+                operators.push(c);
+            }
+            '|' | '&' | '^' | '-' => {
                 // all binary operators
-                collect_operand(&mut post, &mut operand);
+                if expect_operand {
+                    return Err(SieveError::MissingOperand);
+                }
                 while let Some(&top) = operators.last() {
                     if top == '(' || char_to_precedence(top) < char_to_precedence(c) {
                         break;
@@ -56,29 +283,40 @@ pub(crate) fn infix_to_postfix(expr: &str) -> Result<VecDeque<String>, String> {
                     post.push_back(operators.pop().unwrap().to_string())
                 }
                 operators.push(c);
+                expect_operand = true;
             }
-            '(' => operators.push(c),
             ')' => {
-                collect_operand(&mut post, &mut operand);
-                while let Some(top) = operators.pop() {
-                    if top == '(' {
-                        break;
+                if expect_operand {
+                    return Err(SieveError::MissingOperand);
+                }
+                loop {
+                    match operators.pop() {
+                        Some('(') => break,
+                        Some(top) => post.push_back(top.to_string()),
+                        None => return Err(SieveError::UnbalancedParentheses),
                     }
-                    post.push_back(top.to_string())
                 }
             }
-            _ if c.is_whitespace() => {}
             _ => {
-                return Err(format!("Found unsupported operator: {}.", c));
+                return Err(SieveError::UnknownToken(c));
             }
         }
     }
     // get any remaining numbers
-    if !operand.is_empty() {
-        post.push_back(operand);
+    if collect_operand(&mut post, &mut operand) {
+        expect_operand = false;
+    }
+    if expect_operand {
+        if post.is_empty() && operators.is_empty() {
+            return Err(SieveError::Empty);
+        }
+        return Err(SieveError::MissingOperand);
     }
     // get any remaining operators
     while let Some(op) = operators.pop() {
+        if op == '(' {
+            return Err(SieveError::UnbalancedParentheses);
+        }
         post.push_back(op.to_string());
     }
     Ok(post)
@@ -121,10 +359,100 @@ mod tests {
         assert!(residual_to_ints("foo@3").is_err());
     }
 
+    #[test]
+    fn test_residual_to_ints_g() {
+        assert_eq!(residual_to_ints("1_000_003@17").unwrap(), (1_000_003, 17));
+        assert_eq!(
+            residual_to_ints("18446744073709551615@1").unwrap(),
+            (u64::MAX, 1)
+        );
+        assert_eq!(
+            residual_to_ints("18446744073709551616@1").unwrap_err(),
+            SieveError::Overflow("18446744073709551616".to_string())
+        );
+        assert_eq!(
+            residual_to_ints("3@99_999_999_999_999_999_999").unwrap_err(),
+            SieveError::Overflow("99_999_999_999_999_999_999".to_string())
+        );
+    }
+
+    #[test]
+    fn test_residual_to_ints_h() {
+        for e in ["_3@1", "3_@1", "3__0@1", "3@_", "@1"] {
+            assert_eq!(
+                residual_to_ints(e).unwrap_err(),
+                SieveError::InvalidResidual(e.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_infix_to_postfix_separator_a() {
+        assert_eq!(
+            infix_to_postfix("1,000@3").unwrap_err(),
+            SieveError::DigitSeparator(',')
+        );
+        assert_eq!(
+            infix_to_postfix("3@1.5").unwrap_err(),
+            SieveError::DigitSeparator('.')
+        );
+        assert_eq!(
+            infix_to_postfix("1'000@3").unwrap_err(),
+            SieveError::DigitSeparator('\'')
+        );
+        assert_eq!(
+            infix_to_postfix("1 000@3").unwrap_err(),
+            SieveError::DigitSeparator(' ')
+        );
+        assert_eq!(
+            infix_to_postfix(".5").unwrap_err(),
+            SieveError::UnknownToken('.')
+        );
+        let px = infix_to_postfix(" 1_000 @ 3 | 4@1 ").unwrap();
+        assert_eq!(px.iter().collect::<Vec<_>>(), vec!["1_000@3", "4@1", "|"]);
+    }
+
+    #[test]
+    fn test_evaluate_literals_a() {
+        assert_eq!(evaluate_literals("(3*4)@(2+1)").unwrap(), "12@3");
+        assert_eq!(evaluate_literals("0x10@3|0XfF@0x1").unwrap(), "16@3|255@1");
+        assert_eq!(
+            evaluate_literals("!((2*3*5)@ (7-2*3) | (4@1))").unwrap(),
+            "!(30@ 1 | (4@1))"
+        );
+        assert_eq!(evaluate_literals("((1+1)*(0x2+1))@0").unwrap(), "6@0");
+        assert_eq!(evaluate_literals("3@0&(5@1)").unwrap(), "3@0&(5@1)");
+    }
+
+    #[test]
+    fn test_evaluate_literals_b() {
+        assert_eq!(
+            evaluate_literals("(2-3)@0").unwrap_err(),
+            SieveError::InvalidResidual("2-3".to_string())
+        );
+        assert_eq!(
+            evaluate_literals("(2*)@0").unwrap_err(),
+            SieveError::InvalidResidual("2*".to_string())
+        );
+        assert_eq!(
+            evaluate_literals("0x@0").unwrap_err(),
+            SieveError::InvalidResidual("0x".to_string())
+        );
+        assert_eq!(
+            evaluate_literals("(0xffffffffffffffff+1)@0").unwrap_err(),
+            SieveError::Overflow("0xffffffffffffffff+1".to_string())
+        );
+        assert_eq!(
+            evaluate_literals("0x1_0000_0000_0000_0000@0").unwrap_err(),
+            SieveError::Overflow("0x1_0000_0000_0000_0000".to_string())
+        );
+    }
+
     #[test]
     fn test_char_to_precedence_a() {
         assert_eq!(char_to_precedence('!'), 4);
-        assert_eq!(char_to_precedence('-'), 0);
+        assert_eq!(char_to_precedence('-'), 3);
+        assert_eq!(char_to_precedence('?'), 0);
         assert_eq!(char_to_precedence('&'), 3);
     }
 
@@ -186,4 +514,79 @@ mod tests {
         let e1 = "10@0 + 10@9";
         assert!(infix_to_postfix(e1).is_err());
     }
+
+    #[test]
+    fn test_infix_to_postfix_h() {
+        assert_eq!(infix_to_postfix(""), Err(SieveError::Empty));
+        assert_eq!(infix_to_postfix("  "), Err(SieveError::Empty));
+    }
+
+    #[test]
+    fn test_infix_to_postfix_i() {
+        assert_eq!(
+            infix_to_postfix("(3@0|4@0"),
+            Err(SieveError::UnbalancedParentheses)
+        );
+        assert_eq!(
+            infix_to_postfix("3@0|4@0)"),
+            Err(SieveError::UnbalancedParentheses)
+        );
+        assert_eq!(
+            infix_to_postfix("((3@0)|(4@0)))"),
+            Err(SieveError::UnbalancedParentheses)
+        );
+    }
+
+    #[test]
+    fn test_infix_to_postfix_j() {
+        assert_eq!(infix_to_postfix("3@0|"), Err(SieveError::MissingOperand));
+        assert_eq!(infix_to_postfix("&3@0"), Err(SieveError::MissingOperand));
+        assert_eq!(
+            infix_to_postfix("3@0|&4@0"),
+            Err(SieveError::MissingOperand)
+        );
+        assert_eq!(infix_to_postfix("!"), Err(SieveError::MissingOperand));
+        assert_eq!(infix_to_postfix("()"), Err(SieveError::MissingOperand));
+    }
+
+    #[test]
+    fn test_infix_to_postfix_k() {
+        assert_eq!(
+            infix_to_postfix("3@0!4@0"),
+            Err(SieveError::MissingOperator)
+        );
+        assert_eq!(
+            infix_to_postfix("(3@0)(4@0)"),
+            Err(SieveError::MissingOperator)
+        );
+        assert_eq!(
+            infix_to_postfix("(3@0)4@0"),
+            Err(SieveError::MissingOperator)
+        );
+    }
+
+    #[test]
+    fn test_infix_to_postfix_l() {
+        assert_eq!(
+            infix_to_postfix("3@0 % 4@0"),
+            Err(SieveError::UnknownToken('%'))
+        );
+    }
+
+    #[test]
+    fn test_infix_to_postfix_m() {
+        let e1 = "3@0 - 5@1 | 4@0 - 2@0 & 7@1";
+        let px1 = infix_to_postfix(e1).unwrap();
+        assert_eq!(
+            px1.iter().collect::<Vec<_>>(),
+            vec!["3@0", "5@1", "-", "4@0", "2@0", "-", "7@1", "&", "|"]
+        );
+    }
+
+    #[test]
+    fn test_strip_display_a() {
+        assert_eq!(strip_display("Sieve{3@0|4@1}"), "3@0|4@1");
+        assert_eq!(strip_display(" Sieve{3@0} "), "3@0");
+        assert_eq!(strip_display("3@0|4@1"), "3@0|4@1");
+    }
 }
@@ -0,0 +1,116 @@
+use std::ops::Range;
+
+use crate::Sieve;
+
+/// A sequence of Sieves, each in effect from its start position until the start of the next, as produced by `Sieve::append`. The first section has no start, and is in effect for all positions before the second.
+///
+/// ```
+/// let p = xensieve::Sieve::new("2@0").append(&xensieve::Sieve::new("5@3"), 7);
+/// assert_eq!(p.contains(6), true);
+/// assert_eq!(p.contains(7), true);
+/// assert_eq!(p.contains(8), false);
+/// assert_eq!(p.sections()[1].1.to_string(), "Sieve{5@2}");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Piecewise {
+    sections: Vec<(i128, Sieve)>,
+}
+
+impl Piecewise {
+    pub(crate) fn new(first: Sieve) -> Self {
+        Self {
+            sections: vec![(i128::MIN, first)],
+        }
+    }
+
+    /// Return the sections as pairs of start position and Sieve; the start of the first section is `i128::MIN`.
+    pub fn sections(&self) -> &[(i128, Sieve)] {
+        &self.sections
+    }
+
+    /// Return a Piecewise extended by `other` from position `at`, translated such that its first non-negative member lands exactly at `at`. If `other` has no members, it is not translated.
+    ///
+    /// # Panics
+    /// Panics if `at` is not greater than the start of the last section.
+    pub fn append(&self, other: &Sieve, at: i128) -> Self {
+        let last = self.sections[self.sections.len() - 1].0;
+        assert!(
+            at > last,
+            "position must be greater than the start of the last section"
+        );
+        let section = match other.first_in(0..i128::MAX) {
+            Some(first) => other.translate(at - first),
+            None => other.clone(),
+        };
+        let mut post = self.clone();
+        post.push(at, section);
+        post
+    }
+
+    /// Add a section from position `at`, without translation; `at` must be greater than the start of the last section.
+    pub(crate) fn push(&mut self, at: i128, sieve: Sieve) {
+        self.sections.push((at, sieve));
+    }
+
+    /// Return the index of the section in effect at the value.
+    fn section(&self, value: i128) -> usize {
+        self.sections.partition_point(|(start, _)| *start <= value) - 1
+    }
+
+    /// Return `true` if the value is a member of the section in effect at the value.
+    pub fn contains(&self, value: i128) -> bool {
+        self.sections[self.section(value)].1.contains(value)
+    }
+
+    /// Return the members within the range, taking each section's members from the part of the range where it is in effect.
+    pub fn scan(&self, range: Range<i128>) -> Vec<i128> {
+        let mut post = Vec::new();
+        for (i, (start, sieve)) in self.sections.iter().enumerate() {
+            let end = match self.sections.get(i + 1) {
+                Some((next, _)) => *next,
+                None => i128::MAX,
+            };
+            let lo = range.start.max(*start);
+            let hi = range.end.min(end);
+            if lo < hi {
+                post.extend(sieve.scan(lo..hi));
+            }
+        }
+        post
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_piecewise_append_a() {
+        let p = Sieve::new("1@0")
+            .append(&Sieve::new("4@3"), 5)
+            .append(&Sieve::new("3@1"), 12);
+        assert_eq!(p.sections().len(), 3);
+        assert_eq!(
+            p.scan(-2..20),
+            vec![-2, -1, 0, 1, 2, 3, 4, 5, 9, 12, 15, 18]
+        );
+        assert!(!p.contains(13));
+        assert!(p.contains(-100));
+    }
+
+    #[test]
+    fn test_piecewise_append_b() {
+        let p = Sieve::new("3@0").append(&Sieve::empty(), 4);
+        assert_eq!(p.scan(0..10), vec![0, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "position must be greater than the start of the last section")]
+    fn test_piecewise_append_c() {
+        Sieve::new("3@0")
+            .append(&Sieve::new("2@0"), 4)
+            .append(&Sieve::new("2@0"), 4);
+    }
+}
@@ -0,0 +1,214 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::ops::Range;
+
+use crate::SieveNode;
+
+/// The largest period for which a table of one period will be built.
+const TABLE_LIMIT: u64 = 1 << 24;
+
+/// An evaluation strategy for finding the members of a Sieve within a range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Strategy {
+    /// Evaluate the tree of Residuals for every value in the range.
+    TreeWalk,
+    /// Evaluate the tree once for every value in one period, then look up each value in the range.
+    PeriodTable,
+    /// Step through the arithmetic progression of each Residual of a union, merging the results; only available for Sieves that are unions of Residuals.
+    ProgressionMerge,
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Strategy::TreeWalk => "tree walk",
+            Strategy::PeriodTable => "period table",
+            Strategy::ProgressionMerge => "progression merge",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// The evaluation strategy selected for a range, as returned by `Sieve::plan`.
+///
+/// # Fields
+/// * `strategy` - The selected Strategy.
+/// * `cost` - The estimated number of operations of the selected Strategy.
+/// * `reason` - A description of the estimates that determined the selection.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plan {
+    pub strategy: Strategy,
+    pub cost: f64,
+    pub reason: String,
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Plan{{{}: {}}}", self.strategy, self.reason)
+    }
+}
+
+impl Plan {
+    /// Estimate the cost of each available strategy and select the least expensive.
+    pub(crate) fn new(node: &SieveNode, range: &Range<i128>) -> Self {
+        let count = if range.start < range.end {
+            range.end.abs_diff(range.start) as f64
+        } else {
+            0.0
+        };
+        let nodes = node.size() as f64;
+        let mut candidates: Vec<(Strategy, f64, String)> = vec![(
+            Strategy::TreeWalk,
+            count * nodes,
+            format!("{count} values by {nodes} nodes"),
+        )];
+        if let Some(period) = node.cycle().filter(|p| *p <= TABLE_LIMIT) {
+            candidates.push((
+                Strategy::PeriodTable,
+                period as f64 * nodes + count,
+                format!("{period} period values by {nodes} nodes, plus {count} lookups"),
+            ));
+        }
+        if let Some(residuals) = node.union_residuals() {
+            let k = residuals.len() as f64;
+            let members: f64 = residuals
+                .iter()
+                .filter(|r| r.modulus > 0)
+                .map(|r| count / r.modulus as f64 + 1.0)
+                .sum();
+            candidates.push((
+                Strategy::ProgressionMerge,
+                members * (k.log2() + 1.0) + k,
+                format!("{members:.0} progression steps over {k} residuals"),
+            ));
+        }
+        let (strategy, cost, reason) = candidates
+            .into_iter()
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .unwrap();
+        Self {
+            strategy,
+            cost,
+            reason,
+        }
+    }
+
+    /// Return the members of the node within the range using the selected strategy.
+    pub(crate) fn execute(&self, node: &SieveNode, range: Range<i128>) -> Vec<i128> {
+        match self.strategy {
+            Strategy::TreeWalk => range.filter(|v| node.contains(*v)).collect(),
+            Strategy::PeriodTable => {
+                let period = node.cycle().unwrap() as i128;
+                let table: Vec<bool> = (0..period).map(|v| node.contains(v)).collect();
+                range
+                    .filter(|v| table[v.rem_euclid(period) as usize])
+                    .collect()
+            }
+            Strategy::ProgressionMerge => {
+                let mut heap: BinaryHeap<Reverse<(i128, i128)>> = node
+                    .union_residuals()
+                    .unwrap()
+                    .iter()
+                    .filter(|r| r.modulus > 0)
+                    .filter_map(|r| {
+                        let m = r.modulus as i128;
+                        let first = r.next_member(range.start)?;
+                        Some(Reverse((first, m)))
+                    })
+                    .collect();
+                let mut post: Vec<i128> = Vec::new();
+                while let Some(Reverse((v, m))) = heap.pop() {
+                    if v >= range.end {
+                        break;
+                    }
+                    if post.last() != Some(&v) {
+                        post.push(v);
+                    }
+                    if let Some(next) = v.checked_add(m) {
+                        heap.push(Reverse((next, m)));
+                    }
+                }
+                post
+            }
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::Sieve;
+    use crate::Strategy;
+
+    #[test]
+    fn test_plan_a() {
+        let s = Sieve::new("3@0&!4@1");
+        assert_eq!(s.plan(0..10).strategy, Strategy::TreeWalk);
+        assert_eq!(s.plan(0..10_000).strategy, Strategy::PeriodTable);
+    }
+
+    #[test]
+    fn test_plan_b() {
+        let s = Sieve::new("1000@3|1001@7");
+        let p = s.plan(0..100_000);
+        assert_eq!(p.strategy, Strategy::ProgressionMerge);
+        assert!(p.to_string().starts_with("Plan{progression merge: "));
+    }
+
+    #[test]
+    fn test_plan_c() {
+        // the period exceeds the table limit
+        let s = Sieve::new("1000003@0&1000033@1&!7@0");
+        assert_eq!(s.plan(0..1_000_000_000).strategy, Strategy::TreeWalk);
+    }
+
+    #[test]
+    fn test_scan_a() {
+        let s = Sieve::new("3@0&!4@1");
+        let expected: Vec<i128> = s.iter_value(-50..10_000).collect();
+        assert_eq!(s.scan(-50..10_000), expected);
+    }
+
+    #[test]
+    fn test_scan_b() {
+        let s = Sieve::new("7001@3|5003@0|0@0|35@0");
+        let expected: Vec<i128> = s.iter_value(-100..100_000).collect();
+        assert_eq!(s.plan(-100..100_000).strategy, Strategy::ProgressionMerge);
+        assert_eq!(s.scan(-100..100_000), expected);
+    }
+
+    #[test]
+    fn test_scan_c() {
+        let s = Sieve::new("7@3|5@0");
+        assert_eq!(s.scan(10..10), vec![]);
+        let (start, end) = (10, 5);
+        assert_eq!(s.scan(start..end), vec![]);
+    }
+
+    #[test]
+    fn test_scan_d() {
+        // ranges at the limits of i128 do not overflow
+        let s = Sieve::new("7001@3|5003@0");
+        assert_eq!(
+            s.plan(i128::MIN..i128::MAX).strategy,
+            Strategy::ProgressionMerge
+        );
+        let end = i128::MAX - 100_000..i128::MAX;
+        assert_eq!(s.scan(end.clone()), s.iter_value(end).collect::<Vec<_>>());
+        let start = i128::MIN..i128::MIN + 100_000;
+        assert_eq!(
+            s.scan(start.clone()),
+            s.iter_value(start).collect::<Vec<_>>()
+        );
+        let s = Sieve::new("3@0&!4@1");
+        let end = i128::MAX - 10_000..i128::MAX;
+        assert_eq!(s.plan(end.clone()).strategy, Strategy::PeriodTable);
+        assert_eq!(s.scan(end.clone()), s.iter_value(end).collect::<Vec<_>>());
+    }
+}
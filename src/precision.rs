@@ -0,0 +1,91 @@
+use std::fmt;
+
+use crate::util;
+
+/// The width of the integers in which a computation is made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Precision {
+    I64,
+    I128,
+}
+
+impl fmt::Display for Precision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Precision::I64 => "i64",
+            Precision::I128 => "i128",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// A value with the Precision in which it was computed. A computation starts in `i64` and is promoted to `i128` only if an intermediate value would overflow.
+///
+/// # Fields
+/// * `value` - The result of the computation.
+/// * `precision` - The narrowest Precision in which the computation could be made.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Promoted<T> {
+    pub value: T,
+    pub precision: Precision,
+}
+
+impl<T> Promoted<T> {
+    /// Return `true` if the computation was promoted beyond `i64`.
+    pub fn is_promoted(&self) -> bool {
+        self.precision > Precision::I64
+    }
+}
+
+/// Return the least common multiple of the moduli, computed in `u128`, with the narrowest Precision in which the result and each modulus fit, or `None` if it overflows `i128`. Moduli of zero are ignored, and the least common multiple of no moduli is one.
+pub(crate) fn lcm(moduli: impl IntoIterator<Item = u64>) -> Option<Promoted<u128>> {
+    let narrow = i64::MAX as u128;
+    let wide = i128::MAX as u128;
+    let mut post = Promoted {
+        value: 1u128,
+        precision: Precision::I64,
+    };
+    for m in moduli.into_iter().filter(|m| *m > 0).map(u128::from) {
+        let g = util::gcd(post.value, m, 0).ok()?;
+        let value = (post.value / g).checked_mul(m).filter(|v| *v <= wide)?;
+        if m > narrow || value > narrow {
+            post.precision = Precision::I128;
+        }
+        post.value = value;
+    }
+    Some(post)
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lcm_a() {
+        assert_eq!(
+            lcm([4, 6, 0, 10]),
+            Some(Promoted {
+                value: 60,
+                precision: Precision::I64
+            })
+        );
+        assert_eq!(lcm([]).unwrap().value, 1);
+        let p = lcm([1 << 40, 3 << 30]).unwrap();
+        assert_eq!(p.value, 3 << 40);
+        assert!(!p.is_promoted());
+    }
+
+    #[test]
+    fn test_lcm_b() {
+        let p = lcm([(1 << 40) + 1, (1 << 40) - 1]).unwrap();
+        assert_eq!(p.value, (1 << 80) - 1);
+        assert_eq!(p.precision, Precision::I128);
+        assert_eq!(p.precision.to_string(), "i128");
+        assert_eq!(lcm([u64::MAX, u64::MAX - 1, u64::MAX - 2]), None);
+    }
+}
@@ -0,0 +1,237 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::Scale;
+use crate::Sieve;
+use crate::SieveError;
+
+const PREFIX: &str = "XS1:";
+
+/// The error returned when a preset string cannot be decoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PresetError {
+    /// The string does not begin with a supported version prefix, such as `XS1:`.
+    Version(String),
+    /// A field is malformed, duplicated, or out of range, or the required `sieve` field is missing.
+    Field(String),
+    /// The `sieve` field cannot be parsed.
+    Sieve(SieveError),
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetError::Version(s) => write!(f, "unsupported preset version: {s}"),
+            PresetError::Field(s) => write!(f, "invalid preset field: {s}"),
+            PresetError::Sieve(e) => write!(f, "invalid preset sieve: {e}"),
+        }
+    }
+}
+
+impl Error for PresetError {}
+
+//------------------------------------------------------------------------------
+
+/// A complete, persistable sieve setup: a Sieve with the options used to realize it. Presets are encoded as a compact, versioned string, such as `XS1:sieve=3@0|4@1;range=0..24;reference=60;edo=12;tempo=120;steps_per_beat=4`, of `key=value` fields separated by `;`. When decoding, fields other than `sieve` may be omitted, taking their default values, and unknown fields are ignored, such that presets written by other versions of this crate with the same prefix can be read.
+///
+/// # Fields
+/// * `sieve` - The Sieve.
+/// * `range` - The range of values to realize.
+/// * `reference` - The MIDI pitch of step zero, used to map steps to pitches; 60 by default.
+/// * `edo` - The number of equal divisions of the octave, used to map steps to pitches; 12 by default.
+/// * `tempo` - The tempo, in beats per minute; 120 by default.
+/// * `steps_per_beat` - The number of steps in one beat; 4 by default.
+///
+/// ```
+/// let p = xensieve::Preset::new(xensieve::Sieve::new("3@0|4@1"), 0..24);
+/// let s = p.encode();
+/// assert_eq!(s, "XS1:sieve=3@0|4@1;range=0..24;reference=60;edo=12;tempo=120;steps_per_beat=4");
+/// assert_eq!(xensieve::Preset::decode(&s).unwrap(), p);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Preset {
+    pub sieve: Sieve,
+    pub range: Range<i128>,
+    pub reference: f64,
+    pub edo: u32,
+    pub tempo: f64,
+    pub steps_per_beat: u64,
+}
+
+impl Preset {
+    /// Construct a Preset from a Sieve and a range, with default realization options.
+    pub fn new(sieve: Sieve, range: Range<i128>) -> Self {
+        Self {
+            sieve,
+            range,
+            reference: 60.0,
+            edo: 12,
+            tempo: 120.0,
+            steps_per_beat: 4,
+        }
+    }
+
+    /// Return the Scale that maps steps of the Sieve to pitches.
+    pub fn scale(&self) -> Scale {
+        Scale::new(self.sieve.clone(), self.reference, self.edo)
+    }
+
+    /// Return the preset string.
+    pub fn encode(&self) -> String {
+        format!(
+            "{PREFIX}sieve={};range={}..{};reference={};edo={};tempo={};steps_per_beat={}",
+            self.sieve.root,
+            self.range.start,
+            self.range.end,
+            self.reference,
+            self.edo,
+            self.tempo,
+            self.steps_per_beat,
+        )
+    }
+
+    /// Construct a Preset from a preset string.
+    /// ```
+    /// let p = xensieve::Preset::decode("XS1:sieve=5@2;tempo=90.5;swing=0.6").unwrap();
+    /// assert_eq!(p.sieve.to_string(), "Sieve{5@2}");
+    /// assert_eq!(p.tempo, 90.5);
+    /// assert_eq!(p.edo, 12);
+    /// ```
+    pub fn decode(value: &str) -> Result<Self, PresetError> {
+        let body = match value.trim().strip_prefix(PREFIX) {
+            Some(b) => b,
+            None => {
+                let version = value.trim().split(':').next().unwrap_or("");
+                return Err(PresetError::Version(version.to_string()));
+            }
+        };
+        let mut seen: Vec<&str> = Vec::new();
+        let mut sieve = None;
+        let mut preset = Self::new(Sieve::empty(), 0..0);
+        for field in body.split(';').filter(|f| !f.trim().is_empty()) {
+            let (key, v) = match field.split_once('=') {
+                Some((k, v)) => (k.trim(), v.trim()),
+                None => return Err(PresetError::Field(field.to_string())),
+            };
+            if seen.contains(&key) {
+                return Err(PresetError::Field(field.to_string()));
+            }
+            seen.push(key);
+            let invalid = || PresetError::Field(field.to_string());
+            match key {
+                "sieve" => sieve = Some(Sieve::try_new(v).map_err(PresetError::Sieve)?),
+                "range" => {
+                    let (start, end) = v.split_once("..").ok_or_else(invalid)?;
+                    let start = start.trim().parse().map_err(|_| invalid())?;
+                    let end = end.trim().parse().map_err(|_| invalid())?;
+                    preset.range = start..end;
+                }
+                "reference" => preset.reference = v.parse().map_err(|_| invalid())?,
+                "edo" => {
+                    preset.edo = v.parse().map_err(|_| invalid())?;
+                    if preset.edo == 0 {
+                        return Err(invalid());
+                    }
+                }
+                "tempo" => {
+                    preset.tempo = v.parse().map_err(|_| invalid())?;
+                    if !preset.tempo.is_finite() || preset.tempo <= 0.0 {
+                        return Err(invalid());
+                    }
+                }
+                "steps_per_beat" => {
+                    preset.steps_per_beat = v.parse().map_err(|_| invalid())?;
+                    if preset.steps_per_beat == 0 {
+                        return Err(invalid());
+                    }
+                }
+                // fields from other versions
+                _ => {}
+            }
+        }
+        preset.sieve = sieve.ok_or_else(|| PresetError::Field("sieve".to_string()))?;
+        Ok(preset)
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+impl FromStr for Preset {
+    type Err = PresetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode(s)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_encode_a() {
+        let mut p = Preset::new(Sieve::new("(5@0|4@2)&!(30@10)"), -12..36);
+        p.reference = 61.5;
+        p.edo = 19;
+        p.tempo = 72.25;
+        p.steps_per_beat = 3;
+        let s = p.encode();
+        assert_eq!(
+            s,
+            "XS1:sieve=(5@0|4@2)&!(30@10);range=-12..36;reference=61.5;edo=19;tempo=72.25;steps_per_beat=3"
+        );
+        assert_eq!(Preset::decode(&s).unwrap(), p);
+        assert_eq!(s.parse::<Preset>().unwrap().to_string(), s);
+    }
+
+    #[test]
+    fn test_preset_decode_a() {
+        assert_eq!(
+            Preset::decode("XS2:sieve=3@0").unwrap_err(),
+            PresetError::Version("XS2".to_string())
+        );
+        assert_eq!(
+            Preset::decode("sieve=3@0").unwrap_err(),
+            PresetError::Version("sieve=3@0".to_string())
+        );
+        assert_eq!(
+            Preset::decode("XS1:range=0..4").unwrap_err(),
+            PresetError::Field("sieve".to_string())
+        );
+        assert_eq!(
+            Preset::decode("XS1:sieve=3@0|").unwrap_err(),
+            PresetError::Sieve(SieveError::MissingOperand)
+        );
+    }
+
+    #[test]
+    fn test_preset_decode_b() {
+        for (s, field) in [
+            ("XS1:sieve=3@0;edo=0", "edo=0"),
+            ("XS1:sieve=3@0;range=4", "range=4"),
+            ("XS1:sieve=3@0;tempo=-1", "tempo=-1"),
+            ("XS1:sieve=3@0;tempo", "tempo"),
+            ("XS1:sieve=3@0;sieve=4@0", "sieve=4@0"),
+        ] {
+            assert_eq!(
+                Preset::decode(s).unwrap_err(),
+                PresetError::Field(field.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_preset_decode_c() {
+        let p = Preset::decode(" XS1: sieve = 12@0|12@7 ; range = 0 .. 24 ; ").unwrap();
+        assert_eq!(p.range, 0..24);
+        assert_eq!(p.scale().midi(7), Some(67.0));
+    }
+}
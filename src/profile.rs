@@ -0,0 +1,146 @@
+use crate::SieveNode;
+
+/// Evaluation statistics for one node of a Sieve, as collected by `Sieve::profile`.
+///
+/// # Fields
+/// * `expression` - The string representation of the node.
+/// * `depth` - The depth of the node, where the root is zero.
+/// * `evaluations` - The number of times the node was evaluated.
+/// * `hits` - The number of evaluations that returned `true`.
+/// * `short_circuits` - The number of evaluations of an intersection, union, or difference that returned without evaluating the right operand.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeProfile {
+    pub expression: String,
+    pub depth: usize,
+    pub evaluations: u64,
+    pub hits: u64,
+    pub short_circuits: u64,
+}
+
+/// Append a NodeProfile for each node in pre-order, and record the size of each subtree; returns the size of this subtree.
+fn init(
+    node: &SieveNode,
+    depth: usize,
+    profiles: &mut Vec<NodeProfile>,
+    sizes: &mut Vec<usize>,
+) -> usize {
+    let index = profiles.len();
+    profiles.push(NodeProfile {
+        expression: node.to_string(),
+        depth,
+        evaluations: 0,
+        hits: 0,
+        short_circuits: 0,
+    });
+    sizes.push(1);
+    let size = match node {
+        SieveNode::Unit(_) => 1,
+        SieveNode::Intersection(lhs, rhs)
+        | SieveNode::Union(lhs, rhs)
+        | SieveNode::SymmetricDifference(lhs, rhs)
+        | SieveNode::Difference(lhs, rhs) => {
+            1 + init(lhs, depth + 1, profiles, sizes) + init(rhs, depth + 1, profiles, sizes)
+        }
+        SieveNode::Inversion(part) => 1 + init(part, depth + 1, profiles, sizes),
+    };
+    sizes[index] = size;
+    size
+}
+
+/// Evaluate the node at `index` (in pre-order) for the value, recording statistics.
+fn evaluate(
+    node: &SieveNode,
+    value: i128,
+    index: usize,
+    sizes: &[usize],
+    profiles: &mut [NodeProfile],
+) -> bool {
+    let lhs_index = index + 1;
+    let post = match node {
+        SieveNode::Unit(residual) => residual.contains(value),
+        SieveNode::Intersection(lhs, rhs) => {
+            if evaluate(lhs, value, lhs_index, sizes, profiles) {
+                evaluate(rhs, value, lhs_index + sizes[lhs_index], sizes, profiles)
+            } else {
+                profiles[index].short_circuits += 1;
+                false
+            }
+        }
+        SieveNode::Union(lhs, rhs) => {
+            if evaluate(lhs, value, lhs_index, sizes, profiles) {
+                profiles[index].short_circuits += 1;
+                true
+            } else {
+                evaluate(rhs, value, lhs_index + sizes[lhs_index], sizes, profiles)
+            }
+        }
+        SieveNode::SymmetricDifference(lhs, rhs) => {
+            let a = evaluate(lhs, value, lhs_index, sizes, profiles);
+            a ^ evaluate(rhs, value, lhs_index + sizes[lhs_index], sizes, profiles)
+        }
+        SieveNode::Difference(lhs, rhs) => {
+            if evaluate(lhs, value, lhs_index, sizes, profiles) {
+                !evaluate(rhs, value, lhs_index + sizes[lhs_index], sizes, profiles)
+            } else {
+                profiles[index].short_circuits += 1;
+                false
+            }
+        }
+        SieveNode::Inversion(part) => !evaluate(part, value, lhs_index, sizes, profiles),
+    };
+    profiles[index].evaluations += 1;
+    if post {
+        profiles[index].hits += 1;
+    }
+    post
+}
+
+/// Evaluate the node for each value, returning a NodeProfile for each node in pre-order.
+pub(crate) fn profile(node: &SieveNode, iterator: impl Iterator<Item = i128>) -> Vec<NodeProfile> {
+    let mut profiles: Vec<NodeProfile> = Vec::new();
+    let mut sizes: Vec<usize> = Vec::new();
+    init(node, 0, &mut profiles, &mut sizes);
+    for value in iterator {
+        evaluate(node, value, 0, &sizes, &mut profiles);
+    }
+    profiles
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::Sieve;
+
+    #[test]
+    fn test_profile_a() {
+        let s = Sieve::new("3@0");
+        let p = s.profile(0..9);
+        assert_eq!(p.len(), 1);
+        assert_eq!(p[0].evaluations, 9);
+        assert_eq!(p[0].hits, 3);
+        assert_eq!(p[0].short_circuits, 0);
+    }
+
+    #[test]
+    fn test_profile_b() {
+        let s = Sieve::new("(2@0|3@0)&!5@0");
+        let p = s.profile(0..10);
+        let expr: Vec<_> = p.iter().map(|n| n.expression.as_str()).collect();
+        assert_eq!(
+            expr,
+            vec!["(2@0|3@0)&!(5@0)", "2@0|3@0", "2@0", "3@0", "!(5@0)", "5@0"]
+        );
+        let depth: Vec<_> = p.iter().map(|n| n.depth).collect();
+        assert_eq!(depth, vec![0, 1, 2, 2, 1, 2]);
+        // the union short-circuits on 0, 2, 4, 6, 8
+        assert_eq!(p[1].evaluations, 10);
+        assert_eq!(p[1].short_circuits, 5);
+        assert_eq!(p[3].evaluations, 5);
+        // the intersection short-circuits when the union is false: 1, 5, 7
+        assert_eq!(p[0].short_circuits, 3);
+        assert_eq!(p[4].evaluations, 7);
+        assert_eq!(p[0].hits, 6);
+    }
+}
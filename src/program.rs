@@ -0,0 +1,218 @@
+use crate::Residual;
+use crate::SieveNode;
+
+/// The greatest stack depth of a Program, the number of bits of its stack.
+const DEPTH_LIMIT: usize = 128;
+
+/// The number of distinct Residuals whose results are remembered within one evaluation, the number of bits of its result mask.
+const RESULT_LIMIT: usize = 128;
+
+/// An instruction of a Program: a Residual test, by index into the interned Residuals of the Program, pushing its result, or an operator, popping its operands and pushing its result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Instruction {
+    Unit(u32),
+    Intersection,
+    Union,
+    SymmetricDifference,
+    Difference,
+    Inversion,
+}
+
+/// A SieveNode flattened into postfix instructions, evaluated by a stack machine whose stack is the bits of one integer. Evaluation reads one contiguous vector rather than following pointers through the tree, and does not allocate. Residuals are interned: each distinct Residual is stored once, however often the expression repeats it, identical classes are compared by index, and each is tested at most once per evaluation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Program {
+    residuals: Vec<Residual>,
+    instructions: Vec<Instruction>,
+    depth: usize,
+}
+
+impl Program {
+    /// Return the Program of the node, or `None` if its stack would be deeper than `DEPTH_LIMIT`.
+    pub(crate) fn new(node: &SieveNode) -> Option<Self> {
+        let mut program = Self {
+            residuals: Vec::new(),
+            instructions: Vec::new(),
+            depth: 0,
+        };
+        program.depth = program.push(node, 0)?;
+        Some(program)
+    }
+
+    /// Append the instructions of the node, with `depth` values already on the stack, returning the greatest depth reached.
+    fn push(&mut self, node: &SieveNode, depth: usize) -> Option<usize> {
+        let (operands, instruction) = match node {
+            SieveNode::Unit(r) => {
+                let i = self.intern(*r);
+                self.instructions.push(Instruction::Unit(i));
+                return Some(depth + 1).filter(|d| *d <= DEPTH_LIMIT);
+            }
+            SieveNode::Intersection(lhs, rhs) => ((lhs, Some(rhs)), Instruction::Intersection),
+            SieveNode::Union(lhs, rhs) => ((lhs, Some(rhs)), Instruction::Union),
+            SieveNode::SymmetricDifference(lhs, rhs) => {
+                ((lhs, Some(rhs)), Instruction::SymmetricDifference)
+            }
+            SieveNode::Difference(lhs, rhs) => ((lhs, Some(rhs)), Instruction::Difference),
+            SieveNode::Inversion(part) => ((part, None), Instruction::Inversion),
+        };
+        let mut max = self.push(operands.0, depth)?;
+        if let Some(rhs) = operands.1 {
+            max = max.max(self.push(rhs, depth + 1)?);
+        }
+        self.instructions.push(instruction);
+        Some(max)
+    }
+
+    /// Return the index of the Residual, adding it if it is not yet interned. As only the results of the first `RESULT_LIMIT` Residuals are cached, only those are searched, such that interning many Residuals does not take quadratic time; a Residual beyond them may be added more than once.
+    fn intern(&mut self, residual: Residual) -> u32 {
+        match self
+            .residuals
+            .iter()
+            .take(RESULT_LIMIT)
+            .position(|r| *r == residual)
+        {
+            Some(i) => i as u32,
+            None => {
+                self.residuals.push(residual);
+                (self.residuals.len() - 1) as u32
+            }
+        }
+    }
+
+    /// Return the Program of `lhs` and `rhs` combined by the binary operator `instruction`, reusing the instructions of `lhs`, or `None` if its stack would be deeper than `DEPTH_LIMIT`.
+    pub(crate) fn join(mut lhs: Self, rhs: &Self, instruction: Instruction) -> Option<Self> {
+        lhs.depth = lhs.depth.max(rhs.depth + 1);
+        if lhs.depth > DEPTH_LIMIT {
+            return None;
+        }
+        let map: Vec<u32> = rhs.residuals.iter().map(|r| lhs.intern(*r)).collect();
+        lhs.instructions
+            .extend(rhs.instructions.iter().map(|i| match i {
+                Instruction::Unit(j) => Instruction::Unit(map[*j as usize]),
+                _ => *i,
+            }));
+        lhs.instructions.push(instruction);
+        Some(lhs)
+    }
+
+    /// Return the Program of the inversion of `part`, reusing its instructions.
+    pub(crate) fn invert(mut part: Self) -> Self {
+        part.instructions.push(Instruction::Inversion);
+        part
+    }
+
+    /// Return `true` if the value is contained.
+    pub(crate) fn contains(&self, value: i128) -> bool {
+        let mut stack: u128 = 0;
+        let (mut known, mut results): (u128, u128) = (0, 0);
+        for instruction in self.instructions.iter() {
+            let (a, b) = match instruction {
+                Instruction::Unit(i) => {
+                    let i = *i as usize;
+                    let result = if i >= RESULT_LIMIT {
+                        self.residuals[i].contains(value)
+                    } else if known >> i & 1 == 1 {
+                        results >> i & 1 == 1
+                    } else {
+                        let result = self.residuals[i].contains(value);
+                        known |= 1 << i;
+                        results |= (result as u128) << i;
+                        result
+                    };
+                    stack = (stack << 1) | result as u128;
+                    continue;
+                }
+                Instruction::Inversion => {
+                    stack ^= 1;
+                    continue;
+                }
+                _ => {
+                    let b = stack & 1 == 1;
+                    stack >>= 1;
+                    (stack & 1 == 1, b)
+                }
+            };
+            let result = match instruction {
+                Instruction::Intersection => a && b,
+                Instruction::Union => a || b,
+                Instruction::SymmetricDifference => a ^ b,
+                _ => a && !b,
+            };
+            stack = (stack & !1) | result as u128;
+        }
+        stack & 1 == 1
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sieve;
+
+    #[test]
+    fn test_program_new_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^7@3-0@0");
+        let p = Program::new(&s.root).unwrap();
+        assert_eq!(p.instructions.len(), 10);
+        assert_eq!(p.depth, 3);
+        for v in -300..300 {
+            assert_eq!(p.contains(v), s.root.contains(v), "{v}");
+        }
+    }
+
+    #[test]
+    fn test_program_new_b() {
+        // a right-nested chain requires one stack value per level
+        let mut node = SieveNode::Unit(Residual::new(2, 0));
+        for i in 0..200 {
+            node = SieveNode::Union(
+                Box::new(SieveNode::Unit(Residual::new(i + 3, 1))),
+                Box::new(node),
+            );
+            assert_eq!(Program::new(&node).is_some(), i < 127, "{i}");
+        }
+    }
+
+    #[test]
+    fn test_program_join_a() {
+        let a = Sieve::new("3@0|4@1");
+        let b = Sieve::new("5@2&!(2@0)");
+        let pa = Program::new(&a.root).unwrap();
+        let pb = Program::new(&b.root).unwrap();
+        let joined = Program::join(pa.clone(), &pb, Instruction::Difference).unwrap();
+        assert_eq!(joined, Program::new(&(&a - &b).root).unwrap());
+        let inverted = Program::invert(pa);
+        assert_eq!(inverted, Program::new(&(!&a).root).unwrap());
+    }
+
+    #[test]
+    fn test_program_intern_a() {
+        let s = Sieve::new("(3@0|4@1)&!(3@0^5@2)|4@5-5@7");
+        let p = Program::new(&s.root).unwrap();
+        assert_eq!(p.residuals.len(), 3);
+        assert_eq!(p.instructions.len(), 12);
+        for v in -60..60 {
+            assert_eq!(p.contains(v), s.root.contains(v), "{v}");
+        }
+        let joined = Program::join(
+            Program::new(&Sieve::new("7@0|3@0").root).unwrap(),
+            &p,
+            Instruction::Union,
+        )
+        .unwrap();
+        assert_eq!(joined.residuals.len(), 4);
+    }
+
+    #[test]
+    fn test_program_intern_b() {
+        // beyond the result mask, Residuals are tested at each use, and are not searched when interned
+        let s = Sieve::from_residuals((1..200).map(|m| (m, m - 1)));
+        let s = &s | &s;
+        let p = Program::new(&s.root).unwrap();
+        assert_eq!(p.residuals.len(), 199 + 71);
+        for v in -300..300 {
+            assert_eq!(p.contains(v), s.root.contains(v), "{v}");
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::Residual;
+
+/// A cursor over the arithmetic progressions of a union of Residuals, holding the next member of each Residual at or above the last value tested. Testing ascending values compares each with the least of these members, stepping a progression only when a value passes it, such that values between members are tested without evaluating any Residual.
+#[derive(Clone, Debug)]
+pub(crate) struct Progressions {
+    residuals: Vec<Residual>,
+    heap: BinaryHeap<Reverse<(i128, usize)>>,
+    last: Option<i128>,
+}
+
+impl Progressions {
+    pub(crate) fn new(residuals: Vec<Residual>) -> Self {
+        let residuals: Vec<Residual> = residuals.into_iter().filter(|r| r.modulus > 0).collect();
+        Self {
+            heap: BinaryHeap::with_capacity(residuals.len()),
+            residuals,
+            last: None,
+        }
+    }
+
+    /// Set the next member of every Residual to the smallest at or above `value`.
+    fn seek(&mut self, value: i128) {
+        self.heap.clear();
+        for (i, r) in self.residuals.iter().enumerate() {
+            if let Some(v) = r.next_member(value) {
+                self.heap.push(Reverse((v, i)));
+            }
+        }
+    }
+
+    /// Return `true` if the value is a member. Values are best tested in ascending order; a value below the one before it restarts every progression.
+    pub(crate) fn contains(&mut self, value: i128) -> bool {
+        match self.last {
+            Some(last) if value >= last => {}
+            _ => self.seek(value),
+        }
+        self.last = Some(value);
+        while let Some(Reverse((v, i))) = self.heap.peek().copied() {
+            if v >= value {
+                return v == value;
+            }
+            self.heap.pop();
+            if let Some(n) = self.residuals[i].next_member(value) {
+                self.heap.push(Reverse((n, i)));
+            }
+        }
+        false
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// An iterator of the members of a union of Residuals in ascending order, from a start to an exclusive end, merging the arithmetic progression of each Residual. The first member of each Residual is found directly; each later member is one modulus on, such that values between members are never visited.
+#[derive(Clone, Debug)]
+pub(crate) struct Merge {
+    heap: BinaryHeap<Reverse<(i128, u64)>>,
+    end: i128,
+    last: Option<i128>,
+}
+
+impl Merge {
+    pub(crate) fn new(residuals: &[Residual], start: i128, end: i128) -> Self {
+        let heap = residuals
+            .iter()
+            .filter(|r| r.modulus > 0)
+            .filter_map(|r| r.next_member(start).map(|v| Reverse((v, r.modulus))))
+            .collect();
+        Self {
+            heap,
+            end,
+            last: None,
+        }
+    }
+}
+
+impl Iterator for Merge {
+    type Item = i128;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Reverse((v, m)) = self.heap.pop()?;
+            if v >= self.end {
+                self.heap.clear();
+                return None;
+            }
+            if let Some(n) = v.checked_add(m as i128) {
+                self.heap.push(Reverse((n, m)));
+            }
+            if self.last != Some(v) {
+                self.last = Some(v);
+                return Some(v);
+            }
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sieve;
+
+    #[test]
+    fn test_progressions_a() {
+        let s = Sieve::new("10@3|4@1|7@0|0@5");
+        let mut p = Progressions::new(s.root.union_residuals().unwrap());
+        assert_eq!(p.residuals.len(), 3);
+        for v in (-100..100)
+            .chain(50..60)
+            .chain((-30..30).rev())
+            .chain([7, 7, 7])
+        {
+            assert_eq!(p.contains(v), s.contains(v), "{v}");
+        }
+    }
+
+    #[test]
+    fn test_progressions_b() {
+        let mut p = Progressions::new(vec![Residual::new(3, 0)]);
+        assert!(p.contains(i128::MAX - 1));
+        assert!(!p.contains(i128::MAX));
+        assert!(p.contains(-3));
+        let mut p = Progressions::new(vec![]);
+        assert!(!p.contains(0));
+    }
+
+    #[test]
+    fn test_merge_a() {
+        let s = Sieve::new("10@3|4@1|6@1|0@5");
+        let residuals = s.root.union_residuals().unwrap();
+        for (start, end) in [(-50, 50), (7, 8), (13, 13), (20, 10)] {
+            assert_eq!(
+                Merge::new(&residuals, start, end).collect::<Vec<_>>(),
+                s.scan(start..end),
+                "{start}..{end}"
+            );
+        }
+        let mut m = Merge::new(&[Residual::new(3, 0)], i128::MAX - 4, i128::MAX);
+        assert_eq!(m.next(), Some(i128::MAX - 4));
+        assert_eq!(m.next(), Some(i128::MAX - 1));
+        assert_eq!(m.next(), None);
+    }
+}
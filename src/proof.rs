@@ -0,0 +1,223 @@
+use crate::SieveNode;
+
+/// The division of a value by the modulus of a Residual, as evidence of whether the value is in the Residual's class.
+///
+/// # Fields
+/// * `modulus` - The modulus of the Residual; a modulus of zero is the empty class, which holds for no value.
+/// * `shift` - The shift of the Residual.
+/// * `quotient` - The quotient of the value divided by the modulus, rounded toward negative infinity; zero for a modulus of zero.
+/// * `remainder` - The non-negative remainder of the value divided by the modulus; zero for a modulus of zero.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Congruence {
+    pub modulus: u64,
+    pub shift: u64,
+    pub quotient: i128,
+    pub remainder: u64,
+}
+
+impl Congruence {
+    /// Return `true` if the value is in the class: the remainder equals the shift.
+    pub fn holds(&self) -> bool {
+        self.modulus != 0 && self.remainder == self.shift
+    }
+
+    /// Return `true` if the quotient and remainder are a correct division of the value.
+    fn is_valid(&self, value: i128) -> bool {
+        if self.modulus == 0 {
+            return self.quotient == 0 && self.remainder == 0;
+        }
+        self.remainder < self.modulus
+            && self
+                .quotient
+                .checked_mul(self.modulus as i128)
+                .and_then(|p| p.checked_add(self.remainder as i128))
+                == Some(value)
+    }
+}
+
+/// The evidence for the membership of a value, mirroring the structure of a Sieve, with a Congruence for each Residual.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Evidence {
+    Congruence(Congruence),
+    Intersection(Box<Evidence>, Box<Evidence>),
+    Union(Box<Evidence>, Box<Evidence>),
+    SymmetricDifference(Box<Evidence>, Box<Evidence>),
+    Difference(Box<Evidence>, Box<Evidence>),
+    Inversion(Box<Evidence>),
+}
+
+impl Evidence {
+    fn new(node: &SieveNode, value: i128) -> Self {
+        let pair = |lhs: &SieveNode, rhs: &SieveNode| {
+            (
+                Box::new(Evidence::new(lhs, value)),
+                Box::new(Evidence::new(rhs, value)),
+            )
+        };
+        match node {
+            SieveNode::Unit(r) => {
+                let (quotient, remainder) = if r.modulus() == 0 {
+                    (0, 0)
+                } else {
+                    let m = r.modulus() as i128;
+                    (value.div_euclid(m), value.rem_euclid(m) as u64)
+                };
+                Evidence::Congruence(Congruence {
+                    modulus: r.modulus(),
+                    shift: r.shift(),
+                    quotient,
+                    remainder,
+                })
+            }
+            SieveNode::Intersection(lhs, rhs) => {
+                let (a, b) = pair(lhs, rhs);
+                Evidence::Intersection(a, b)
+            }
+            SieveNode::Union(lhs, rhs) => {
+                let (a, b) = pair(lhs, rhs);
+                Evidence::Union(a, b)
+            }
+            SieveNode::SymmetricDifference(lhs, rhs) => {
+                let (a, b) = pair(lhs, rhs);
+                Evidence::SymmetricDifference(a, b)
+            }
+            SieveNode::Difference(lhs, rhs) => {
+                let (a, b) = pair(lhs, rhs);
+                Evidence::Difference(a, b)
+            }
+            SieveNode::Inversion(part) => Evidence::Inversion(Box::new(Evidence::new(part, value))),
+        }
+    }
+
+    /// Return the membership established by this evidence, or `None` if a Congruence is not a correct division of the value.
+    fn establish(&self, value: i128) -> Option<bool> {
+        Some(match self {
+            Evidence::Congruence(c) => {
+                if !c.is_valid(value) {
+                    return None;
+                }
+                c.holds()
+            }
+            Evidence::Intersection(a, b) => a.establish(value)? & b.establish(value)?,
+            Evidence::Union(a, b) => a.establish(value)? | b.establish(value)?,
+            Evidence::SymmetricDifference(a, b) => a.establish(value)? ^ b.establish(value)?,
+            Evidence::Difference(a, b) => a.establish(value)? & !b.establish(value)?,
+            Evidence::Inversion(a) => !a.establish(value)?,
+        })
+    }
+
+    fn collect<'a>(&'a self, post: &mut Vec<&'a Congruence>) {
+        match self {
+            Evidence::Congruence(c) => post.push(c),
+            Evidence::Intersection(a, b)
+            | Evidence::Union(a, b)
+            | Evidence::SymmetricDifference(a, b)
+            | Evidence::Difference(a, b) => {
+                a.collect(post);
+                b.collect(post);
+            }
+            Evidence::Inversion(a) => a.collect(post),
+        }
+    }
+}
+
+/// A proof of the membership, or non-membership, of a value in a Sieve, as produced by `Sieve::prove`. The proof can be checked with `verify` by arithmetic alone, without the Sieve.
+///
+/// # Fields
+/// * `value` - The value.
+/// * `member` - The claimed membership of the value.
+/// * `evidence` - The Congruences of the value for each Residual, in the structure of the Sieve.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MembershipProof {
+    pub value: i128,
+    pub member: bool,
+    pub evidence: Evidence,
+}
+
+impl MembershipProof {
+    pub(crate) fn new(node: &SieveNode, value: i128) -> Self {
+        Self {
+            value,
+            member: node.contains(value),
+            evidence: Evidence::new(node, value),
+        }
+    }
+
+    /// Return the Congruences, in the order of the Residuals of the Sieve.
+    pub fn congruences(&self) -> Vec<&Congruence> {
+        let mut post = Vec::new();
+        self.evidence.collect(&mut post);
+        post
+    }
+}
+
+/// Return `true` if the proof is correct: each Congruence is a correct division of the value, and the Congruences, combined by the structure of the evidence, establish the claimed membership.
+///
+/// ```
+/// let s = xensieve::Sieve::new("3@0&!(4@1)");
+/// let mut p = s.prove(9);
+/// assert_eq!(p.member, false);
+/// assert!(xensieve::verify(&p));
+/// p.member = true;
+/// assert!(!xensieve::verify(&p));
+/// ```
+pub fn verify(proof: &MembershipProof) -> bool {
+    proof.evidence.establish(proof.value) == Some(proof.member)
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sieve;
+
+    #[test]
+    fn test_congruence_a() {
+        let c = Congruence {
+            modulus: 5,
+            shift: 3,
+            quotient: -2,
+            remainder: 3,
+        };
+        assert!(c.holds());
+        assert!(c.is_valid(-7));
+        assert!(!c.is_valid(-8));
+        let c = Congruence {
+            modulus: 0,
+            shift: 0,
+            quotient: 0,
+            remainder: 0,
+        };
+        assert!(!c.holds());
+        assert!(c.is_valid(12));
+    }
+
+    #[test]
+    fn test_membership_proof_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^0@0-7@1");
+        for v in -40..40 {
+            let p = s.prove(v);
+            assert_eq!(p.member, s.contains(v));
+            assert!(verify(&p), "{v}");
+            assert_eq!(p.congruences().len(), 5);
+        }
+    }
+
+    #[test]
+    fn test_membership_proof_b() {
+        let mut p = Sieve::new("3@1|5@0").prove(11);
+        assert!(!p.member);
+        p.member = true;
+        if let Evidence::Union(a, _) = &mut p.evidence {
+            if let Evidence::Congruence(c) = a.as_mut() {
+                // a false division claiming 11 is 3 * 3 + 1
+                c.quotient = 3;
+                c.remainder = 1;
+            }
+        }
+        assert!(!verify(&p));
+    }
+}
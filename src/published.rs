@@ -0,0 +1,47 @@
+use crate::Sieve;
+
+/// Sieves published by Xenakis, available with the `presets` feature. Each is given as notated in the cited source; sieves of other works, such as Psappha (1975) and Jonchaies (1977), are not included until their notation can be taken from such a source.
+impl Sieve {
+    /// Construct the sieve of Nomos Alpha (1966), for solo cello, as given by Xenakis in *Formalized Music* (rev. ed., Pendragon, 1992), in the chapter "Towards a Metamusic". The period is 143.
+    /// ```
+    /// let s = xensieve::Sieve::nomos_alpha();
+    /// assert_eq!(s.period(), Some(143));
+    /// assert_eq!(s.iter_value(0..20).collect::<Vec<_>>(), vec![0, 1, 2, 6, 9, 13, 14, 19]);
+    /// ```
+    pub fn nomos_alpha() -> Self {
+        Self::new("!(13@3|13@5|13@7|13@9)&11@2|!(11@4|11@8)&13@9|13@0|13@1|13@6")
+    }
+
+    /// Construct the sieve, of period 40, given by Xenakis as an example of the decomposition of a scale into Residual classes in "Sieves" (*Perspectives of New Music* 28(1), 1990), as notated in Ariza, "The Xenakis Sieve as Object" (*Computer Music Journal* 29(2), 2005).
+    /// ```
+    /// let s = xensieve::Sieve::xenakis_1990();
+    /// assert_eq!(s.period(), Some(40));
+    /// ```
+    pub fn xenakis_1990() -> Self {
+        Self::new(
+            "(8@0|8@1|8@7)&(5@1|5@3)|(8@0|8@1|8@2)&5@0|8@3&(5@0|5@1|5@2|5@3|5@4)|8@4&(5@0|5@1|5@2|5@3|5@4)|(8@5|8@6)&(5@2|5@3|5@4)|8@6&5@1|8@7&5@3",
+        )
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nomos_alpha_a() {
+        let s = Sieve::nomos_alpha();
+        assert_eq!(s.period(), Some(143));
+        // the union of 13@0, 13@1, and 13@6 is contained
+        assert!(Sieve::new("13@0|13@1|13@6").is_subset_of(&s));
+    }
+
+    #[test]
+    fn test_xenakis_1990_a() {
+        let s = Sieve::xenakis_1990();
+        assert_eq!(s.period(), Some(40));
+        assert!(Sieve::new("40@3|40@11|40@19|40@27|40@35").is_subset_of(&s));
+    }
+}
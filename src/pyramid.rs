@@ -0,0 +1,134 @@
+use std::ops::Range;
+
+/// A precomputed, multi-resolution view of a Sieve over a finite range. Level zero holds the state of each value; each higher level `k` holds, for each bucket of `2^k` values, whether any value in that bucket is a member. Queries for any member in a range are answered from the coarsest buckets that fit, without scanning values.
+///
+/// ```
+/// let s = xensieve::Sieve::new("12@0|12@7");
+/// let p = s.pyramid(0..48);
+/// assert_eq!(p.any_in(1..7), false);
+/// assert_eq!(p.any_in(1..8), true);
+/// assert_eq!(p.level(3).unwrap(), &[true, true, true, true, true, true]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Pyramid {
+    start: i128,
+    levels: Vec<Vec<bool>>,
+}
+
+impl Pyramid {
+    /// Construct a Pyramid from the members of a range; members outside the range are ignored.
+    pub(crate) fn new(range: Range<i128>, members: impl IntoIterator<Item = i128>) -> Self {
+        let len = if range.end > range.start {
+            (range.end - range.start) as usize
+        } else {
+            0
+        };
+        let mut base = vec![false; len];
+        for m in members.into_iter().filter(|m| range.contains(m)) {
+            base[(m - range.start) as usize] = true;
+        }
+        let mut levels = vec![base];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| pair.iter().any(|s| *s))
+                .collect();
+            levels.push(next);
+        }
+        Self {
+            start: range.start,
+            levels,
+        }
+    }
+
+    /// Return the range of values covered by this Pyramid.
+    pub fn range(&self) -> Range<i128> {
+        self.start..self.start + self.levels[0].len() as i128
+    }
+
+    /// Return the number of levels, including level zero.
+    pub fn levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Return the bucket states at level `k`, where each bucket spans `2^k` values from the start of the range; the last bucket may be partial. Returns `None` if `k` exceeds the top level.
+    pub fn level(&self, k: usize) -> Option<&[bool]> {
+        self.levels.get(k).map(|l| l.as_slice())
+    }
+
+    /// Return `true` if any member is in the range. Only values within the range of this Pyramid are considered.
+    pub fn any_in(&self, range: Range<i128>) -> bool {
+        let covered = self.range();
+        let start = range.start.max(covered.start);
+        let end = range.end.min(covered.end);
+        if start >= end {
+            return false;
+        }
+        let mut lo = (start - self.start) as usize;
+        let mut hi = (end - self.start) as usize;
+        for level in self.levels.iter() {
+            if lo >= hi {
+                break;
+            }
+            if lo % 2 == 1 {
+                if level[lo] {
+                    return true;
+                }
+                lo += 1;
+            }
+            if hi % 2 == 1 {
+                hi -= 1;
+                if level[hi] {
+                    return true;
+                }
+            }
+            lo /= 2;
+            hi /= 2;
+        }
+        false
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pyramid_new_a() {
+        let p = Pyramid::new(0..5, vec![1, 4]);
+        assert_eq!(p.levels(), 4);
+        assert_eq!(p.level(0).unwrap(), &[false, true, false, false, true]);
+        assert_eq!(p.level(1).unwrap(), &[true, false, true]);
+        assert_eq!(p.level(2).unwrap(), &[true, true]);
+        assert_eq!(p.level(3).unwrap(), &[true]);
+        assert_eq!(p.level(4), None);
+    }
+
+    #[test]
+    fn test_pyramid_new_b() {
+        let p = Pyramid::new(3..3, vec![]);
+        assert_eq!(p.levels(), 1);
+        assert_eq!(p.range(), 3..3);
+        assert!(!p.any_in(0..10));
+    }
+
+    #[test]
+    fn test_pyramid_any_in_a() {
+        let members = vec![-7, 3, 20];
+        let p = Pyramid::new(-10..30, members.clone());
+        for a in -12..32 {
+            for b in a..32 {
+                let expected = members.iter().any(|m| *m >= a && *m < b);
+                assert_eq!(p.any_in(a..b), expected, "{a}..{b}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_pyramid_any_in_b() {
+        let p = Pyramid::new(0..10, vec![12]);
+        assert!(!p.any_in(0..20));
+    }
+}
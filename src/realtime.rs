@@ -0,0 +1,132 @@
+//! Functions that are safe to call on a real-time thread, such as an audio callback. After setup (compiling a Sieve with `Sieve::compile` and allocating any buffers), no function of this module allocates, frees, locks, or performs I/O, and each runs in time bounded by its arguments: `contains` in constant time, `fill` and `fill_values` in time proportional to the buffer, and `next_member` in time proportional to one period of the mask. These guarantees are tested with an allocation-counting allocator.
+//!
+//! ```
+//! use xensieve::realtime;
+//! // setup, off the real-time thread
+//! let c = xensieve::Sieve::new("4@0|3@1").compile();
+//! let mut states = [false; 8];
+//! // on the real-time thread
+//! realtime::fill(&c, 0, &mut states);
+//! assert_eq!(states, [true, true, false, false, true, false, false, true]);
+//! assert_eq!(realtime::next_member(&c, 5), Some(7));
+//! ```
+
+use crate::CompiledSieve;
+
+/// Return `true` if the value is a member, in constant time.
+pub fn contains(compiled: &CompiledSieve, value: i128) -> bool {
+    compiled.contains(value)
+}
+
+/// Write the membership of each of the consecutive values starting at `start` into `post`.
+pub fn fill(compiled: &CompiledSieve, start: i128, post: &mut [bool]) {
+    for (i, p) in post.iter_mut().enumerate() {
+        *p = compiled.contains(start + i as i128);
+    }
+}
+
+/// Write successive members, from the smallest member greater than or equal to `start`, into `post`, returning the number written; fewer than the length of `post` are written only if the sieve has no further members.
+pub fn fill_values(compiled: &CompiledSieve, start: i128, post: &mut [i128]) -> usize {
+    let mut pos = start;
+    for (i, p) in post.iter_mut().enumerate() {
+        match compiled.next_member(pos) {
+            Some(v) => {
+                *p = v;
+                match v.checked_add(1) {
+                    Some(n) => pos = n,
+                    None => return i + 1,
+                }
+            }
+            None => return i,
+        }
+    }
+    post.len()
+}
+
+/// Return the smallest member greater than or equal to `start`, or `None` if there is none.
+pub fn next_member(compiled: &CompiledSieve, start: i128) -> Option<i128> {
+    compiled.next_member(start)
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sieve;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    /// An allocator that counts the allocations of each thread, such that tests run in parallel do not interfere.
+    struct Counting;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.with(|a| a.set(a.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            ALLOCATIONS.with(|a| a.set(a.get() + 1));
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            ALLOCATIONS.with(|a| a.set(a.get() + 1));
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    /// Return the number of allocations, frees, and reallocations of this thread during `f`.
+    fn allocations(f: impl FnOnce()) -> usize {
+        let before = ALLOCATIONS.with(|a| a.get());
+        f();
+        ALLOCATIONS.with(|a| a.get()) - before
+    }
+
+    #[test]
+    fn test_allocations_a() {
+        assert_eq!(allocations(|| drop(vec![1u8])), 2);
+    }
+
+    #[test]
+    fn test_realtime_a() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)^7@3");
+        let c = s.compile();
+        let mut states = [false; 256];
+        let mut values = [0i128; 64];
+        let mut found = (false, None, 0);
+        let n = allocations(|| {
+            found.0 = contains(&c, 14);
+            found.1 = next_member(&c, 19);
+            fill(&c, -128, &mut states);
+            found.2 = fill_values(&c, -20, &mut values);
+        });
+        assert_eq!(n, 0);
+        assert_eq!(found.0, s.contains(14));
+        assert_eq!(found.1, s.next_above(18));
+        assert_eq!(states.to_vec(), s.iter_state(-128..128).collect::<Vec<_>>());
+        assert_eq!(found.2, 64);
+        assert_eq!(
+            values.to_vec(),
+            s.iter_value(-20..1000).take(64).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_realtime_b() {
+        let c = Sieve::new("6@0&4@1").compile();
+        let mut values = [0i128; 4];
+        assert_eq!(fill_values(&c, 0, &mut values), 0);
+        let c = Sieve::new("1@0").compile();
+        assert_eq!(fill_values(&c, i128::MAX - 1, &mut values), 2);
+        assert_eq!(values[..2], [i128::MAX - 1, i128::MAX]);
+    }
+}
@@ -0,0 +1,135 @@
+use std::ops::Range;
+use std::thread;
+
+use crate::Sieve;
+use crate::SieveNode;
+
+const CELL: usize = 10;
+const TEXT: usize = 16;
+
+/// Return the text with the characters special to XML escaped.
+fn escape(text: &str) -> String {
+    let mut post = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => post.push_str("&amp;"),
+            '<' => post.push_str("&lt;"),
+            '>' => post.push_str("&gt;"),
+            '"' => post.push_str("&quot;"),
+            _ => post.push(c),
+        }
+    }
+    post
+}
+
+/// Return an SVG drawing of the formula of the node above a strip of one cell per value of the range, filled for members.
+pub(crate) fn svg(node: &SieveNode, range: Range<i128>) -> String {
+    let count = (range.end - range.start).max(0) as usize;
+    let width = (count * CELL).max(CELL);
+    let height = TEXT + CELL + 2;
+    let mut post = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">"
+    );
+    post.push_str(&format!(
+        "<text x=\"0\" y=\"12\" font-family=\"monospace\" font-size=\"12\">{}</text>",
+        escape(&node.to_string())
+    ));
+    for (i, v) in range.enumerate() {
+        let fill = if node.contains(v) { "black" } else { "white" };
+        post.push_str(&format!(
+            "<rect x=\"{}\" y=\"{TEXT}\" width=\"{CELL}\" height=\"{CELL}\" fill=\"{fill}\" stroke=\"gray\"/>",
+            i * CELL
+        ));
+    }
+    post.push_str("</svg>");
+    post
+}
+
+/// Return an HTML fragment of the formula of the node, as code, followed by the SVG drawing of the range.
+pub(crate) fn html(node: &SieveNode, range: Range<i128>) -> String {
+    format!(
+        "<div><code>{}</code><br/>{}</div>",
+        escape(&node.to_string()),
+        svg(node, range)
+    )
+}
+
+/// Return the members of each sieve within the range, in the order of the sieves, evaluating the sieves concurrently on up to `threads` threads, such as for exporting all layers of a piece at once. Sieves are distributed to threads in turn, and each thread evaluates its sieves with `Sieve::scan`.
+///
+/// ```
+/// let sieves = vec![xensieve::Sieve::new("3@0"), xensieve::Sieve::new("4@1"), xensieve::Sieve::new("5@2")];
+/// let post = xensieve::render_segments(&sieves, 0..10, 2);
+/// assert_eq!(post, vec![vec![0, 3, 6, 9], vec![1, 5, 9], vec![2, 7]]);
+/// ```
+///
+/// # Panics
+/// Panics if `threads` is zero.
+pub fn render_segments(sieves: &[Sieve], range: Range<i128>, threads: usize) -> Vec<Vec<i128>> {
+    assert!(threads > 0, "threads must be greater than zero");
+    let threads = threads.min(sieves.len());
+    let handles: Vec<_> = (0..threads)
+        .map(|t| {
+            let work: Vec<(usize, Sieve)> = sieves
+                .iter()
+                .enumerate()
+                .skip(t)
+                .step_by(threads)
+                .map(|(i, s)| (i, s.clone()))
+                .collect();
+            let range = range.clone();
+            thread::spawn(move || {
+                work.into_iter()
+                    .map(|(i, s)| (i, s.scan(range.clone())))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let mut post = vec![Vec::new(); sieves.len()];
+    for handle in handles {
+        for (i, values) in handle.join().expect("render thread panicked") {
+            post[i] = values;
+        }
+    }
+    post
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_a() {
+        assert_eq!(escape("3@0&!(4@1)"), "3@0&amp;!(4@1)");
+        assert_eq!(escape("<\">"), "&lt;&quot;&gt;");
+    }
+
+    #[test]
+    fn test_svg_a() {
+        let s = Sieve::new("3@0&!(6@3)");
+        let post = s.to_svg(0..7);
+        assert!(post.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"70\""));
+        assert!(post.contains(">3@0&amp;!(6@3)</text>"));
+        assert_eq!(post.matches("fill=\"black\"").count(), 2);
+        assert_eq!(post.matches("fill=\"white\"").count(), 5);
+        assert!(post.ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_svg_b() {
+        let post = Sieve::new("3@0").to_svg(5..5);
+        assert!(post.contains("width=\"10\""));
+        assert_eq!(post.matches("<rect").count(), 0);
+    }
+
+    #[test]
+    fn test_render_segments_a() {
+        let sieves: Vec<Sieve> = (1..20).map(|m| Sieve::unit(m, m / 2)).collect();
+        let expected: Vec<Vec<i128>> = sieves.iter().map(|s| s.scan(-50..50)).collect();
+        for threads in [1, 3, 19, 40] {
+            assert_eq!(render_segments(&sieves, -50..50, threads), expected);
+        }
+        assert_eq!(render_segments(&[], 0..10, 4), Vec::<Vec<i128>>::new());
+    }
+}
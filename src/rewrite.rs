@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use crate::Sieve;
+
+/// A set of rewriting rules, as in an L-system, over an alphabet of intervals. Each generation replaces every interval that has a rule with the rule's intervals, in parallel; intervals without a rule are kept.
+///
+/// ```
+/// let r = xensieve::Rewriting::new(vec![(3, vec![1, 2]), (2, vec![3])]);
+/// assert_eq!(r.rewrite(&[3], 3), vec![1, 1, 2]);
+/// let s = xensieve::Sieve::new("3@0");
+/// assert_eq!(r.realize(&s, 0..7, 1), vec![0, 1, 3, 4, 6]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rewriting {
+    rules: BTreeMap<i128, Vec<i128>>,
+}
+
+impl Rewriting {
+    /// Construct a Rewriting from pairs of an interval and its replacement. Where an interval is given more than once, the last replacement is used.
+    pub fn new(rules: impl IntoIterator<Item = (i128, Vec<i128>)>) -> Self {
+        Self {
+            rules: rules.into_iter().collect(),
+        }
+    }
+
+    /// Return the intervals after the provided number of generations of rewriting.
+    pub fn rewrite(&self, intervals: &[i128], generations: usize) -> Vec<i128> {
+        let mut post = intervals.to_vec();
+        for _ in 0..generations {
+            post = post
+                .iter()
+                .flat_map(|i| match self.rules.get(i) {
+                    Some(r) => r.clone(),
+                    None => vec![*i],
+                })
+                .collect();
+        }
+        post
+    }
+
+    /// Rewrite the intervals between the members of the Sieve within the range, and realize the result as points starting from the first member. If there are no members, there are no points.
+    pub fn realize(&self, sieve: &Sieve, range: Range<i128>, generations: usize) -> Vec<i128> {
+        let members = sieve.scan(range);
+        let start = match members.first() {
+            Some(v) => *v,
+            None => return Vec::new(),
+        };
+        let intervals: Vec<i128> = members.windows(2).map(|w| w[1] - w[0]).collect();
+        let mut points = vec![start];
+        for i in self.rewrite(&intervals, generations) {
+            points.push(points[points.len() - 1] + i);
+        }
+        points
+    }
+
+    /// Realize the rewritten points, as `realize`, and analyze them into a Sieve with a period equal to their span, such that the rewritten passage repeats. If the span is not positive, the empty Sieve is returned.
+    /// ```
+    /// let r = xensieve::Rewriting::new(vec![(4, vec![1, 3])]);
+    /// let s = xensieve::Sieve::new("4@0");
+    /// assert_eq!(r.realize_sieve(&s, 0..9, 1).to_string(), "Sieve{8@0|8@1|8@4|8@5}");
+    /// ```
+    pub fn realize_sieve(&self, sieve: &Sieve, range: Range<i128>, generations: usize) -> Sieve {
+        let points = self.realize(sieve, range, generations);
+        match (points.first(), points.last()) {
+            (Some(first), Some(last)) if last > first => {
+                Sieve::from_points(points.iter().copied(), (last - first) as u64)
+            }
+            _ => Sieve::empty(),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewriting_rewrite_a() {
+        // Fibonacci word over intervals 2 and 1
+        let r = Rewriting::new(vec![(2, vec![2, 1]), (1, vec![2])]);
+        assert_eq!(r.rewrite(&[2], 0), vec![2]);
+        assert_eq!(r.rewrite(&[2], 4), vec![2, 1, 2, 2, 1, 2, 1, 2]);
+        assert_eq!(r.rewrite(&[5, 1], 1), vec![5, 2]);
+    }
+
+    #[test]
+    fn test_rewriting_new_a() {
+        let r = Rewriting::new(vec![(2, vec![1]), (2, vec![3, 3])]);
+        assert_eq!(r.rewrite(&[2], 1), vec![3, 3]);
+    }
+
+    #[test]
+    fn test_rewriting_realize_a() {
+        let r = Rewriting::new(vec![(2, vec![2, 1])]);
+        let s = Sieve::new("2@1");
+        assert_eq!(r.realize(&s, 0..6, 2), vec![1, 3, 4, 5, 7, 8, 9]);
+        assert_eq!(r.realize(&Sieve::empty(), 0..6, 2), vec![]);
+        assert_eq!(r.realize(&Sieve::new("10@3"), 0..6, 2), vec![3]);
+        assert_eq!(
+            r.realize_sieve(&Sieve::new("10@3"), 0..6, 2),
+            Sieve::empty()
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use std::fmt;
+
+use crate::util;
+
+/// A notated note value: a power-of-two fraction of a whole note, with dots, optionally within a tuplet.
+///
+/// # Fields
+/// * `base` - The denominator of the undotted value: 1 for a whole note, 4 for a quarter note, 8 for an eighth note.
+/// * `dots` - The number of augmentation dots.
+/// * `tuplet` - The tuplet ratio as (actual, normal), such as (3, 2) for a triplet, or `None`.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NoteValue {
+    pub base: u64,
+    pub dots: u8,
+    pub tuplet: Option<(u64, u64)>,
+}
+
+/// A duration realized from an interval, as produced by `Sieve::iter_duration`.
+///
+/// # Fields
+/// * `interval` - The interval, in steps, between successive members.
+/// * `values` - The note values, tied in order, that notate the interval; empty for an interval of zero.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Duration {
+    pub interval: i128,
+    pub values: Vec<NoteValue>,
+}
+
+/// Split `numerator / denominator` of a whole note, where the denominator is a power of two, into tied dotted values, largest first.
+fn dotted_values(
+    mut numerator: u128,
+    denominator: u128,
+    tuplet: Option<(u64, u64)>,
+) -> Vec<NoteValue> {
+    let mut values = Vec::new();
+    while numerator > 0 {
+        // the largest undotted value that fits, no larger than a whole note
+        let mut units = denominator;
+        while units > numerator {
+            units /= 2;
+        }
+        let mut used = units;
+        let mut dot = units / 2;
+        let mut dots = 0;
+        while dot > 0 && used + dot <= numerator {
+            used += dot;
+            dot /= 2;
+            dots += 1;
+        }
+        values.push(NoteValue {
+            base: (denominator / units) as u64,
+            dots,
+            tuplet,
+        });
+        numerator -= used;
+    }
+    values
+}
+
+/// Return the Euclidean rhythm of `pulses` onsets distributed as evenly as possible over `steps`, by the algorithm of Bjorklund, with the first step an onset where there are any.
+pub(crate) fn bjorklund(pulses: u64, steps: u64) -> Vec<bool> {
+    if pulses == 0 {
+        return vec![false; steps as usize];
+    }
+    let mut a: Vec<Vec<bool>> = vec![vec![true]; pulses as usize];
+    let mut b: Vec<Vec<bool>> = vec![vec![false]; (steps - pulses) as usize];
+    while b.len() > 1 {
+        let n = a.len().min(b.len());
+        let remainder = if a.len() > n {
+            a.split_off(n)
+        } else {
+            b.split_off(n)
+        };
+        for (x, y) in a.iter_mut().zip(b) {
+            x.extend(y);
+        }
+        b = remainder;
+    }
+    a.into_iter().chain(b).flatten().collect()
+}
+
+impl Duration {
+    /// Notate an interval of steps, where `steps_per_beat` steps make a quarter-note beat. Durations that are not a power-of-two division of the beat are placed in a tuplet of their odd factor against the next lower power of two (3:2, 5:4, 7:4); durations that cannot be notated by a single value are tied.
+    pub(crate) fn new(interval: i128, steps_per_beat: u64) -> Self {
+        assert!(
+            steps_per_beat > 0,
+            "steps_per_beat must be greater than zero"
+        );
+        // duration in whole notes: interval / (4 * steps_per_beat)
+        let mut numerator = interval.unsigned_abs();
+        let mut denominator = 4 * steps_per_beat as u128;
+        let g = util::gcd(numerator, denominator, 0).unwrap_or(1);
+        numerator /= g;
+        denominator /= g;
+
+        let mut odd = denominator;
+        while odd % 2 == 0 {
+            odd /= 2;
+        }
+        let values = if odd == 1 {
+            dotted_values(numerator, denominator, None)
+        } else {
+            let mut normal = 1;
+            while normal * 2 < odd {
+                normal *= 2;
+            }
+            // scale the duration by actual / normal, removing the odd factor
+            dotted_values(
+                numerator,
+                denominator / odd * normal,
+                Some((odd as u64, normal as u64)),
+            )
+        };
+        Self { interval, values }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// A node of a rhythm tree, in the proportional notation of OpenMusic and bach: a duration relative to its siblings, either a leaf or a group subdivided by its children.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RhythmNode {
+    /// An onset of a relative duration, notated `n`.
+    Note(u64),
+    /// A rest of a relative duration, notated `-n`.
+    Rest(u64),
+    /// A continuation, tied from the previous note, of a relative duration, notated `n.0`.
+    Tie(u64),
+    /// A relative duration subdivided proportionally by its children, notated `(n (children))`.
+    Group(u64, Vec<RhythmNode>),
+}
+
+impl fmt::Display for RhythmNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RhythmNode::Note(n) => write!(f, "{n}"),
+            RhythmNode::Rest(n) => write!(f, "-{n}"),
+            RhythmNode::Tie(n) => write!(f, "{n}.0"),
+            RhythmNode::Group(n, children) => {
+                let children: Vec<String> = children.iter().map(|c| c.to_string()).collect();
+                write!(f, "({n} ({}))", children.join(" "))
+            }
+        }
+    }
+}
+
+/// A rhythm tree of measures of one time signature, each measure a sequence of beats, as produced by `Sieve::to_rhythm_tree`. Displayed in the notation of OpenMusic and bach, with the duration of the whole left to be computed (`?`).
+///
+/// # Fields
+/// * `signature` - The time signature of every measure, as (beats, beat unit).
+/// * `measures` - The beats of each measure.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RhythmTree {
+    pub signature: (u64, u64),
+    pub measures: Vec<Vec<RhythmNode>>,
+}
+
+impl fmt::Display for RhythmTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (n, d) = self.signature;
+        let measures: Vec<String> = self
+            .measures
+            .iter()
+            .map(|beats| {
+                let beats: Vec<String> = beats.iter().map(|b| b.to_string()).collect();
+                format!("(({n} {d}) ({}))", beats.join(" "))
+            })
+            .collect();
+        write!(f, "(? ({}))", measures.join(" "))
+    }
+}
+
+/// Return the node of one beat from the onsets of its steps, where `sounding` is `true` if a note begun before the beat continues into it. The beat is divided at each onset, and the durations are reduced by their greatest common divisor; a beat of one duration is a leaf.
+pub(crate) fn beat(onsets: &[bool], sounding: bool) -> RhythmNode {
+    let mut starts: Vec<usize> = (0..onsets.len()).filter(|i| onsets[*i]).collect();
+    let lead = starts.first() != Some(&0);
+    if lead {
+        starts.insert(0, 0);
+    }
+    let lengths: Vec<u64> = starts
+        .iter()
+        .zip(starts.iter().skip(1).chain(std::iter::once(&onsets.len())))
+        .map(|(a, b)| (b - a) as u64)
+        .collect();
+    let g = lengths.iter().fold(0, |g, l| {
+        if g == 0 {
+            *l
+        } else {
+            util::gcd(g, *l, 0).unwrap()
+        }
+    });
+    let mut nodes: Vec<RhythmNode> = lengths
+        .iter()
+        .enumerate()
+        .map(|(i, l)| match (i, lead, sounding) {
+            (0, true, true) => RhythmNode::Tie(l / g),
+            (0, true, false) => RhythmNode::Rest(l / g),
+            _ => RhythmNode::Note(l / g),
+        })
+        .collect();
+    match nodes.len() {
+        1 => match nodes.pop().unwrap() {
+            RhythmNode::Note(_) => RhythmNode::Note(1),
+            RhythmNode::Rest(_) => RhythmNode::Rest(1),
+            _ => RhythmNode::Tie(1),
+        },
+        _ => RhythmNode::Group(1, nodes),
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nv(base: u64, dots: u8) -> NoteValue {
+        NoteValue {
+            base,
+            dots,
+            tuplet: None,
+        }
+    }
+
+    fn pattern(pulses: u64, steps: u64) -> String {
+        bjorklund(pulses, steps)
+            .iter()
+            .map(|p| if *p { 'x' } else { '.' })
+            .collect()
+    }
+
+    #[test]
+    fn test_bjorklund_a() {
+        assert_eq!(pattern(3, 8), "x..x..x.");
+        assert_eq!(pattern(5, 8), "x.xx.xx.");
+        assert_eq!(pattern(2, 5), "x.x..");
+        assert_eq!(pattern(4, 12), "x..x..x..x..");
+        assert_eq!(pattern(7, 16), "x..x.x.x..x.x.x.");
+    }
+
+    #[test]
+    fn test_bjorklund_b() {
+        assert_eq!(pattern(0, 4), "....");
+        assert_eq!(pattern(4, 4), "xxxx");
+        assert_eq!(pattern(1, 3), "x..");
+        assert_eq!(pattern(0, 0), "");
+    }
+
+    #[test]
+    fn test_duration_new_a() {
+        assert_eq!(Duration::new(4, 4).values, vec![nv(4, 0)]);
+        assert_eq!(Duration::new(2, 4).values, vec![nv(8, 0)]);
+        assert_eq!(Duration::new(6, 4).values, vec![nv(4, 1)]);
+        assert_eq!(Duration::new(7, 4).values, vec![nv(4, 2)]);
+        assert_eq!(Duration::new(16, 4).values, vec![nv(1, 0)]);
+        assert_eq!(Duration::new(0, 4).values, vec![]);
+    }
+
+    #[test]
+    fn test_duration_new_b() {
+        assert_eq!(Duration::new(5, 4).values, vec![nv(4, 0), nv(16, 0)]);
+        assert_eq!(Duration::new(20, 4).values, vec![nv(1, 0), nv(4, 0)]);
+        assert_eq!(Duration::new(-6, 4).values, vec![nv(4, 1)]);
+    }
+
+    #[test]
+    fn test_duration_new_c() {
+        let triplet = Some((3, 2));
+        assert_eq!(
+            Duration::new(1, 3).values,
+            vec![NoteValue {
+                base: 8,
+                dots: 0,
+                tuplet: triplet
+            }]
+        );
+        assert_eq!(
+            Duration::new(2, 3).values,
+            vec![NoteValue {
+                base: 4,
+                dots: 0,
+                tuplet: triplet
+            }]
+        );
+        assert_eq!(
+            Duration::new(1, 5).values,
+            vec![NoteValue {
+                base: 16,
+                dots: 0,
+                tuplet: Some((5, 4))
+            }]
+        );
+    }
+
+    fn onsets(pattern: &str) -> Vec<bool> {
+        pattern.chars().map(|c| c == 'x').collect()
+    }
+
+    #[test]
+    fn test_beat_a() {
+        assert_eq!(beat(&onsets("x..."), false).to_string(), "1");
+        assert_eq!(beat(&onsets("...."), false).to_string(), "-1");
+        assert_eq!(beat(&onsets("...."), true).to_string(), "1.0");
+        assert_eq!(beat(&onsets("x.x."), false).to_string(), "(1 (1 1))");
+        assert_eq!(beat(&onsets("..x."), false).to_string(), "(1 (-1 1))");
+        assert_eq!(beat(&onsets(".x.."), true).to_string(), "(1 (1.0 3))");
+        assert_eq!(beat(&onsets("x.xxx."), false).to_string(), "(1 (2 1 1 2))");
+    }
+}
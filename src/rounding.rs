@@ -0,0 +1,56 @@
+/// A policy for rounding a non-integer result to an integer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round toward negative infinity.
+    Floor,
+    /// Round toward positive infinity.
+    Ceiling,
+    /// Round to the nearest integer, with halves rounded up.
+    Nearest,
+}
+
+impl Rounding {
+    /// Return `numerator / denominator` rounded by this policy, and `true` if no rounding was necessary.
+    pub(crate) fn divide(&self, numerator: u128, denominator: u128) -> (u128, bool) {
+        let q = numerator / denominator;
+        let r = numerator % denominator;
+        if r == 0 {
+            return (q, true);
+        }
+        let rounded = match self {
+            Rounding::Floor => q,
+            Rounding::Ceiling => q + 1,
+            Rounding::Nearest => {
+                if r * 2 >= denominator {
+                    q + 1
+                } else {
+                    q
+                }
+            }
+        };
+        (rounded, false)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rounding_divide_a() {
+        assert_eq!(Rounding::Floor.divide(9, 3), (3, true));
+        assert_eq!(Rounding::Ceiling.divide(9, 3), (3, true));
+        assert_eq!(Rounding::Nearest.divide(9, 3), (3, true));
+    }
+
+    #[test]
+    fn test_rounding_divide_b() {
+        assert_eq!(Rounding::Floor.divide(7, 2), (3, false));
+        assert_eq!(Rounding::Ceiling.divide(7, 2), (4, false));
+        assert_eq!(Rounding::Nearest.divide(7, 2), (4, false));
+        assert_eq!(Rounding::Nearest.divide(4, 3), (1, false));
+        assert_eq!(Rounding::Nearest.divide(5, 3), (2, false));
+    }
+}
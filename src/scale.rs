@@ -0,0 +1,472 @@
+use crate::util;
+use crate::Sieve;
+
+const NAMES_SHARP: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+const NAMES_FLAT: [&str; 12] = [
+    "C", "Db", "D", "Eb", "E", "F", "Gb", "G", "Ab", "A", "Bb", "B",
+];
+
+/// The intervals, in semitones, of the major scale.
+pub(crate) const MAJOR: [u64; 7] = [2, 2, 1, 2, 2, 2, 1];
+/// The intervals, in semitones, of the natural minor scale.
+pub(crate) const NATURAL_MINOR: [u64; 7] = [2, 1, 2, 2, 1, 2, 2];
+/// The intervals, in semitones, of the harmonic minor scale.
+pub(crate) const HARMONIC_MINOR: [u64; 7] = [2, 1, 2, 2, 1, 3, 1];
+/// The intervals, in semitones, of the ascending melodic minor scale.
+pub(crate) const MELODIC_MINOR: [u64; 7] = [2, 1, 2, 2, 2, 2, 1];
+/// The intervals, in semitones, of Messiaen's seven modes of limited transposition, in their first transposition.
+pub(crate) const MESSIAEN_MODES: [&[u64]; 7] = [
+    &[2, 2, 2, 2, 2, 2],
+    &[1, 2, 1, 2, 1, 2, 1, 2],
+    &[2, 1, 1, 2, 1, 1, 2, 1, 1],
+    &[1, 1, 3, 1, 1, 1, 3, 1],
+    &[1, 4, 1, 1, 4, 1],
+    &[2, 2, 1, 1, 2, 2, 1, 1],
+    &[1, 1, 1, 2, 1, 1, 1, 1, 2, 1],
+];
+
+/// The accidental used when spelling pitches that do not fall on a natural note name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Spelling {
+    Sharp,
+    Flat,
+}
+
+//------------------------------------------------------------------------------
+
+/// A pitch scale built from a Sieve of steps, a reference MIDI pitch that corresponds to step zero, and the number of equal divisions of the octave (EDO).
+///
+/// ```
+/// use xensieve::{Scale, Sieve, Spelling};
+/// let s = Scale::new(Sieve::new("12@0|12@4|12@7"), 60.0, 12);
+/// assert_eq!(s.note_name(4).unwrap(), "E4");
+/// assert_eq!(s.midi(7).unwrap(), 67.0);
+/// assert_eq!(s.note_name(5), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Scale {
+    sieve: Sieve,
+    reference: f64,
+    edo: u32,
+    spelling: Spelling,
+}
+
+impl Scale {
+    /// Construct a Scale from a Sieve, the MIDI pitch of step zero, and the EDO. Pitches are spelled with sharps by default.
+    ///
+    /// # Panics
+    /// Panics if `edo` is zero.
+    pub fn new(sieve: Sieve, reference: f64, edo: u32) -> Self {
+        assert!(edo > 0, "EDO must be greater than zero");
+        Self {
+            sieve,
+            reference,
+            edo,
+            spelling: Spelling::Sharp,
+        }
+    }
+
+    /// Return this Scale with the provided Spelling.
+    pub fn with_spelling(mut self, spelling: Spelling) -> Self {
+        self.spelling = spelling;
+        self
+    }
+
+    /// Return `true` if the step is contained within the Scale's Sieve.
+    pub fn contains(&self, value: i128) -> bool {
+        self.sieve.contains(value)
+    }
+
+    /// Return the, possibly fractional, MIDI pitch of a step, or `None` if the step is not in the Scale.
+    pub fn midi(&self, value: i128) -> Option<f64> {
+        if !self.sieve.contains(value) {
+            return None;
+        }
+        Some(self.reference + value as f64 * 12.0 / self.edo as f64)
+    }
+
+    /// Return the frequency in Hz (with A4 at 440 Hz) of a step, or `None` if the step is not in the Scale.
+    ///
+    /// ```
+    /// let s = xensieve::Scale::new(xensieve::Sieve::new("1@0"), 69.0, 24);
+    /// assert_eq!(s.frequency(24).unwrap(), 880.0);
+    /// ```
+    pub fn frequency(&self, value: i128) -> Option<f64> {
+        self.midi(value)
+            .map(|m| 440.0 * 2f64.powf((m - 69.0) / 12.0))
+    }
+
+    /// Return the note name, with octave, of a step, or `None` if the step is not in the Scale. Pitches that deviate from 12-EDO are given as the nearest note name followed by a signed deviation in cents.
+    ///
+    /// ```
+    /// use xensieve::{Scale, Sieve, Spelling};
+    /// let s = Scale::new(Sieve::new("1@0"), 60.0, 24).with_spelling(Spelling::Flat);
+    /// assert_eq!(s.note_name(2).unwrap(), "Db4");
+    /// assert_eq!(s.note_name(3).unwrap(), "Db4+50");
+    /// ```
+    pub fn note_name(&self, value: i128) -> Option<String> {
+        let midi = self.midi(value)?;
+        // ties resolve to the lower note name, so quarter tones are spelled upward
+        let nearest = (midi - 0.5).ceil();
+        let cents = ((midi - nearest) * 100.0).round() as i64;
+        let pitch = nearest as i64;
+        let names = match self.spelling {
+            Spelling::Sharp => &NAMES_SHARP,
+            Spelling::Flat => &NAMES_FLAT,
+        };
+        let name = names[pitch.rem_euclid(12) as usize];
+        let octave = pitch.div_euclid(12) - 1;
+        if cents == 0 {
+            Some(format!("{name}{octave}"))
+        } else {
+            Some(format!("{name}{octave}{cents:+}"))
+        }
+    }
+
+    /// For the iterator provided as an input, iterate a MidiNote for each value contained within the Scale. Notes are rotated over the MPE member channels (zero-based channels 1 through 15) so that each note can carry its own pitch bend; pitches that fall outside 12-EDO are given a bend computed for a synth configured with a bend range of `bend_range` semitones. Values whose nearest MIDI note is outside of 0 to 127 are skipped.
+    ///
+    /// ```
+    /// use xensieve::{Scale, Sieve};
+    /// let s = Scale::new(Sieve::new("1@0"), 60.0, 24);
+    /// let notes: Vec<_> = s.iter_midi_note(0..3, 2.0).collect();
+    /// assert_eq!((notes[0].channel, notes[0].note, notes[0].bend), (1, 60, None));
+    /// assert_eq!((notes[1].channel, notes[1].note, notes[1].bend), (2, 60, Some(10240)));
+    /// assert_eq!((notes[2].channel, notes[2].note, notes[2].bend), (3, 61, None));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `bend_range` is not finite and greater than zero.
+    pub fn iter_midi_note<'a>(
+        &'a self,
+        iterator: impl Iterator<Item = i128> + 'a,
+        bend_range: f64,
+    ) -> impl Iterator<Item = MidiNote> + 'a {
+        assert!(
+            bend_range.is_finite() && bend_range > 0.0,
+            "bend range must be finite and greater than zero"
+        );
+        let mut count: u8 = 0;
+        iterator.filter_map(move |v| {
+            let midi = self.midi(v)?;
+            let nearest = (midi - 0.5).ceil();
+            if !(0.0..=127.0).contains(&nearest) {
+                return None;
+            }
+            let deviation = midi - nearest;
+            let bend = if deviation.abs() < 1e-9 {
+                None
+            } else {
+                let b = 8192.0 + deviation / bend_range * 8192.0;
+                Some(b.round().clamp(0.0, 16383.0) as u16)
+            };
+            let channel = 1 + count % 15;
+            count = count.wrapping_add(1) % 15;
+            Some(MidiNote {
+                value: v,
+                channel,
+                note: nearest as u8,
+                bend,
+            })
+        })
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// A MIDI note realized from a Scale, as produced by `Scale::iter_midi_note`.
+///
+/// # Fields
+/// * `value` - The Sieve value (step) realized.
+/// * `channel` - The zero-based MIDI channel.
+/// * `note` - The MIDI note number.
+/// * `bend` - The 14-bit pitch bend, where 8192 is no bend, or `None` if the pitch is in 12-EDO.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MidiNote {
+    pub value: i128,
+    pub channel: u8,
+    pub note: u8,
+    pub bend: Option<u16>,
+}
+
+//------------------------------------------------------------------------------
+
+/// A mapping of the members of a Sieve to just-intonation frequency ratios. With `N` ratios, a member `v` is assigned the ratio at index `v mod N`, transposed by the equave (by default the octave, 2/1) once for each whole multiple of `N`.
+///
+/// ```
+/// use xensieve::{RatioMap, Sieve};
+/// let m = RatioMap::new(Sieve::new("3@0|3@2"), vec![(1, 1), (5, 4), (3, 2)]);
+/// assert_eq!(m.iter_ratio(0..6).collect::<Vec<_>>(), vec![(1, 1), (3, 2), (2, 1), (3, 1)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RatioMap {
+    sieve: Sieve,
+    ratios: Vec<(u64, u64)>,
+    equave: (u64, u64),
+}
+
+impl RatioMap {
+    /// Construct a RatioMap from a Sieve and the ratios, given as numerator and denominator pairs, assigned to each residue of the period.
+    ///
+    /// # Panics
+    /// Panics if no ratios are provided, or if any ratio has a zero term.
+    pub fn new(sieve: Sieve, ratios: Vec<(u64, u64)>) -> Self {
+        assert!(!ratios.is_empty(), "at least one ratio is required");
+        assert!(
+            ratios.iter().all(|&(n, d)| n > 0 && d > 0),
+            "ratio terms must be greater than zero"
+        );
+        Self {
+            sieve,
+            ratios,
+            equave: (2, 1),
+        }
+    }
+
+    /// Return this RatioMap with the provided equave, the interval of equivalence that transposes each period.
+    ///
+    /// # Panics
+    /// Panics if either term is zero.
+    pub fn with_equave(mut self, numerator: u64, denominator: u64) -> Self {
+        assert!(
+            numerator > 0 && denominator > 0,
+            "ratio terms must be greater than zero"
+        );
+        self.equave = (numerator, denominator);
+        self
+    }
+
+    /// Return the ratio, reduced to lowest terms, of a value, or `None` if the value is not in the Sieve, or if the ratio cannot be represented in `u64` terms.
+    pub fn ratio(&self, value: i128) -> Option<(u64, u64)> {
+        if !self.sieve.contains(value) {
+            return None;
+        }
+        let count = self.ratios.len() as i128;
+        let (n, d) = self.ratios[value.rem_euclid(count) as usize];
+        let g = util::gcd(n, d, 0).unwrap();
+        let (mut n, mut d) = (n / g, d / g);
+        let equaves = value.div_euclid(count);
+        let g = util::gcd(self.equave.0, self.equave.1, 0).unwrap();
+        let (en, ed) = if equaves < 0 {
+            (self.equave.1 / g, self.equave.0 / g)
+        } else {
+            (self.equave.0 / g, self.equave.1 / g)
+        };
+        if en == ed {
+            return Some((n, d));
+        }
+        // as a reduced equave other than 1/1 has a term of at least 2, this overflows within 128 steps
+        for _ in 0..equaves.unsigned_abs() {
+            n = n.checked_mul(en)?;
+            d = d.checked_mul(ed)?;
+            let g = util::gcd(n, d, 0).unwrap();
+            n /= g;
+            d /= g;
+        }
+        Some((n, d))
+    }
+
+    /// Return the size in cents of the ratio of a value, or `None` if the value is not in the Sieve. The size is the sum of the sizes of the ratio and of the transposing equaves, so it is available where the ratio itself is not representable.
+    pub fn cents(&self, value: i128) -> Option<f64> {
+        if !self.sieve.contains(value) {
+            return None;
+        }
+        let count = self.ratios.len() as i128;
+        let (n, d) = self.ratios[value.rem_euclid(count) as usize];
+        let equaves = value.div_euclid(count) as f64;
+        let (en, ed) = self.equave;
+        Some(1200.0 * ((n as f64 / d as f64).log2() + equaves * (en as f64 / ed as f64).log2()))
+    }
+
+    /// For the iterator provided as an input, iterate the ratios of the values contained within the Sieve.
+    pub fn iter_ratio<'a>(
+        &'a self,
+        iterator: impl Iterator<Item = i128> + 'a,
+    ) -> impl Iterator<Item = (u64, u64)> + 'a {
+        iterator.filter_map(move |v| self.ratio(v))
+    }
+
+    /// For the iterator provided as an input, iterate the cents of the values contained within the Sieve.
+    pub fn iter_cents<'a>(
+        &'a self,
+        iterator: impl Iterator<Item = i128> + 'a,
+    ) -> impl Iterator<Item = f64> + 'a {
+        iterator.filter_map(move |v| self.cents(v))
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_midi_a() {
+        let s = Scale::new(Sieve::new("7@0|7@2"), 60.0, 12);
+        assert_eq!(s.midi(0).unwrap(), 60.0);
+        assert_eq!(s.midi(-5).unwrap(), 55.0);
+        assert_eq!(s.midi(1), None);
+    }
+
+    #[test]
+    fn test_scale_midi_b() {
+        let s = Scale::new(Sieve::new("1@0"), 60.0, 19);
+        assert!((s.midi(19).unwrap() - 72.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_scale_frequency_a() {
+        let s = Scale::new(Sieve::new("12@9"), 60.0, 12);
+        assert_eq!(s.frequency(9).unwrap(), 440.0);
+        assert_eq!(s.frequency(-3).unwrap(), 220.0);
+        assert_eq!(s.frequency(10), None);
+    }
+
+    #[test]
+    fn test_scale_note_name_a() {
+        let s = Scale::new(Sieve::new("1@0"), 60.0, 12);
+        assert_eq!(s.note_name(0).unwrap(), "C4");
+        assert_eq!(s.note_name(1).unwrap(), "C#4");
+        assert_eq!(s.note_name(-1).unwrap(), "B3");
+        assert_eq!(s.note_name(-13).unwrap(), "B2");
+        assert_eq!(s.note_name(12).unwrap(), "C5");
+    }
+
+    #[test]
+    fn test_scale_note_name_b() {
+        let s = Scale::new(Sieve::new("1@0"), 60.0, 12).with_spelling(Spelling::Flat);
+        assert_eq!(s.note_name(3).unwrap(), "Eb4");
+        assert_eq!(s.note_name(10).unwrap(), "Bb4");
+    }
+
+    #[test]
+    fn test_scale_note_name_c() {
+        let s = Scale::new(Sieve::new("1@0"), 60.0, 24);
+        assert_eq!(s.note_name(1).unwrap(), "C4+50");
+        assert_eq!(s.note_name(-1).unwrap(), "B3+50");
+        assert_eq!(s.note_name(5).unwrap(), "D4+50");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_scale_new_a() {
+        Scale::new(Sieve::new("1@0"), 60.0, 0);
+    }
+
+    //--------------------------------------------------------------------------
+
+    #[test]
+    fn test_ratio_map_ratio_a() {
+        let m = RatioMap::new(Sieve::new("1@0"), vec![(1, 1), (9, 8), (5, 4)]);
+        assert_eq!(m.ratio(0).unwrap(), (1, 1));
+        assert_eq!(m.ratio(4).unwrap(), (9, 4));
+        assert_eq!(m.ratio(-1).unwrap(), (5, 8));
+        assert_eq!(m.ratio(-6).unwrap(), (1, 4));
+    }
+
+    #[test]
+    fn test_ratio_map_ratio_b() {
+        let m = RatioMap::new(Sieve::new("2@0"), vec![(1, 1), (6, 4)]);
+        assert_eq!(m.ratio(1), None);
+        assert_eq!(m.ratio(2).unwrap(), (2, 1));
+    }
+
+    #[test]
+    fn test_ratio_map_ratio_c() {
+        let m = RatioMap::new(Sieve::new("1@0"), vec![(1, 1), (9, 7)]).with_equave(3, 1);
+        assert_eq!(m.ratio(3).unwrap(), (27, 7));
+        assert_eq!(m.ratio(-2).unwrap(), (1, 3));
+    }
+
+    #[test]
+    fn test_ratio_map_ratio_d() {
+        let m = RatioMap::new(Sieve::new("1@0"), vec![(1, 1), (3, 2)]);
+        assert_eq!(m.ratio(126).unwrap(), (1 << 63, 1));
+        assert_eq!(m.ratio(128), None);
+        assert_eq!(m.ratio(-130), None);
+        assert_eq!(m.ratio(i128::MAX), None);
+        let m = RatioMap::new(Sieve::new("1@0"), vec![(1, 1), (3, 2)]).with_equave(3, 3);
+        assert_eq!(m.ratio(i128::MIN + 1).unwrap(), (3, 2));
+    }
+
+    #[test]
+    fn test_ratio_map_cents_b() {
+        let m = RatioMap::new(Sieve::new("1@0"), vec![(1, 1), (3, 2)]);
+        assert_eq!(m.cents(400).unwrap().round(), 240000.0);
+        assert_eq!(m.cents(-399).unwrap().round(), -240000.0 + 702.0);
+    }
+
+    #[test]
+    fn test_ratio_map_cents_a() {
+        let m = RatioMap::new(Sieve::new("1@0"), vec![(1, 1), (3, 2)]);
+        let post: Vec<_> = m.iter_cents(0..4).map(|c| c.round()).collect();
+        assert_eq!(post, vec![0.0, 702.0, 1200.0, 1902.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ratio_map_new_a() {
+        RatioMap::new(Sieve::new("1@0"), vec![(1, 0)]);
+    }
+
+    //--------------------------------------------------------------------------
+
+    #[test]
+    fn test_scale_iter_midi_note_a() {
+        let s = Scale::new(Sieve::new("1@0"), 60.0, 12);
+        let notes: Vec<_> = s.iter_midi_note(0..17, 2.0).collect();
+        assert_eq!(notes.len(), 17);
+        assert!(notes.iter().all(|n| n.bend.is_none()));
+        assert_eq!(notes[14].channel, 15);
+        assert_eq!(notes[15].channel, 1);
+        assert_eq!(notes[16].note, 76);
+    }
+
+    #[test]
+    fn test_scale_iter_midi_note_b() {
+        let s = Scale::new(Sieve::new("1@0"), 60.0, 24);
+        let notes: Vec<_> = s.iter_midi_note(-1..2, 1.0).collect();
+        assert_eq!(notes[0].note, 59);
+        assert_eq!(notes[0].bend, Some(12288));
+        assert_eq!(notes[2].bend, Some(12288));
+    }
+
+    #[test]
+    fn test_scale_iter_midi_note_c() {
+        let s = Scale::new(Sieve::new("1@0"), 120.0, 12);
+        let notes: Vec<_> = s.iter_midi_note(0..12, 2.0).collect();
+        assert_eq!(notes.len(), 8);
+        assert_eq!(notes[7].note, 127);
+    }
+
+    #[test]
+    fn test_scale_iter_midi_note_d() {
+        // channels rotate over 1 through 15, repeatedly, and skipped values do not advance the rotation
+        let s = Scale::new(Sieve::new("1@0"), 60.0, 12);
+        let notes: Vec<_> = s.iter_midi_note(-100..40, 2.0).collect();
+        assert_eq!(notes.len(), 100);
+        assert_eq!(notes[0].note, 0);
+        for (i, n) in notes.iter().enumerate() {
+            assert_eq!(n.channel as usize, 1 + i % 15);
+        }
+        assert_eq!(notes[44].channel, 15);
+        assert_eq!(notes[45].channel, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_scale_iter_midi_note_e() {
+        let s = Scale::new(Sieve::new("1@0"), 60.0, 24);
+        let _ = s.iter_midi_note(0..3, 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_scale_iter_midi_note_f() {
+        let s = Scale::new(Sieve::new("1@0"), 60.0, 24);
+        let _ = s.iter_midi_note(0..3, f64::NAN);
+    }
+}
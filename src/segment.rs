@@ -0,0 +1,142 @@
+use crate::Sieve;
+
+/// A finite, ascending sequence of unique values, such as those realized from a Sieve over a range.
+///
+/// ```
+/// let s = xensieve::Segment::new(vec![0, 2, 3, 7]);
+/// assert_eq!(s.intervals(), vec![2, 1, 4]);
+/// assert_eq!(s.retrograde().values(), &[0, 4, 5, 7]);
+/// assert_eq!(s.rotate(1).values(), &[0, 1, 5, 7]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    values: Vec<i128>,
+}
+
+impl Segment {
+    /// Construct a Segment from any values; values are sorted and duplicates are removed.
+    pub fn new(values: impl IntoIterator<Item = i128>) -> Self {
+        let mut values: Vec<i128> = values.into_iter().collect();
+        values.sort_unstable();
+        values.dedup();
+        Self { values }
+    }
+
+    /// Construct a Segment from a first value and successive intervals.
+    fn from_intervals(first: i128, intervals: impl IntoIterator<Item = i128>) -> Self {
+        let mut values = vec![first];
+        let mut last = first;
+        for i in intervals {
+            last += i;
+            values.push(last);
+        }
+        Self::new(values)
+    }
+
+    /// Return the values of this Segment.
+    pub fn values(&self) -> &[i128] {
+        &self.values
+    }
+
+    /// Return the intervals between successive values.
+    pub fn intervals(&self) -> Vec<i128> {
+        self.values.windows(2).map(|w| w[1] - w[0]).collect()
+    }
+
+    /// Return the retrograde of this Segment: the interval sequence reversed, spanning the same first and last values.
+    pub fn retrograde(&self) -> Self {
+        match (self.values.first(), self.values.last()) {
+            (Some(&first), Some(&last)) => Self::new(self.values.iter().map(|v| first + last - v)),
+            _ => self.clone(),
+        }
+    }
+
+    /// Return a rotation of this Segment: the interval sequence rotated left by `n` positions (right if `n` is negative), starting from the same first value.
+    pub fn rotate(&self, n: i64) -> Self {
+        let mut intervals = self.intervals();
+        match self.values.first() {
+            Some(&first) if !intervals.is_empty() => {
+                let shift = n.rem_euclid(intervals.len() as i64) as usize;
+                intervals.rotate_left(shift);
+                Self::from_intervals(first, intervals)
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Return a Sieve, with the provided period, that contains the values of this Segment, compressed into Residuals as `Sieve::from_values`.
+    ///
+    /// ```
+    /// let s = xensieve::Segment::new(vec![0, 3, 4, 6, 8, 9]);
+    /// assert_eq!(s.to_sieve(12).to_string(), "Sieve{3@0|4@0}");
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `period` is zero.
+    pub fn to_sieve(&self, period: u64) -> Sieve {
+        Sieve::from_values(self.values.iter().copied(), period)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_new_a() {
+        let s = Segment::new(vec![5, 1, 3, 1]);
+        assert_eq!(s.values(), &[1, 3, 5]);
+        assert_eq!(s.intervals(), vec![2, 2]);
+    }
+
+    #[test]
+    fn test_segment_retrograde_a() {
+        let s = Segment::new(vec![0, 1, 3, 6, 10]);
+        assert_eq!(s.retrograde().values(), &[0, 4, 7, 9, 10]);
+        assert_eq!(s.retrograde().retrograde(), s);
+    }
+
+    #[test]
+    fn test_segment_retrograde_b() {
+        let s = Segment::new(vec![]);
+        assert_eq!(s.retrograde().values(), &[]);
+        let s = Segment::new(vec![4]);
+        assert_eq!(s.retrograde().values(), &[4]);
+    }
+
+    #[test]
+    fn test_segment_rotate_a() {
+        let s = Segment::new(vec![10, 12, 13, 17]);
+        assert_eq!(s.rotate(0), s);
+        assert_eq!(s.rotate(3), s);
+        assert_eq!(s.rotate(2).values(), &[10, 14, 16, 17]);
+        assert_eq!(s.rotate(-1).values(), &[10, 14, 16, 17]);
+    }
+
+    #[test]
+    fn test_segment_to_sieve_a() {
+        let s = Segment::new(vec![0, 2, 3]).rotate(1);
+        let sieve = s.to_sieve(5);
+        assert_eq!(sieve.to_string(), "Sieve{5@0|5@1|5@3}");
+        assert_eq!(
+            sieve.iter_value(0..10).collect::<Vec<_>>(),
+            vec![0, 1, 3, 5, 6, 8]
+        );
+    }
+
+    #[test]
+    fn test_segment_to_sieve_b() {
+        let s = Segment::new(vec![]);
+        assert_eq!(s.to_sieve(4).to_string(), "Sieve{0@0}");
+    }
+
+    #[test]
+    fn test_segment_to_sieve_c() {
+        let s = Segment::new(vec![1, 3, 5, 7, 9, 11]);
+        let sieve = s.to_sieve(12);
+        assert_eq!(sieve.to_string(), "Sieve{2@1}");
+        assert_eq!(sieve.scan(0..12), s.values());
+    }
+}
@@ -0,0 +1,211 @@
+use crate::Residual;
+use crate::SieveNode;
+
+fn is_empty(node: &SieveNode) -> bool {
+    matches!(node, SieveNode::Unit(r) if r.modulus() == 0)
+}
+
+fn is_all(node: &SieveNode) -> bool {
+    matches!(node, SieveNode::Unit(r) if r.modulus() == 1)
+}
+
+fn empty() -> SieveNode {
+    SieveNode::Unit(Residual::new(0, 0))
+}
+
+fn all() -> SieveNode {
+    SieveNode::Unit(Residual::new(1, 0))
+}
+
+/// Return `true` if `a` is the inversion of `b`, or `b` the inversion of `a`.
+fn is_complement(a: &SieveNode, b: &SieveNode) -> bool {
+    matches!(a, SieveNode::Inversion(p) if **p == *b)
+        || matches!(b, SieveNode::Inversion(p) if **p == *a)
+}
+
+/// Return `true` if `part` is an operand of the binary node `node` of the provided kind.
+fn absorbs(part: &SieveNode, node: &SieveNode, union: bool) -> bool {
+    match node {
+        SieveNode::Union(x, y) if union => **x == *part || **y == *part,
+        SieveNode::Intersection(x, y) if !union => **x == *part || **y == *part,
+        _ => false,
+    }
+}
+
+fn inversion(part: SieveNode) -> SieveNode {
+    if is_empty(&part) {
+        return all();
+    }
+    if is_all(&part) {
+        return empty();
+    }
+    match part {
+        // double negation
+        SieveNode::Inversion(p) => *p,
+        // De Morgan, where both operands are inverted
+        SieveNode::Union(a, b)
+            if matches!(*a, SieveNode::Inversion(_)) && matches!(*b, SieveNode::Inversion(_)) =>
+        {
+            intersection(inversion(*a), inversion(*b))
+        }
+        SieveNode::Intersection(a, b)
+            if matches!(*a, SieveNode::Inversion(_)) && matches!(*b, SieveNode::Inversion(_)) =>
+        {
+            union(inversion(*a), inversion(*b))
+        }
+        p => SieveNode::Inversion(Box::new(p)),
+    }
+}
+
+fn intersection(a: SieveNode, b: SieveNode) -> SieveNode {
+    if is_empty(&a) || is_empty(&b) || is_complement(&a, &b) {
+        return empty();
+    }
+    if is_all(&a) || a == b || absorbs(&a, &b, true) {
+        return if is_all(&a) { b } else { a };
+    }
+    if is_all(&b) || absorbs(&b, &a, true) {
+        return if is_all(&b) { a } else { b };
+    }
+    if let (SieveNode::Unit(r1), SieveNode::Unit(r2)) = (&a, &b) {
+        if let Some(r) = r1.intersection(r2) {
+            return SieveNode::Unit(r);
+        }
+    }
+    SieveNode::Intersection(Box::new(a), Box::new(b))
+}
+
+fn union(a: SieveNode, b: SieveNode) -> SieveNode {
+    if is_all(&a) || is_all(&b) || is_complement(&a, &b) {
+        return all();
+    }
+    if is_empty(&a) {
+        return b;
+    }
+    if is_empty(&b) || a == b || absorbs(&a, &b, false) {
+        return a;
+    }
+    if absorbs(&b, &a, false) {
+        return b;
+    }
+    if let (SieveNode::Unit(r1), SieveNode::Unit(r2)) = (&a, &b) {
+        if r1.is_subset_of(r2) {
+            return b;
+        }
+        if r2.is_subset_of(r1) {
+            return a;
+        }
+    }
+    SieveNode::Union(Box::new(a), Box::new(b))
+}
+
+fn symmetric_difference(a: SieveNode, b: SieveNode) -> SieveNode {
+    if a == b {
+        return empty();
+    }
+    if is_complement(&a, &b) {
+        return all();
+    }
+    if is_empty(&a) {
+        return b;
+    }
+    if is_empty(&b) {
+        return a;
+    }
+    if is_all(&a) {
+        return inversion(b);
+    }
+    if is_all(&b) {
+        return inversion(a);
+    }
+    SieveNode::SymmetricDifference(Box::new(a), Box::new(b))
+}
+
+fn difference(a: SieveNode, b: SieveNode) -> SieveNode {
+    if is_empty(&a) || is_all(&b) || a == b {
+        return empty();
+    }
+    if is_empty(&b) || is_complement(&a, &b) {
+        return a;
+    }
+    if is_all(&a) {
+        return inversion(b);
+    }
+    if let (SieveNode::Unit(r1), SieveNode::Unit(r2)) = (&a, &b) {
+        if r1.is_subset_of(r2) {
+            return empty();
+        }
+        if r1.intersection(r2).map_or(false, |r| r.modulus() == 0) {
+            return a;
+        }
+    }
+    SieveNode::Difference(Box::new(a), Box::new(b))
+}
+
+/// Return an equivalent node, no larger, with double negations, idempotent and absorbed operands, and empty (`0@0`) and universal (`1@0`) operands removed, inverted operands combined by De Morgan's laws, and intersections of Residuals folded into one Residual. Rewriting is bottom-up.
+pub(crate) fn simplify(node: &SieveNode) -> SieveNode {
+    match node {
+        SieveNode::Unit(r) => SieveNode::Unit(*r),
+        SieveNode::Inversion(part) => inversion(simplify(part)),
+        SieveNode::Intersection(lhs, rhs) => intersection(simplify(lhs), simplify(rhs)),
+        SieveNode::Union(lhs, rhs) => union(simplify(lhs), simplify(rhs)),
+        SieveNode::SymmetricDifference(lhs, rhs) => {
+            symmetric_difference(simplify(lhs), simplify(rhs))
+        }
+        SieveNode::Difference(lhs, rhs) => difference(simplify(lhs), simplify(rhs)),
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sieve;
+
+    fn simplified(e: &str) -> String {
+        let s = Sieve::new(e);
+        let n = simplify(&s.root);
+        assert!(n.size() <= s.root.size());
+        let t = Sieve::from_node(n);
+        assert!(t.is_equivalent(&s), "{e} -> {t}");
+        t.to_string()
+    }
+
+    #[test]
+    fn test_simplify_a() {
+        assert_eq!(simplified("!!(3@0)"), "Sieve{3@0}");
+        assert_eq!(simplified("!(!(3@0)|!(4@1))"), "Sieve{12@9}");
+        assert_eq!(simplified("!(!(3@0)&!(4@1))"), "Sieve{3@0|4@1}");
+        assert_eq!(simplified("!(0@0)"), "Sieve{1@0}");
+        assert_eq!(simplified("!(1@0)"), "Sieve{0@0}");
+    }
+
+    #[test]
+    fn test_simplify_b() {
+        assert_eq!(simplified("3@0|3@0"), "Sieve{3@0}");
+        assert_eq!(simplified("3@0&(3@0|5@1)"), "Sieve{3@0}");
+        assert_eq!(simplified("(5@1&7@2)|5@1"), "Sieve{5@1}");
+        assert_eq!(simplified("1@0&(5@1|7@2)"), "Sieve{5@1|7@2}");
+        assert_eq!(simplified("0@0|(5@1^7@2)"), "Sieve{5@1^7@2}");
+        assert_eq!(simplified("6@0|3@0"), "Sieve{3@0}");
+        assert_eq!(simplified("3@0|!(3@0)"), "Sieve{1@0}");
+        assert_eq!(simplified("3@0&!(3@0)"), "Sieve{0@0}");
+    }
+
+    #[test]
+    fn test_simplify_c() {
+        assert_eq!(simplified("4@3&6@1"), "Sieve{12@7}");
+        assert_eq!(simplified("4@0&6@1"), "Sieve{0@0}");
+        assert_eq!(simplified("(4@0&6@1)|5@2"), "Sieve{5@2}");
+        assert_eq!(simplified("3@0^3@0"), "Sieve{0@0}");
+        assert_eq!(simplified("1@0^3@0"), "Sieve{!(3@0)}");
+        assert_eq!(simplified("6@0-3@0"), "Sieve{0@0}");
+        assert_eq!(simplified("6@0-3@1"), "Sieve{6@0}");
+        assert_eq!(simplified("1@0-(2@0|3@0)"), "Sieve{!(2@0|3@0)}");
+        assert_eq!(
+            simplified("(5@0|4@2)&!(30@10)"),
+            "Sieve{(5@0|4@2)&!(30@10)}"
+        );
+    }
+}
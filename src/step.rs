@@ -0,0 +1,158 @@
+use std::fmt;
+
+use crate::Residual;
+use crate::SieveNode;
+
+/// One step of the evaluation of a value by a Sieve, as produced by `Sieve::evaluate_verbose`. Steps are ordered as evaluated: the operands of an operation before the operation.
+///
+/// ```
+/// let steps = xensieve::Sieve::new("3@0|4@1").evaluate_verbose(5);
+/// let lines: Vec<String> = steps.iter().map(|s| s.to_string()).collect();
+/// assert_eq!(lines, vec![
+///     "5 = 3 * 1 + 2; 2 != 0, so 5 is not in 3@0",
+///     "5 = 4 * 1 + 1; 1 == 1, so 5 is in 4@1",
+///     "3@0|4@1: false or true is true",
+/// ]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// The test of a value against a Residual: the value divided by the modulus, and whether the remainder equals the shift.
+    Congruence {
+        residual: Residual,
+        value: i128,
+        quotient: i128,
+        remainder: u64,
+        result: bool,
+    },
+    /// The combination of the results of one or two operands by an operator, named as `and`, `or`, `xor`, `and not`, or `not`.
+    Operation {
+        expression: String,
+        operator: &'static str,
+        operands: Vec<bool>,
+        result: bool,
+    },
+}
+
+impl Step {
+    /// Return the result of this step.
+    pub fn result(&self) -> bool {
+        match self {
+            Step::Congruence { result, .. } | Step::Operation { result, .. } => *result,
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Congruence {
+                residual,
+                value,
+                quotient,
+                remainder,
+                result,
+            } => {
+                if residual.modulus() == 0 {
+                    return write!(f, "{residual} contains no values, so {value} is not in it");
+                }
+                let (op, is) = if *result {
+                    ("==", "is")
+                } else {
+                    ("!=", "is not")
+                };
+                write!(
+                    f,
+                    "{value} = {} * {quotient} + {remainder}; {remainder} {op} {}, so {value} {is} in {residual}",
+                    residual.modulus(),
+                    residual.shift(),
+                )
+            }
+            Step::Operation {
+                expression,
+                operator,
+                operands,
+                result,
+            } => match operands.as_slice() {
+                [a] => write!(f, "{expression}: {operator} {a} is {result}"),
+                [a, b] => write!(f, "{expression}: {a} {operator} {b} is {result}"),
+                _ => write!(f, "{expression}: {result}"),
+            },
+        }
+    }
+}
+
+/// Evaluate the value by the node, appending each step to `steps`, and return the result. All operands are evaluated, without short-circuiting, such that every Residual is shown.
+pub(crate) fn evaluate(node: &SieveNode, value: i128, steps: &mut Vec<Step>) -> bool {
+    let (operator, operands) = match node {
+        SieveNode::Unit(r) => {
+            let (quotient, remainder) = if r.modulus() == 0 {
+                (0, 0)
+            } else {
+                let m = r.modulus() as i128;
+                (value.div_euclid(m), value.rem_euclid(m) as u64)
+            };
+            let result = r.contains(value);
+            steps.push(Step::Congruence {
+                residual: *r,
+                value,
+                quotient,
+                remainder,
+                result,
+            });
+            return result;
+        }
+        SieveNode::Intersection(lhs, rhs) => ("and", vec![lhs, rhs]),
+        SieveNode::Union(lhs, rhs) => ("or", vec![lhs, rhs]),
+        SieveNode::SymmetricDifference(lhs, rhs) => ("xor", vec![lhs, rhs]),
+        SieveNode::Difference(lhs, rhs) => ("and not", vec![lhs, rhs]),
+        SieveNode::Inversion(part) => ("not", vec![part]),
+    };
+    let operands: Vec<bool> = operands
+        .into_iter()
+        .map(|n| evaluate(n, value, steps))
+        .collect();
+    let result = node.contains(value);
+    steps.push(Step::Operation {
+        expression: node.to_string(),
+        operator,
+        operands,
+        result,
+    });
+    result
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::Sieve;
+
+    #[test]
+    fn test_evaluate_a() {
+        let s = Sieve::new("!(0@0)-5@2^4@3");
+        let steps = s.evaluate_verbose(-2);
+        let lines: Vec<String> = steps.iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "0@0 contains no values, so -2 is not in it",
+                "!(0@0): not false is true",
+                "-2 = 5 * -1 + 3; 3 != 2, so -2 is not in 5@2",
+                "!(0@0)-5@2: true and not false is true",
+                "-2 = 4 * -1 + 2; 2 != 3, so -2 is not in 4@3",
+                "!(0@0)-5@2^4@3: true xor false is true",
+            ]
+        );
+        assert_eq!(steps.last().unwrap().result(), s.contains(-2));
+    }
+
+    #[test]
+    fn test_evaluate_b() {
+        let s = Sieve::new("(5@0|4@2)&!(30@10)");
+        for v in -30..30 {
+            let steps = s.evaluate_verbose(v);
+            assert_eq!(steps.len(), 6);
+            assert_eq!(steps.last().unwrap().result(), s.contains(v));
+        }
+    }
+}
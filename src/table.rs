@@ -0,0 +1,182 @@
+use std::io;
+use std::ops::Range;
+
+use crate::Sieve;
+
+/// A columnar table of a Sieve realized over a range, with one row for each value of the range, as produced by `Sieve::to_table`. Columns are plain vectors of equal length, such that they can be moved into the column builders of dataframe libraries such as Arrow or Polars, or written as CSV with `to_csv`.
+///
+/// # Fields
+/// * `value` - The value.
+/// * `state` - `true` if the value is a member.
+/// * `interval` - For members after the first member of the range, the interval from the previous member; otherwise `None`.
+/// * `residuals` - For members, the channel Residuals (see `Sieve::channels`) that contain the value, in the notation `M@S` separated by spaces; otherwise an empty string.
+///
+/// ```
+/// let t = xensieve::Sieve::new("3@0|4@1").to_table(0..6);
+/// assert_eq!(t.state, vec![true, true, false, true, false, true]);
+/// assert_eq!(t.interval, vec![None, Some(1), None, Some(2), None, Some(2)]);
+/// assert_eq!(t.residuals[0], "3@0");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Table {
+    pub value: Vec<i128>,
+    pub state: Vec<bool>,
+    pub interval: Vec<Option<i128>>,
+    pub residuals: Vec<String>,
+}
+
+impl Table {
+    pub(crate) fn new(sieve: &Sieve, range: Range<i128>) -> Self {
+        let channels = sieve.channels();
+        let len = (range.end - range.start).max(0) as usize;
+        let mut table = Self {
+            value: Vec::with_capacity(len),
+            state: Vec::with_capacity(len),
+            interval: Vec::with_capacity(len),
+            residuals: Vec::with_capacity(len),
+        };
+        let mut last = None;
+        for (v, s) in range.clone().zip(sieve.iter_state(range)) {
+            table.value.push(v);
+            table.state.push(s);
+            if s {
+                table.interval.push(last.map(|l| v - l));
+                table.residuals.push(
+                    channels
+                        .iter()
+                        .filter(|r| r.contains(v))
+                        .map(|r| r.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                );
+                last = Some(v);
+            } else {
+                table.interval.push(None);
+                table.residuals.push(String::new());
+            }
+        }
+        table
+    }
+
+    /// Return the number of rows.
+    pub fn len(&self) -> usize {
+        self.value.len()
+    }
+
+    /// Return `true` if there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Return the table as CSV, with a header row of the column names; a missing interval is an empty field.
+    /// ```
+    /// let t = xensieve::Sieve::new("3@0|4@1").to_table(0..3);
+    /// assert_eq!(t.to_csv(), "value,state,interval,residuals\n0,true,,3@0\n1,true,1,4@1\n2,false,,\n");
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut post = String::from("value,state,interval,residuals\n");
+        for i in 0..self.len() {
+            let interval = self.interval[i].map_or(String::new(), |v| v.to_string());
+            post.push_str(&format!(
+                "{},{},{},{}\n",
+                self.value[i], self.state[i], interval, self.residuals[i]
+            ));
+        }
+        post
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Write the Tables of many sieves over the range as one CSV dataset for corpus studies, with columns for the expression, period, and density of each sieve before the columns of each row of its Table (see `Table::to_csv`). A sieve without a period has an empty period field. Rows are written as they are realized, such that large corpora are not held in memory.
+///
+/// ```
+/// let sieves = [xensieve::Sieve::new("2@0"), xensieve::Sieve::new("3@1")];
+/// let mut post = Vec::new();
+/// xensieve::write_corpus(&mut post, &sieves, 0..2).unwrap();
+/// assert_eq!(
+///     String::from_utf8(post).unwrap(),
+///     "expression,period,density,value,state,interval,residuals\n\
+///      2@0,2,0.5,0,true,,2@0\n\
+///      2@0,2,0.5,1,false,,\n\
+///      3@1,3,0.3333333333333333,0,false,,\n\
+///      3@1,3,0.3333333333333333,1,true,,3@1\n"
+/// );
+/// ```
+///
+/// # Panics
+/// Panics if the period of a sieve overflows.
+pub fn write_corpus<W: io::Write>(
+    mut writer: W,
+    sieves: &[Sieve],
+    range: Range<i128>,
+) -> io::Result<()> {
+    writeln!(
+        writer,
+        "expression,period,density,value,state,interval,residuals"
+    )?;
+    for sieve in sieves {
+        let period = sieve.period().map_or(String::new(), |p| p.to_string());
+        let density = sieve.density();
+        let table = Table::new(sieve, range.clone());
+        for i in 0..table.len() {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                sieve.root,
+                period,
+                density,
+                table.value[i],
+                table.state[i],
+                table.interval[i].map_or(String::new(), |v| v.to_string()),
+                table.residuals[i],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_new_a() {
+        let s = Sieve::new("4@0|6@0&!(12@0)");
+        let t = s.to_table(-12..13);
+        assert_eq!(t.len(), 25);
+        assert_eq!(
+            t.value.iter().zip(&t.state).filter(|(_, s)| **s).count(),
+            s.count(-12..13) as usize
+        );
+        let i = t.value.iter().position(|v| *v == 0).unwrap();
+        assert_eq!(
+            (t.interval[i], t.residuals[i].as_str()),
+            (Some(4), "4@0 6@0")
+        );
+        let i = t.value.iter().position(|v| *v == 6).unwrap();
+        assert_eq!((t.interval[i], t.residuals[i].as_str()), (Some(2), "6@0"));
+        assert_eq!(t.interval[0], None);
+    }
+
+    #[test]
+    fn test_table_new_b() {
+        let t = Sieve::new("3@0").to_table(5..5);
+        assert!(t.is_empty());
+        assert_eq!(t.to_csv(), "value,state,interval,residuals\n");
+    }
+
+    #[test]
+    fn test_write_corpus_a() {
+        let sieves = [Sieve::empty(), Sieve::new("3@0&!(6@0)")];
+        let mut post = Vec::new();
+        write_corpus(&mut post, &sieves, 0..4).unwrap();
+        let post = String::from_utf8(post).unwrap();
+        let lines: Vec<&str> = post.lines().collect();
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[1], "0@0,,0,0,false,,");
+        assert_eq!(lines[8], "3@0&!(6@0),6,0.16666666666666666,3,true,,3@0");
+    }
+}
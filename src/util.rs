@@ -1,5 +1,5 @@
 /// Find the greatest common divisor.
-fn gcd<T>(mut n: T, mut m: T, zero: T) -> Result<T, &'static str>
+pub(crate) fn gcd<T>(mut n: T, mut m: T, zero: T) -> Result<T, &'static str>
 where
     T: std::ops::Rem<Output = T> + std::cmp::Ord + Copy,
 {
@@ -15,22 +15,87 @@ where
     Ok(n)
 }
 
-/// This is a brute-force implementation of modular inverse. The Extended Euclidian Algorithm might be a better choice.
+/// Return the divisors of `n`, ascending.
+pub(crate) fn divisors(n: u64) -> Vec<u64> {
+    let mut low = Vec::new();
+    let mut high = Vec::new();
+    let mut d = 1;
+    while d * d <= n {
+        if n % d == 0 {
+            low.push(d);
+            if d * d != n {
+                high.push(n / d);
+            }
+        }
+        d += 1;
+    }
+    low.extend(high.into_iter().rev());
+    low
+}
+
+/// Return the distinct prime factors of `n`, ascending.
+pub(crate) fn prime_factors(mut n: u64) -> Vec<u64> {
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p * p <= n {
+        if n % p == 0 {
+            factors.push(p);
+            while n % p == 0 {
+                n /= p;
+            }
+        }
+        p += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+/// Return the primes less than or equal to `n`, by the sieve of Eratosthenes.
+pub(crate) fn primes(n: u64) -> Vec<u64> {
+    let n = n as usize;
+    let mut composite = vec![false; n + 1];
+    let mut post = Vec::new();
+    for i in 2..=n {
+        if !composite[i] {
+            post.push(i as u64);
+            let mut j = i * i;
+            while j <= n {
+                composite[j] = true;
+                j += i;
+            }
+        }
+    }
+    post
+}
+
+/// Find the least common multiple, or `None` if the result overflows.
+pub(crate) fn lcm(n: u64, m: u64) -> Option<u64> {
+    if n == 0 || m == 0 {
+        return Some(0);
+    }
+    (n / gcd(n, m, 0).unwrap()).checked_mul(m)
+}
+
+/// Find the modular inverse of `a` modulo `b` with the Extended Euclidean Algorithm. Following the original brute-force implementation, a modulus of one returns one, and equal values return zero.
 fn meziriac(a: u64, b: u64) -> Result<u64, &'static str> {
-    let mut g: u64 = 1;
     if b == 1 {
-        g = 1;
+        return Ok(1);
     } else if a == b {
-        g = 0;
-    } else {
-        while g < u64::MAX {
-            if ((g * a) % b) == 1 {
-                break;
-            }
-            g += 1;
-        }
+        return Ok(0);
+    }
+    let (mut r0, mut r1) = (a as i128 % b as i128, b as i128);
+    let (mut t0, mut t1) = (1i128, 0i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
     }
-    Ok(g)
+    if r0 != 1 {
+        return Err("no modular inverse");
+    }
+    Ok(t0.rem_euclid(b as i128) as u64)
 }
 
 /// Core implementation of intersection of two residual classes.
@@ -52,9 +117,9 @@ pub(crate) fn intersection(
     let d = gcd(m1, m2, 0)?;
     let md1 = m1 / d;
     let md2 = m2 / d;
-    let span: u64 = (s2 as i128 - s1 as i128).abs().try_into().unwrap();
+    let span: i128 = s2 as i128 - s1 as i128;
 
-    if d != 1 && (span % d != 0) {
+    if d != 1 && (span % d as i128 != 0) {
         return Ok((0, 0)); // no intersection
     }
     // NOTE: though this case was specified, it seems impossible to replicate
@@ -62,9 +127,72 @@ pub(crate) fn intersection(
     //     return Ok((d, s1));
     // }
 
-    // d might be 1
-    let m = md1 * md2 * d;
-    Ok((m, (s1 + (meziriac(md1, md2).unwrap() * span * md1)) % m))
+    // d might be 1; solve s1 + m1 * t = s2 (mod m2) for t
+    let m = md1
+        .checked_mul(md2)
+        .and_then(|m| m.checked_mul(d))
+        .ok_or("modulus overflow")?;
+    let k = (span / d as i128).rem_euclid(md2 as i128) as u128;
+    let t = meziriac(md1, md2)? as u128 * k % md2 as u128;
+    Ok((m, ((s1 as u128 + m1 as u128 * t) % m as u128) as u64))
+}
+
+/// Return the start index of every occurrence of `needle` within `haystack`, including overlapping occurrences, by the Knuth-Morris-Pratt algorithm. An empty needle occurs at every index.
+pub(crate) fn find_all<T: PartialEq>(haystack: &[T], needle: &[T]) -> Vec<usize> {
+    if needle.is_empty() {
+        return (0..=haystack.len()).collect();
+    }
+    // failure[i]: the length of the longest proper prefix of needle[..=i] that is also its suffix
+    let mut failure = vec![0; needle.len()];
+    let mut k = 0;
+    for i in 1..needle.len() {
+        while k > 0 && needle[i] != needle[k] {
+            k = failure[k - 1];
+        }
+        if needle[i] == needle[k] {
+            k += 1;
+        }
+        failure[i] = k;
+    }
+    let mut post = Vec::new();
+    let mut k = 0;
+    for (i, h) in haystack.iter().enumerate() {
+        while k > 0 && *h != needle[k] {
+            k = failure[k - 1];
+        }
+        if *h == needle[k] {
+            k += 1;
+        }
+        if k == needle.len() {
+            post.push(i + 1 - k);
+            k = failure[k - 1];
+        }
+    }
+    post
+}
+
+/// A small, seeded pseudo-random number generator (SplitMix64), such that results are reproducible from a seed without external dependencies.
+pub(crate) struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Return a value in `0..n`, where `n` is greater than zero.
+    pub(crate) fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
 }
 
 #[cfg(test)]
@@ -98,6 +226,37 @@ mod tests {
         assert_eq!(gcd(0, 3, 0).is_err(), true);
     }
 
+    #[test]
+    fn test_divisors_a() {
+        assert_eq!(divisors(1), vec![1]);
+        assert_eq!(divisors(12), vec![1, 2, 3, 4, 6, 12]);
+        assert_eq!(divisors(49), vec![1, 7, 49]);
+    }
+
+    #[test]
+    fn test_prime_factors_a() {
+        assert_eq!(prime_factors(1), vec![]);
+        assert_eq!(prime_factors(12), vec![2, 3]);
+        assert_eq!(prime_factors(97), vec![97]);
+        assert_eq!(prime_factors(2 * 2 * 7 * 11 * 11), vec![2, 7, 11]);
+    }
+
+    #[test]
+    fn test_primes_a() {
+        assert_eq!(primes(1), vec![]);
+        assert_eq!(primes(2), vec![2]);
+        assert_eq!(primes(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(primes(10_000).len(), 1229);
+    }
+
+    #[test]
+    fn test_lcm_a() {
+        assert_eq!(lcm(4, 6).unwrap(), 12);
+        assert_eq!(lcm(7, 1).unwrap(), 7);
+        assert_eq!(lcm(0, 3).unwrap(), 0);
+        assert_eq!(lcm(u64::MAX, 2), None);
+    }
+
     #[test]
     fn test_intersection_a() {
         assert_eq!(intersection(0, 0, 2, 3).unwrap(), (0, 0));
@@ -105,7 +264,36 @@ mod tests {
 
     #[test]
     fn test_intersection_b() {
-        assert_eq!(intersection(45, 40, 11, 1).unwrap(), (360, 101));
+        assert_eq!(intersection(45, 40, 11, 1).unwrap(), (360, 281));
+    }
+
+    #[test]
+    fn test_intersection_c() {
+        // where the second shift is below the first
+        assert_eq!(intersection(4, 6, 3, 1).unwrap(), (12, 7));
+        assert_eq!(intersection(6, 4, 1, 3).unwrap(), (12, 7));
+        for m1 in 1..13 {
+            for m2 in 1..13 {
+                for s1 in 0..m1 {
+                    for s2 in 0..m2 {
+                        let (m, s) = intersection(m1, m2, s1, s2).unwrap();
+                        for v in 0..(m1 * m2) {
+                            let expected = v % m1 == s1 && v % m2 == s2;
+                            assert_eq!(m != 0 && v % m == s, expected);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_intersection_d() {
+        let (m, s) = intersection(1_000_000_007, 998_244_353, 5, 9).unwrap();
+        assert_eq!(m, 1_000_000_007 * 998_244_353);
+        assert_eq!(s % 1_000_000_007, 5);
+        assert_eq!(s % 998_244_353, 9);
+        assert!(intersection(u64::MAX, u64::MAX - 1, 0, 0).is_err());
     }
 
     #[test]
@@ -118,4 +306,26 @@ mod tests {
         assert_eq!(meziriac(20, 9).unwrap(), 5);
         assert_eq!(meziriac(101, 13).unwrap(), 4);
     }
+
+    #[test]
+    fn test_random_a() {
+        let a: Vec<_> = (0..4)
+            .scan(Random::new(7), |r, _| Some(r.below(10)))
+            .collect();
+        let b: Vec<_> = (0..4)
+            .scan(Random::new(7), |r, _| Some(r.below(10)))
+            .collect();
+        assert_eq!(a, b);
+        assert!(a.iter().all(|v| *v < 10));
+        assert_ne!(Random::new(1).next_u64(), Random::new(2).next_u64());
+    }
+
+    #[test]
+    fn test_find_all_a() {
+        assert_eq!(find_all(&[1, 2, 1, 2, 1, 3], &[1, 2, 1]), vec![0, 2]);
+        assert_eq!(find_all(&[2, 2, 2], &[2, 2]), vec![0, 1]);
+        assert_eq!(find_all(&[1, 2], &[1, 2, 3]), Vec::<usize>::new());
+        assert_eq!(find_all::<u8>(&[], &[]), vec![0]);
+        assert_eq!(find_all(&[1, 1, 2, 1, 1, 1, 2], &[1, 1, 2]), vec![0, 4]);
+    }
 }
@@ -0,0 +1,175 @@
+//! Self-test vectors and an invariant checker, for validating an integration of this crate, such as a custom element type or a port of the notation, against brute-force membership. `check_invariants` evaluates algebraic laws of a Sieve over a range by testing each value through the tree of Residuals, and compares every optimized evaluation path with the same; `check_vectors` compares the members of reference expressions with known values.
+//!
+//! ```
+//! use xensieve::verify;
+//! let s = xensieve::Sieve::new("(5@0|4@2)&!(30@10)");
+//! assert!(verify::check_invariants(&s, -100..100).is_empty());
+//! assert!(verify::check_vectors().is_empty());
+//! ```
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::Sieve;
+
+/// Reference expressions, with a range and the members of the expression within that range.
+pub const VECTORS: &[(&str, Range<i128>, &[i128])] = &[
+    ("3@0|4@0", 0..13, &[0, 3, 4, 6, 8, 9, 12]),
+    ("(5@0|4@2)&!(30@10)", 0..20, &[0, 2, 5, 6, 14, 15, 18]),
+    ("12@0|12@4|12@7", 0..24, &[0, 4, 7, 12, 16, 19]),
+    ("!(3@0)", -3..6, &[-2, -1, 1, 2, 4, 5]),
+    ("6@0&4@2", 0..24, &[6, 18]),
+    ("5@2^10@2", 0..20, &[7, 17]),
+    ("4@0-8@0", 0..16, &[4, 12]),
+    ("6@0&4@1", 0..24, &[]),
+];
+
+/// A law that does not hold for a Sieve, as returned by `check_invariants` and `check_vectors`.
+///
+/// # Fields
+/// * `law` - The name of the law.
+/// * `expression` - The expression of the Sieve checked.
+/// * `value` - A value at which the law does not hold, if the law is of one value.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    pub law: &'static str,
+    pub expression: String,
+    pub value: Option<i128>,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.value {
+            Some(v) => write!(
+                f,
+                "{} does not hold for {} at {}",
+                self.law, self.expression, v
+            ),
+            None => write!(f, "{} does not hold for {}", self.law, self.expression),
+        }
+    }
+}
+
+/// Return the first value of the range at which `law` is `false`, as a Violation.
+fn check(
+    law: &'static str,
+    sieve: &Sieve,
+    range: &Range<i128>,
+    holds: impl Fn(i128) -> bool,
+) -> Option<Violation> {
+    range.clone().find(|v| !holds(*v)).map(|v| Violation {
+        law,
+        expression: sieve.root.to_string(),
+        value: Some(v),
+    })
+}
+
+/// Return the laws that do not hold for the Sieve over the range, each with its first counterexample; an empty result means every law holds. Membership is tested by brute force through the tree of Residuals and compared with: `contains` and `Sieve::warm`; the complement; De Morgan's laws and distributivity, spot-checked with the Sieve translated by one and with `3@1`; equality at one period apart; `scan`, `iter_value`, and `values`; and the Sieve parsed from its Display.
+pub fn check_invariants(sieve: &Sieve, range: Range<i128>) -> Vec<Violation> {
+    let brute = |s: &Sieve, v: i128| s.root.contains(v);
+    let t = sieve.translate(1);
+    let u = Sieve::unit(3, 1);
+    let mut warm = sieve.clone();
+    warm.warm();
+    let complement = !sieve;
+    let mut post = Vec::new();
+    post.extend(check("contains", sieve, &range, |v| {
+        sieve.contains(v) == brute(sieve, v)
+    }));
+    post.extend(check("warm contains", sieve, &range, |v| {
+        warm.contains(v) == brute(sieve, v)
+    }));
+    post.extend(check("complement", sieve, &range, |v| {
+        brute(&complement, v) != brute(sieve, v)
+    }));
+    for (law, a, b) in [
+        ("De Morgan intersection", !(sieve & &t), !sieve | !&t),
+        ("De Morgan union", !(sieve | &t), !sieve & !&t),
+        (
+            "distributivity",
+            sieve & (&t | &u),
+            (sieve & &t) | (sieve & &u),
+        ),
+    ] {
+        post.extend(check(law, sieve, &range, |v| brute(&a, v) == brute(&b, v)));
+    }
+    if let Some(period) = sieve.period() {
+        post.extend(check("period", sieve, &range, |v| {
+            v.checked_add(period as i128)
+                .map_or(true, |w| brute(sieve, v) == brute(sieve, w))
+        }));
+    }
+    let members: Vec<i128> = range.clone().filter(|v| brute(sieve, *v)).collect();
+    for (law, found) in [
+        ("scan", sieve.scan(range.clone())),
+        ("iter_value", sieve.iter_value(range.clone()).collect()),
+        ("values", sieve.values(range.clone()).collect()),
+    ] {
+        if found != members {
+            post.push(Violation {
+                law,
+                expression: sieve.root.to_string(),
+                value: None,
+            });
+        }
+    }
+    match Sieve::try_new(&sieve.to_string()) {
+        Ok(parsed) => post.extend(check("Display round-trip", sieve, &range, |v| {
+            brute(&parsed, v) == brute(sieve, v)
+        })),
+        Err(_) => post.push(Violation {
+            law: "Display round-trip",
+            expression: sieve.root.to_string(),
+            value: None,
+        }),
+    }
+    post
+}
+
+/// Return the reference expressions of `VECTORS` whose members differ from the known values, or that do not parse; an empty result means every vector matches.
+pub fn check_vectors() -> Vec<Violation> {
+    VECTORS
+        .iter()
+        .filter(|(expr, range, members)| match Sieve::try_new(expr) {
+            Ok(s) => s.scan(range.clone()) != *members,
+            Err(_) => true,
+        })
+        .map(|(expr, _, _)| Violation {
+            law: "vector",
+            expression: expr.to_string(),
+            value: None,
+        })
+        .collect()
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_invariants_a() {
+        for e in [
+            "(5@0|4@2)&!(30@10)^7@3",
+            "3@0-4@0",
+            "0@0",
+            "1@0",
+            "!(6@0&4@1)",
+        ] {
+            let s = Sieve::new(e);
+            assert_eq!(check_invariants(&s, -120..120), vec![], "{e}");
+        }
+        assert_eq!(check_vectors(), vec![]);
+    }
+
+    #[test]
+    fn test_check_a() {
+        let s = Sieve::new("3@0");
+        let v = check("odd", &s, &(0..10), |v| v % 2 == 0).unwrap();
+        assert_eq!(v.value, Some(1));
+        assert_eq!(v.to_string(), "odd does not hold for 3@0 at 1");
+        assert_eq!(check("always", &s, &(0..10), |_| true), None);
+    }
+}
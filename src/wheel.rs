@@ -0,0 +1,116 @@
+use std::ops::Range;
+
+use crate::util;
+use crate::Sieve;
+
+/// A factorization wheel: the residues, within the circumference (the least common multiple of a basis of moduli), that are coprime to every modulus of the basis. Members are iterated by stepping from spoke to spoke, without testing the values between them.
+///
+/// ```
+/// let w = xensieve::Wheel::new(&[2, 3, 5]);
+/// assert_eq!(w.circumference(), 30);
+/// assert_eq!(w.spokes(), &[1, 7, 11, 13, 17, 19, 23, 29]);
+/// assert_eq!(w.iter_value(25..50).collect::<Vec<_>>(), vec![29, 31, 37, 41, 43, 47, 49]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Wheel {
+    circumference: u64,
+    spokes: Vec<u64>,
+}
+
+impl Wheel {
+    /// Construct a Wheel from a basis of moduli, typically the first primes.
+    ///
+    /// # Panics
+    /// Panics if a modulus is zero, or if the circumference overflows.
+    pub fn new(basis: &[u64]) -> Self {
+        assert!(
+            basis.iter().all(|m| *m > 0),
+            "moduli must be greater than zero"
+        );
+        let circumference = basis
+            .iter()
+            .try_fold(1, |c, m| util::lcm(c, *m))
+            .expect("circumference overflows");
+        let spokes = (0..circumference)
+            .filter(|r| {
+                basis
+                    .iter()
+                    .all(|m| util::gcd(*r, *m, 0) == Ok(1) || *m == 1)
+            })
+            .collect();
+        Self {
+            circumference,
+            spokes,
+        }
+    }
+
+    /// Return the circumference, the period of the Wheel.
+    pub fn circumference(&self) -> u64 {
+        self.circumference
+    }
+
+    /// Return the spokes, the residues within the circumference, ascending.
+    pub fn spokes(&self) -> &[u64] {
+        &self.spokes
+    }
+
+    /// Iterate the members of the Wheel within the range.
+    pub fn iter_value(&self, range: Range<i128>) -> impl Iterator<Item = i128> + '_ {
+        let c = self.circumference as i128;
+        let mut base = range.start.div_euclid(c) * c;
+        let offset = (range.start - base) as u64;
+        let mut index = self.spokes.partition_point(|s| *s < offset);
+        let end = range.end;
+        std::iter::from_fn(move || {
+            if self.spokes.is_empty() {
+                return None;
+            }
+            if index == self.spokes.len() {
+                index = 0;
+                base += c;
+            }
+            let v = base + self.spokes[index] as i128;
+            index += 1;
+            if v < end {
+                Some(v)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Return a Sieve of the Wheel, as a union of one Residual of the circumference for each spoke.
+    pub fn to_sieve(&self) -> Sieve {
+        Sieve::from_residuals(self.spokes.iter().map(|s| (self.circumference, *s)))
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wheel_new_a() {
+        assert_eq!(Wheel::new(&[2]).spokes(), &[1]);
+        assert_eq!(Wheel::new(&[2, 3]).spokes(), &[1, 5]);
+        assert_eq!(Wheel::new(&[2, 4]).spokes(), &[1, 3]);
+        assert_eq!(Wheel::new(&[]).spokes(), &[0]);
+        assert_eq!(Wheel::new(&[1]).spokes(), &[0]);
+        assert_eq!(Wheel::new(&[2, 3, 5, 7]).spokes().len(), 48);
+    }
+
+    #[test]
+    fn test_wheel_iter_value_a() {
+        let w = Wheel::new(&[2, 3, 5]);
+        let s = w.to_sieve();
+        for (a, b) in [(-61, 61), (0, 0), (29, 30), (30, 31), (-1, 1)] {
+            assert_eq!(
+                w.iter_value(a..b).collect::<Vec<_>>(),
+                s.iter_value(a..b).collect::<Vec<_>>(),
+                "{a}..{b}"
+            );
+        }
+    }
+}
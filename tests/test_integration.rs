@@ -1,4 +1,6 @@
+use xensieve::Residual;
 use xensieve::Sieve;
+use xensieve::SieveError;
 
 //------------------------------------------------------------------------------
 #[test]
@@ -54,7 +56,7 @@ fn test_sieve_new_c() {
     assert_eq!(s3.contains(30), true);
 
     let s4 = (Sieve::new("5@0") | Sieve::new("4@2")) & !Sieve::new("30@10");
-    assert_eq!(s4.to_string(), "Sieve{5@0|4@2&!(30@10)}");
+    assert_eq!(s4.to_string(), "Sieve{(5@0|4@2)&!(30@10)}");
     assert_eq!(
         s3.iter_value(0..100).collect::<Vec<_>>(),
         s4.iter_value(0..100).collect::<Vec<_>>()
@@ -204,3 +206,391 @@ fn test_sieve_iter_interval_b() {
     let post1: Vec<_> = s1.iter_interval(-20..30).collect();
     assert_eq!(post1, vec![5, 2, 3, 4, 1, 5, 1, 4, 3, 2, 5, 5, 2, 3, 4]);
 }
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_sieve_frequencies_a() {
+    let s1 = Sieve::new("12@0");
+    let post1: Vec<_> = s1.frequencies(-12..=24, 440.0, 12).collect();
+    assert_eq!(post1, vec![220.0, 440.0, 880.0, 1760.0]);
+}
+
+#[test]
+fn test_sieve_frequencies_b() {
+    let s1 = Sieve::new("19@0|19@11");
+    let post1: Vec<_> = s1.frequencies(0..20, 100.0, 19).collect();
+    assert_eq!(post1.len(), 3);
+    assert!((post1[1] - 100.0 * 2f64.powf(11.0 / 19.0)).abs() < 1e-9);
+    assert!((post1[2] - 200.0).abs() < 1e-9);
+}
+
+#[test]
+#[should_panic(expected = "steps per octave must be greater than zero")]
+fn test_sieve_frequencies_c() {
+    let s1 = Sieve::new("12@0");
+    let _ = s1.frequencies(0..12, 440.0, 0);
+}
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_sieve_nest_a() {
+    let s1 = Sieve::new("4@0|4@1").nest(4, 1);
+    assert_eq!(s1.to_string(), "Sieve{4@0|4@1}");
+}
+
+#[test]
+fn test_sieve_nest_b() {
+    let s1 = Sieve::new("4@0|4@1").nest(4, 2);
+    let post1: Vec<_> = s1.iter_value(0..32).collect();
+    assert_eq!(post1, vec![0, 1, 4, 5, 16, 17, 20, 21]);
+}
+
+#[test]
+fn test_sieve_nest_c() {
+    // the higher part is tested against the Sieve as a whole
+    let s1 = Sieve::new("5@0|5@3").nest(2, 2);
+    let post1: Vec<_> = s1.iter_value(0..24).collect();
+    assert_eq!(post1, vec![0, 6, 10, 16, 20]);
+}
+
+#[test]
+fn test_sieve_nest_d() {
+    // 1023 Residuals of the digit levels and 2048 of the highest level
+    let s1 = Sieve::new("3@0|3@2").nest(2, 11);
+    // members have ten lower binary digits of zero, and a higher part in the Sieve
+    let post1: Vec<_> = s1.iter_value(0..8192).collect();
+    assert_eq!(post1, vec![0, 2048, 3072, 5120, 6144]);
+}
+
+#[test]
+#[should_panic(expected = "nested sieve exceeds 4096 Residuals")]
+fn test_sieve_nest_e() {
+    Sieve::new("3@0|3@2").nest(2, 22);
+}
+
+#[test]
+fn test_sieve_derive_from_intervals_a() {
+    let s1 = Sieve::new("(7@0 | 8@1 | 8@6 ) & !(24@7 | 24@17)");
+    let s2 = s1.derive_from_intervals(0..=22);
+    assert_eq!(s2.to_string(), "Sieve{22@1|22@3|22@5|22@7}");
+}
+
+#[test]
+fn test_sieve_derive_from_intervals_b() {
+    let s1 = Sieve::new("5@0");
+    assert_eq!(s1.derive_from_intervals(0..5).to_string(), "Sieve{0@0}");
+    let s2 = s1.derive_from_intervals(0..=20);
+    assert_eq!(s2.iter_value(0..12).collect::<Vec<_>>(), vec![5]);
+}
+
+#[test]
+fn test_sieve_derive_from_intervals_c() {
+    // derivations can be applied recursively
+    let s1 = Sieve::new("3@0|4@0").derive_from_intervals(0..=24);
+    let s2 = s1.derive_from_intervals(0..=48);
+    assert_eq!(s2.iter_value(0..24).collect::<Vec<_>>(), vec![1, 22]);
+}
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_sieve_try_new_a() {
+    let s1 = Sieve::try_new("!(3@0|5@1|5@4)|9@6").unwrap();
+    assert_eq!(s1.to_string(), "Sieve{!(3@0|5@1|5@4)|9@6}");
+}
+
+#[test]
+fn test_sieve_try_new_b() {
+    assert_eq!(
+        Sieve::try_new("3@0|5@").unwrap_err(),
+        SieveError::InvalidResidual(String::from("5@"))
+    );
+    assert_eq!(
+        Sieve::try_new("3@0@1").unwrap_err(),
+        SieveError::InvalidResidual(String::from("3@0@1"))
+    );
+    assert_eq!(
+        Sieve::try_new("3@0|x").unwrap_err(),
+        SieveError::UnknownToken('x')
+    );
+    assert_eq!(Sieve::try_new("").unwrap_err(), SieveError::Empty);
+}
+
+#[test]
+fn test_sieve_try_new_c() {
+    let e = Sieve::try_new("((3@0)").unwrap_err();
+    assert_eq!(e.to_string(), "unbalanced parentheses");
+}
+
+#[test]
+#[should_panic(expected = "Invalid syntax: missing operand")]
+fn test_sieve_new_d() {
+    Sieve::new("3@0&");
+}
+
+#[test]
+fn test_sieve_from_str_a() {
+    let s1: Sieve = "3@0|5@1".parse().unwrap();
+    assert_eq!(s1.to_string(), "Sieve{3@0|5@1}");
+    assert_eq!(
+        "3@0|5@1&".parse::<Sieve>().unwrap_err(),
+        SieveError::MissingOperand
+    );
+}
+
+#[test]
+fn test_sieve_from_str_b() {
+    fn load(expr: &str) -> Result<Vec<i128>, Box<dyn std::error::Error>> {
+        let s = expr.parse::<Sieve>()?;
+        Ok(s.iter_value(0..10).collect())
+    }
+    assert_eq!(load("3@0").unwrap(), vec![0, 3, 6, 9]);
+    assert_eq!(load("3@0|(").unwrap_err().to_string(), "missing operand");
+}
+
+//------------------------------------------------------------------------------
+// ordering guarantees: iterators follow the order of the input iterator, and duplicates are passed through
+
+#[test]
+fn test_sieve_iter_order_a() {
+    let s1 = Sieve::new("3@0|4@0");
+    let post1: Vec<_> = s1.iter_value(0..100).collect();
+    assert!(post1.windows(2).all(|w| w[0] < w[1]));
+
+    let input = vec![12, 3, 5, 8, 0];
+    let post2: Vec<_> = s1.iter_value(input.into_iter()).collect();
+    assert_eq!(post2, vec![12, 3, 8, 0]);
+}
+
+#[test]
+fn test_sieve_iter_order_b() {
+    let s1 = Sieve::new("3@0|4@0");
+    let input = vec![3, 3, 4, 5, 4, 6];
+    let post1: Vec<_> = s1.iter_value(input.clone().into_iter()).collect();
+    assert_eq!(post1, vec![3, 3, 4, 4, 6]);
+    let post2: Vec<_> = s1.iter_state(input.clone().into_iter()).collect();
+    assert_eq!(post2, vec![true, true, true, false, true, true]);
+    let post3: Vec<_> = s1.iter_interval(input.into_iter()).collect();
+    assert_eq!(post3, vec![0, 1, 0, 2]);
+}
+
+#[test]
+fn test_sieve_iter_order_c() {
+    // repeated iteration over the same input is deterministic
+    let s1 = Sieve::new("(7@0 | 8@1 | 8@6 ) & !(24@7 | 24@17)");
+    let a: Vec<_> = s1.iter_interval(-50..50).collect();
+    let b: Vec<_> = s1.clone().iter_interval(-50..50).collect();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_sieve_iter_order_d() {
+    let s1 = Sieve::new("3@0|4@0");
+    let input = vec![9, 3, 3, 4, 0, 8];
+    let post1: Vec<_> = s1
+        .iter_interval(xensieve::sorted_unique(input.clone()))
+        .collect();
+    assert_eq!(post1, vec![3, 1, 4, 1]);
+    let post2: Vec<_> = s1.iter_value(xensieve::sorted_unique(input)).collect();
+    assert_eq!(post2, vec![0, 3, 4, 8, 9]);
+}
+
+#[test]
+#[should_panic(expected = "input is not strictly ascending")]
+fn test_sieve_iter_order_e() {
+    let s1 = Sieve::new("3@0|4@0");
+    let input = vec![0, 3, 4, 3];
+    s1.iter_interval(xensieve::strictly_ascending(input.into_iter()))
+        .for_each(drop);
+}
+
+#[test]
+fn test_sieve_iter_interval_c() {
+    let s1 = Sieve::new("5@0") | Sieve::new("7@1");
+    let up: Vec<_> = s1.iter_interval(-20..30).collect();
+    let down: Vec<_> = s1.iter_interval((-20..30).rev()).collect();
+    assert_eq!(down, up.iter().rev().map(|i| -i).collect::<Vec<_>>());
+    let down_abs: Vec<_> = s1.iter_interval_abs((-20..30).rev()).collect();
+    assert_eq!(down_abs, up.iter().rev().copied().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_sieve_iter_interval_d() {
+    // a bidirectional walk
+    let s1 = Sieve::new("3@0|4@0");
+    let walk = vec![0, 1, 3, 4, 2, 0, 6, 8, 4];
+    let post1: Vec<_> = s1.iter_interval(walk.clone().into_iter()).collect();
+    assert_eq!(post1, vec![3, 1, -4, 6, 2, -4]);
+    let post2: Vec<_> = s1.iter_interval_abs(walk.into_iter()).collect();
+    assert_eq!(post2, vec![3, 1, 4, 6, 2, 4]);
+}
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_sieve_contains_approx_a() {
+    let s1 = Sieve::new("3@0|5@1");
+    assert!(s1.contains_approx(2.9, 0.1));
+    assert!(!s1.contains_approx(2.8, 0.1));
+    assert!(!s1.contains_approx(-2.0, 0.0));
+    assert!(s1.contains_approx(-3.0, 0.0));
+    assert!(!s1.contains_approx(f64::NAN, 1.0));
+    assert!(!s1.contains_approx(3.0, -1.0));
+}
+
+#[test]
+fn test_sieve_quantize_approx_a() {
+    let s1 = Sieve::new("3@0|5@1");
+    let onsets = [0.1, 1.2, 2.6, 4.4, 5.9, 7.6];
+    let post1: Vec<_> = onsets.iter().map(|x| s1.quantize_approx(*x, 0.5)).collect();
+    assert_eq!(post1, vec![Some(0), Some(1), Some(3), None, Some(6), None]);
+    assert_eq!(s1.quantize_approx(4.5, 1.5), Some(3));
+    assert_eq!(s1.quantize_approx(5.0, 1.5), Some(6));
+}
+
+#[test]
+fn test_sieve_quantize_approx_b() {
+    // a large tolerance is not searched value by value
+    let s1 = Sieve::new("1000003@7");
+    assert_eq!(s1.quantize_approx(0.0, 1e30), Some(7));
+    assert_eq!(s1.quantize_approx(-1e6, 1e30), Some(-999996));
+    assert_eq!(s1.quantize_approx(0.0, f64::INFINITY), None);
+    assert_eq!(s1.quantize_approx(0.0, f64::NAN), None);
+    assert_eq!(s1.quantize_approx(1e300, 1.0), None);
+    assert_eq!(Sieve::empty().quantize_approx(0.0, 1e30), None);
+}
+
+#[test]
+fn test_sieve_iter_value_element_a() {
+    let s1 = Sieve::new("5@0|7@2");
+    let v1: Vec<i32> = s1.iter_value(0i32..20).collect();
+    assert_eq!(v1, vec![0, 2, 5, 9, 10, 15, 16]);
+    let v2: Vec<u64> = s1.iter_value(0u64..20).collect();
+    assert_eq!(v2, vec![0, 2, 5, 9, 10, 15, 16]);
+}
+
+#[test]
+fn test_sieve_pyramid_a() {
+    let s1 = Sieve::new("(5@0|4@2)&!30@10");
+    let p = s1.pyramid(0..100);
+    for a in 0..100 {
+        for b in a..100 {
+            assert_eq!(p.any_in(a..b), s1.iter_value(a..b).next().is_some());
+        }
+    }
+}
+
+#[test]
+fn test_residual_a() {
+    // enumerate the residual classes of modulus 6 that contain 8
+    let classes: Vec<String> = (0..6)
+        .map(|s| Residual::new(6, s))
+        .filter(|r| r.contains(8))
+        .map(|r| r.to_string())
+        .collect();
+    assert_eq!(classes, vec!["6@2"]);
+
+    let s1 = (0..3)
+        .map(|s| Sieve::from(Residual::new(9, s * 3)))
+        .reduce(|a, b| a | b)
+        .unwrap();
+    assert_eq!(s1.iter_value(0..10).collect::<Vec<_>>(), vec![0, 3, 6, 9]);
+}
+
+#[test]
+fn test_sieve_first_in_a() {
+    let s1 = Sieve::new("(5@0|4@2)&!30@10");
+    assert_eq!(s1.first_in(7..20), Some(14));
+    assert!(!s1.any_in(7..14));
+    assert_eq!(s1.first_in(1_000_000_007..i128::MAX), Some(1_000_000_010));
+}
+
+#[test]
+fn test_sieve_iter_duration_a() {
+    use xensieve::NoteValue;
+    // eighth-note triplets against quarter notes, three steps per beat
+    let s1 = Sieve::new("3@0|1@0&!(3@0)&6@1");
+    let d: Vec<_> = s1.iter_duration(0..=6, 3).collect();
+    assert_eq!(d.len(), 3);
+    assert_eq!(d[0].interval, 1);
+    assert_eq!(
+        d[0].values,
+        vec![NoteValue {
+            base: 8,
+            dots: 0,
+            tuplet: Some((3, 2))
+        }]
+    );
+    assert_eq!(
+        d[1].values,
+        vec![NoteValue {
+            base: 4,
+            dots: 0,
+            tuplet: Some((3, 2))
+        }]
+    );
+    assert_eq!(
+        d[2].values,
+        vec![NoteValue {
+            base: 4,
+            dots: 0,
+            tuplet: None
+        }]
+    );
+}
+
+#[test]
+fn test_sieve_infer_meter_a() {
+    // a 6/8 pattern: dotted-quarter beats of three eighths, one step per eighth
+    let s1 = Sieve::new("3@0|6@1");
+    let m = s1.infer_meter();
+    assert_eq!(m[0].downbeat, 0);
+    assert_eq!(m[0].levels, vec![3, 6]);
+    assert_eq!(m[0].time_signature(2), Some((6, 8)));
+    // a 3/4 pattern: quarter-note beats of two eighths
+    let s2 = Sieve::new("2@0|6@1");
+    let m = s2.infer_meter();
+    assert_eq!(m[0].levels, vec![2, 6]);
+    assert_eq!(m[0].time_signature(2), Some((3, 4)));
+}
+
+#[test]
+fn test_interleave_a() {
+    let a = Sieve::new("3@0");
+    let b = Sieve::new("4@0");
+    let v = xensieve::interleave(&a, &b, 0..25, xensieve::Conflict::Alternate);
+    assert_eq!(v.conflicts, vec![0, 12, 24]);
+    assert_eq!(v.first, vec![0, 3, 6, 9, 15, 18, 21, 24]);
+    assert_eq!(v.second, vec![4, 8, 12, 16, 20]);
+}
+
+#[test]
+fn test_arpeggiator_a() {
+    let pitch = Sieve::new("12@0|12@3|12@7");
+    let a = xensieve::Arpeggiator::new(&pitch, 48..72, xensieve::Pattern::Down);
+    let n = a.notes(&Sieve::new("4@0|4@3"), 0..12);
+    let onsets: Vec<_> = n.iter().map(|n| n.onset).collect();
+    let pitches: Vec<_> = n.iter().map(|n| n.pitch).collect();
+    assert_eq!(onsets, vec![0, 3, 4, 7, 8, 11]);
+    assert_eq!(pitches, vec![67, 63, 60, 55, 51, 48]);
+}
+
+#[test]
+fn test_sieve_try_new_literal_a() {
+    assert_eq!(
+        Sieve::new("1_000_003@17 | 3@0").to_string(),
+        "Sieve{1000003@17|3@0}"
+    );
+    assert_eq!(
+        Sieve::try_new("10 000@1").unwrap_err(),
+        SieveError::DigitSeparator(' ')
+    );
+    assert_eq!(
+        Sieve::try_new("2@1|20000000000000000000@3")
+            .unwrap_err()
+            .to_string(),
+        "number out of range: 20000000000000000000"
+    );
+}